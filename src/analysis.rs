@@ -0,0 +1,208 @@
+// src/analysis.rs
+//
+// Single entry point that runs the tokenizer, parser, framework detection and
+// type analysis over a Lua source, so library users don't have to wire them up.
+
+use std::fs;
+use std::path::Path;
+
+use crate::diagnostics::{self, Diagnostic, Severity};
+use crate::error::LuaToolsError;
+use crate::parser::code_parser::CodeParser;
+use crate::project_context::ProjectContext;
+use crate::tokenizer::token::{Span, Token};
+use crate::tokenizer::CodeTokenizer;
+use crate::parser::annotation_parser::AnnotationParser;
+use crate::parser::ast::{AnnotationASTNode, CodeASTNode, TypeInfo};
+use crate::type_inference::{TypeAnalyzer, TypedName};
+
+/// Everything produced by analyzing one Lua source
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Analysis {
+    /// Token stream produced by the code tokenizer
+    pub tokens: Vec<Token>,
+    /// Parsed code AST
+    pub ast: Vec<CodeASTNode>,
+    /// Frameworks detected for the source's project (name, version)
+    pub frameworks: Vec<(String, String)>,
+    /// Problems found during analysis
+    pub diagnostics: Vec<Diagnostic>,
+    /// Name occurrences with their resolved types
+    pub names: Vec<TypedName>,
+}
+
+/// What kind of declaration a symbol is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Module,
+    Function,
+    /// A function stored in a table, such as `M.new` or `Class:method`
+    Method,
+    /// A type declared with `---@class`
+    Class,
+}
+
+/// A top-level declaration, as listed in an editor outline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    /// Declared name; methods keep their owner prefix (`M.new`)
+    pub name: String,
+    pub kind: SymbolKind,
+    pub span: Span,
+    /// Methods of a module, nested under it in source order
+    pub children: Vec<Symbol>,
+}
+
+impl Analysis {
+    /// The whole analysis as JSON, for caching or handing to another process
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+    
+    /// Name and type of the identifier covering a 1-based line and column, if any
+    pub fn type_at(&self, line: usize, column: usize) -> Option<(String, TypeInfo)> {
+        self.names
+            .iter()
+            .filter(|n| {
+                n.span.line == line
+                    && n.span.column <= column
+                    && column < n.span.column + (n.span.end - n.span.start)
+            })
+            .min_by_key(|n| n.span.end - n.span.start)
+            .map(|n| (n.name.clone(), n.type_info.clone()))
+    }
+
+    /// Top-level modules, functions, methods and classes, in source order. Methods of a
+    /// module declared in the same source are nested under it rather than listed at the top
+    pub fn symbols(&self) -> Vec<Symbol> {
+        let mut symbols: Vec<Symbol> = Vec::new();
+        for node in &self.ast {
+            match node {
+                CodeASTNode::ModuleDeclaration { name, span, .. } => symbols.push(Symbol {
+                    name: name.clone(),
+                    kind: SymbolKind::Module,
+                    span: span.clone(),
+                    children: Vec::new(),
+                }),
+                CodeASTNode::FunctionDef { name, span, .. } => {
+                    let owner = name.rsplit_once(['.', ':']).map(|(owner, _)| owner);
+                    let kind = if owner.is_some() {
+                        SymbolKind::Method
+                    } else {
+                        SymbolKind::Function
+                    };
+                    let symbol = Symbol {
+                        name: name.clone(),
+                        kind,
+                        span: span.clone(),
+                        children: Vec::new(),
+                    };
+                    let module = symbols.iter_mut().find(|symbol| {
+                        symbol.kind == SymbolKind::Module && Some(symbol.name.as_str()) == owner
+                    });
+                    match module {
+                        Some(module) => module.children.push(symbol),
+                        None => symbols.push(symbol),
+                    }
+                }
+                _ => {}
+            }
+        }
+        
+        // Classes only exist in annotations, which the code AST doesn't keep
+        for (annotation, span) in AnnotationParser::new(&self.tokens).parse_with_spans() {
+            if let AnnotationASTNode::Class { name, .. } = annotation {
+                symbols.push(Symbol {
+                    name,
+                    kind: SymbolKind::Class,
+                    span,
+                    children: Vec::new(),
+                });
+            }
+        }
+        
+        symbols.sort_by_key(|symbol| symbol.span.start);
+        symbols
+    }
+}
+
+/// Analyze a Lua file, detecting its project root, Lua version and frameworks from its location
+pub fn analyze_file(path: &Path) -> Result<Analysis, LuaToolsError> {
+    let content = fs::read_to_string(path)
+        .map_err(|source| LuaToolsError::Io { path: path.to_path_buf(), source })?;
+    
+    let mut context = ProjectContext::new();
+    context.detect_project_root(path);
+    
+    Ok(analyze_with_context(&content, context))
+}
+
+/// Analyze Lua source text with a default project context
+pub fn analyze_str(source: &str) -> Analysis {
+    analyze_with_context(source, ProjectContext::new())
+}
+
+/// Analyze Lua source text against an existing project context
+pub fn analyze_with_context(source: &str, context: ProjectContext) -> Analysis {
+    let tokens = CodeTokenizer::new(source).tokenize();
+    let ast = CodeParser::new(tokens.clone()).parse();
+    
+    let frameworks = context.detected_frameworks.clone();
+    let mut analyzer = TypeAnalyzer::new(context);
+    analyzer.analyze(&ast);
+    
+    let mut diagnostics = analyzer.diagnostics;
+    // Input the tokenizer gave up on, such as an unterminated string
+    for token in &tokens {
+        if let Token::Error(message, span) = token {
+            diagnostics.push(Diagnostic::new(Severity::Error, "syntax-error", message.clone(), span.clone()));
+        }
+    }
+    diagnostics::apply_directives(&mut diagnostics, &tokens);
+    
+    Analysis {
+        tokens,
+        ast,
+        frameworks,
+        diagnostics,
+        names: analyzer.typed_names,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn methods_nest_under_their_module() {
+        let source =
+            "local M = {}\n\nfunction M.new()\nend\n\nfunction helper()\nend\n\nreturn M\n";
+        let symbols = analyze_str(source).symbols();
+        let outline: Vec<(&str, SymbolKind, usize)> = symbols
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.kind, symbol.span.line))
+            .collect();
+        assert_eq!(
+            outline,
+            [("M", SymbolKind::Module, 1), ("helper", SymbolKind::Function, 6)]
+        );
+        let methods = &symbols[0].children;
+        assert_eq!(methods.len(), 1);
+        assert_eq!(methods[0].name, "M.new");
+        assert_eq!(methods[0].kind, SymbolKind::Method);
+        assert_eq!(methods[0].span.line, 3);
+        assert_eq!(&source[methods[0].span.start..methods[0].span.end], "function M.new()\nend");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn ast_round_trips_through_json() {
+        let source = "local M = {}\n\nfunction M.add(a, b)\n    return a + b\nend\n\nreturn M\n";
+        let analysis = analyze_str(source);
+        assert!(analysis.to_json().is_ok());
+        let json = serde_json::to_string(&analysis.ast).unwrap();
+        let ast: Vec<CodeASTNode> = serde_json::from_str(&json).unwrap();
+        assert_eq!(ast, analysis.ast);
+    }
+}
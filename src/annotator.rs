@@ -1,10 +1,103 @@
 // src/annotator.rs
 
-use crate::parser::ast::{CodeASTNode, ExportItem, TypeInfo};
+use crate::parser::ast::{AnnotationASTNode, CodeASTNode, ExportItem, Expression, TypeInfo};
+use crate::parser::pretty_print;
+use crate::parser::visitor::{self, Visitor};
+
+/// What the annotator writes in place of a type it could not infer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum UnknownType {
+    /// `any`, which LuaLS accepts; parameters also get a TODO description
+    #[default]
+    Any,
+    /// A `<TODO>` marker, left for the author to replace
+    Todo,
+    /// Custom text, written as is
+    Custom(String),
+}
+
+impl UnknownType {
+    /// `any`, `todo`, or any other text as custom text.
+    pub fn parse(text: &str) -> Self {
+        match text {
+            "any" => UnknownType::Any,
+            "todo" => UnknownType::Todo,
+            _ => UnknownType::Custom(text.to_string()),
+        }
+    }
+}
+
+/// The kinds of lines in a generated annotation block, for ordering them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationKind {
+    /// Description comments
+    Description,
+    Generic,
+    Param,
+    Return,
+    Overload,
+    /// Every other tag, such as `@function`, `@async` or `@deprecated`
+    Other,
+}
+
+impl AnnotationKind {
+    /// The LuaLS convention: description, `@generic`, `@param`, `@return`, `@overload`, then
+    /// the rest.
+    pub const CANONICAL_ORDER: [AnnotationKind; 6] = [
+        AnnotationKind::Description,
+        AnnotationKind::Generic,
+        AnnotationKind::Param,
+        AnnotationKind::Return,
+        AnnotationKind::Overload,
+        AnnotationKind::Other,
+    ];
+
+    /// Parse a kind name: `description`, `generic`, `param`, `return`, `overload` or `other`.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text {
+            "description" => Some(AnnotationKind::Description),
+            "generic" => Some(AnnotationKind::Generic),
+            "param" => Some(AnnotationKind::Param),
+            "return" => Some(AnnotationKind::Return),
+            "overload" => Some(AnnotationKind::Overload),
+            "other" => Some(AnnotationKind::Other),
+            _ => None,
+        }
+    }
+
+    /// The kind of an annotation block line.
+    fn of_line(line: &str) -> Self {
+        let Some(tag) = line.strip_prefix("---@") else {
+            return AnnotationKind::Description;
+        };
+        match tag.split_whitespace().next().unwrap_or("") {
+            "generic" => AnnotationKind::Generic,
+            "param" => AnnotationKind::Param,
+            "return" => AnnotationKind::Return,
+            "overload" => AnnotationKind::Overload,
+            _ => AnnotationKind::Other,
+        }
+    }
+}
 
 pub struct Annotator {
     current_module: String,
     pub preserve_existing: bool,
+    /// Pad the names and types of each block of `---@param`/`---@field` lines into columns.
+    pub align: bool,
+    /// What to write for types that could not be inferred.
+    pub unknown_type: UnknownType,
+    /// Mark functions that return a known type with `---@nodiscard` (off by default).
+    pub nodiscard: bool,
+    /// The order of the lines of a function's annotation block. Kinds left out come last, in
+    /// canonical order.
+    pub order: Vec<AnnotationKind>,
+}
+
+impl Default for Annotator {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Annotator {
@@ -12,27 +105,46 @@ impl Annotator {
         Self {
             current_module: String::new(),
             preserve_existing: true,
+            align: false,
+            unknown_type: UnknownType::default(),
+            nodiscard: false,
+            order: AnnotationKind::CANONICAL_ORDER.to_vec(),
         }
     }
 
     pub fn generate_docs(&mut self, ast: &[CodeASTNode]) -> String {
         let mut output = String::new();
+        let module = returned_name(ast);
 
         for node in ast {
             match node {
-                CodeASTNode::ModuleDeclaration { name, exports, .. } => {
-                    self.current_module = name.clone();
-                    output.push_str(&self.format_module_header(name, exports));
+                CodeASTNode::ModuleDeclaration {
+                    name,
+                    exports,
+                    items,
+                    annotations,
+                    ..
+                } => {
+                    // Only the table the file returns is the module; other tables get a type.
+                    if module == Some(name.as_str()) {
+                        self.current_module = name.clone();
+                        output.push_str(&self.format_module_header(name, exports));
+                    } else {
+                        let type_info = TypeInfo::table_of(exports, items);
+                        output.push_str(&format!("---@type {}\n", self.type_text(&type_info)));
+                    }
+                    output.push_str(&self.format_preserved(annotations));
                 }
                 CodeASTNode::FunctionDef {
                     name,
                     params,
                     return_types,
                     doc,
-                    annotations,
                     body,
+                    annotations,
+                    ..
                 } => {
-                    let full_name = if self.current_module.is_empty() || name.contains('.') {
+                    let full_name = if self.current_module.is_empty() || name.contains(['.', ':']) {
                         name.clone()
                     } else {
                         format!("{}.{}", self.current_module, name)
@@ -41,12 +153,14 @@ impl Annotator {
                         .as_ref()
                         .map(|s| vec![s.clone()])
                         .unwrap_or_else(Vec::new);
-                    output.push_str(&self.format_function(
+                    let block = self.format_function(
                         &full_name,
                         params,
                         return_types,
                         &docs_vec,
-                    ));
+                        yields(body),
+                    ) + &self.format_preserved(annotations);
+                    output.push_str(&self.reorder(&block));
                 }
                 CodeASTNode::Comment(text) => {
                     if text.contains('\n') {
@@ -64,17 +178,19 @@ impl Annotator {
     }
 
     fn format_module_header(&self, name: &str, exports: &[ExportItem]) -> String {
-        let mut output = format!("---@module {}\n", name);
-        if !exports.is_empty() {
-            output.push_str("---Exports:\n");
-            for export in exports {
-                output.push_str(&format!(
-                    "---@field {} {}\n",
-                    export.name,
-                    self.type_to_string(&export.type_info)
-                ));
-            }
-        }
+        // The class gives LuaLS a type for the table the module returns.
+        let mut output = format!("---@module {}\n---@class {}\n", name, name);
+        let rows: Vec<_> = exports
+            .iter()
+            .map(|export| {
+                (
+                    export.name.clone(),
+                    self.type_text(&export.type_info),
+                    String::new(),
+                )
+            })
+            .collect();
+        output.push_str(&self.format_tag_lines("field", &rows));
         output
     }
 
@@ -84,36 +200,55 @@ impl Annotator {
         params: &[(String, TypeInfo)],
         returns: &[TypeInfo],
         existing_docs: &[String],
+        is_async: bool,
     ) -> String {
         let mut output = String::new();
 
         if existing_docs.is_empty() {
             output.push_str("-- TODO: Describe the function\n");
         } else {
-            for doc in existing_docs {
-                output.push_str(&format!("{}\n", doc));
+            // Docs are comment text after the leading `--`.
+            for line in existing_docs.iter().flat_map(|doc| doc.lines()) {
+                output.push_str(&format!("--{}\n", line));
             }
         }
 
+        if is_async {
+            output.push_str("---@async\n");
+        }
+        if self.nodiscard
+            && returns
+                .iter()
+                .any(|type_info| *type_info != TypeInfo::Unknown)
+        {
+            output.push_str("---@nodiscard\n");
+        }
         output.push_str(&format!("---@function {}\n", name));
 
-        for (param, type_info) in params {
-            let type_str = self.type_to_string(type_info);
-            let placeholder = if type_str == "any" {
-                " @TODO: Specify type and describe"
-            } else {
-                ""
-            };
-            output.push_str(&format!(
-                "---@param {} {}{}\n",
-                param, type_str, placeholder
-            ));
-        }
+        // `self` is typed by the method's class and is not documented.
+        let rows: Vec<_> = params
+            .iter()
+            .filter(|(param, _)| param != "self")
+            .map(|(param, type_info)| {
+                let placeholder =
+                    if self.unknown_type == UnknownType::Any && *type_info == TypeInfo::Unknown {
+                        "@TODO: Specify type and describe"
+                    } else {
+                        ""
+                    };
+                (
+                    param.clone(),
+                    self.type_text(type_info),
+                    placeholder.to_string(),
+                )
+            })
+            .collect();
+        output.push_str(&self.format_tag_lines("param", &rows));
 
         if !returns.is_empty() {
             let return_types = returns
                 .iter()
-                .map(|t| self.type_to_string(t))
+                .map(|type_info| self.type_text(type_info))
                 .collect::<Vec<_>>()
                 .join(", ");
             output.push_str(&format!("---@return {}\n", return_types));
@@ -122,6 +257,80 @@ impl Annotator {
         output
     }
 
+    /// With `preserve_existing`, the source's annotations that the annotator does not
+    /// generate itself (`@deprecated`, `@see`, `@generic` and `@overload`), one per line.
+    fn format_preserved(&self, annotations: &[AnnotationASTNode]) -> String {
+        if !self.preserve_existing {
+            return String::new();
+        }
+        annotations
+            .iter()
+            .filter(|annotation| {
+                matches!(
+                    annotation,
+                    AnnotationASTNode::Deprecated
+                        | AnnotationASTNode::See { .. }
+                        | AnnotationASTNode::Generic { .. }
+                        | AnnotationASTNode::Overload { .. }
+                )
+            })
+            .map(|annotation| pretty_print::emit_annotation(annotation) + "\n")
+            .collect()
+    }
+
+    /// Sort the lines of an annotation block by `order`, keeping lines of the same kind in
+    /// the order they were written.
+    fn reorder(&self, block: &str) -> String {
+        let rank = |kind: AnnotationKind| {
+            self.order.iter().position(|k| *k == kind).unwrap_or_else(|| {
+                let canonical = AnnotationKind::CANONICAL_ORDER.iter();
+                self.order.len() + canonical.take_while(|k| **k != kind).count()
+            })
+        };
+        let mut lines: Vec<&str> = block.lines().collect();
+        lines.sort_by_key(|line| rank(AnnotationKind::of_line(line)));
+        lines.iter().map(|line| format!("{}\n", line)).collect()
+    }
+
+    /// The annotation text for a type, using the configured placeholder if it is unknown.
+    fn type_text(&self, type_info: &TypeInfo) -> String {
+        match (type_info, &self.unknown_type) {
+            (TypeInfo::Unknown, UnknownType::Todo) => "<TODO>".to_string(),
+            (TypeInfo::Unknown, UnknownType::Custom(text)) => text.clone(),
+            _ => type_info.to_string(),
+        }
+    }
+
+    /// Format `---@<tag> name type description` lines, one per row. With `align`, names and
+    /// types are padded to the widest of the block.
+    fn format_tag_lines(&self, tag: &str, rows: &[(String, String, String)]) -> String {
+        let (name_width, type_width) = if self.align {
+            (
+                rows.iter()
+                    .map(|(name, _, _)| name.len())
+                    .max()
+                    .unwrap_or(0),
+                rows.iter().map(|(_, ty, _)| ty.len()).max().unwrap_or(0),
+            )
+        } else {
+            (0, 0)
+        };
+        let mut output = String::new();
+        for (name, type_str, description) in rows {
+            let line = if description.is_empty() {
+                format!("---@{} {:<name_width$} {}", tag, name, type_str)
+            } else {
+                format!(
+                    "---@{} {:<name_width$} {:<type_width$} {}",
+                    tag, name, type_str, description
+                )
+            };
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+        output
+    }
+
     fn format_line_comment(&self, text: &str) -> String {
         if text.starts_with('-') {
             format!("--{}", text)
@@ -133,16 +342,194 @@ impl Annotator {
     fn format_block_comment(&self, text: &str) -> String {
         format!("--[[\n{}\n--]]", text)
     }
+}
 
-    fn type_to_string(&self, type_info: &TypeInfo) -> String {
-        match type_info {
-            TypeInfo::String => "string".to_string(),
-            TypeInfo::Number => "number".to_string(),
-            TypeInfo::Boolean => "boolean".to_string(),
-            TypeInfo::Table => "table".to_string(),
-            TypeInfo::Function => "function".to_string(),
-            TypeInfo::Unknown => "any".to_string(),
-            _ => "any".to_string(),
+/// The name of the local a file returns at its top level (`return M`), if any.
+fn returned_name(ast: &[CodeASTNode]) -> Option<&str> {
+    ast.iter().rev().find_map(|node| match node {
+        CodeASTNode::ReturnStatement(exprs) => match exprs.as_slice() {
+            [Expression::Identifier(name, _)] => Some(name.as_str()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Whether a function body calls `coroutine.yield`, outside of the functions nested in it
+/// (named or anonymous).
+fn yields(body: &[CodeASTNode]) -> bool {
+    #[derive(Default)]
+    struct YieldFinder {
+        nested_functions: usize,
+        found: bool,
+    }
+
+    impl Visitor for YieldFinder {
+        fn visit_function_def(&mut self, _node: &CodeASTNode) {
+            self.nested_functions += 1;
+        }
+
+        fn leave_node(&mut self, node: &CodeASTNode) {
+            if matches!(node, CodeASTNode::FunctionDef { .. }) {
+                self.nested_functions -= 1;
+            }
         }
+
+        fn visit_expression(&mut self, expr: &Expression) {
+            match expr {
+                Expression::FunctionCall { callee, .. } => {
+                    self.found |= self.nested_functions == 0 && callee == "coroutine.yield";
+                }
+                Expression::Function { .. } => self.nested_functions += 1,
+                _ => {}
+            }
+        }
+
+        fn leave_expression(&mut self, expr: &Expression) {
+            if matches!(expr, Expression::Function { .. }) {
+                self.nested_functions -= 1;
+            }
+        }
+    }
+
+    let mut finder = YieldFinder::default();
+    visitor::walk(body, &mut finder);
+    finder.found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::code_parser::CodeParser;
+    use crate::tokenizer::CodeTokenizer;
+
+    fn docs(source: &str) -> String {
+        docs_with(Annotator::new(), source)
+    }
+
+    fn docs_with(mut annotator: Annotator, source: &str) -> String {
+        let ast = CodeParser::new(CodeTokenizer::new(source).tokenize()).parse();
+        annotator.generate_docs(&ast)
+    }
+
+    #[test]
+    fn returned_table_is_a_class() {
+        let output = docs("local M = { x = 1, y = \"s\" }\nreturn M\n");
+        assert!(output.contains("---@class M\n---@field x number\n---@field y string\n"));
+    }
+
+    #[test]
+    fn other_tables_get_a_type() {
+        let source = "local list = { 1, 2, 3 }\nlocal counts = { a = 1 }\nlocal M = {}\nreturn M\n";
+        let output = docs(source);
+        assert!(output.contains("---@type number[]\n"));
+        assert!(output.contains("---@type table<string, number>\n"));
+        assert_eq!(output.matches("---@class").count(), 1);
+    }
+
+    #[test]
+    fn align_pads_parameter_names() {
+        let source = "local function f(id, description)\n    return id\nend\nf(1, 2)";
+        let mut annotator = Annotator::new();
+        annotator.align = true;
+        let output = docs_with(annotator, source);
+        assert!(output.contains(
+            "---@param id          any @TODO: Specify type and describe\n\
+             ---@param description any @TODO: Specify type and describe\n"
+        ));
+        assert!(docs(source).contains("---@param id any @TODO: Specify type and describe\n"));
+    }
+
+    #[test]
+    fn unknown_type_placeholders() {
+        let source = "local function f(x)\nend\nf(1)";
+        let param_line = |unknown_type: UnknownType| {
+            let mut annotator = Annotator::new();
+            annotator.unknown_type = unknown_type;
+            let output = docs_with(annotator, source);
+            output.lines().find(|line| line.starts_with("---@param")).unwrap().to_string()
+        };
+        assert_eq!(
+            param_line(UnknownType::Any),
+            "---@param x any @TODO: Specify type and describe"
+        );
+        assert_eq!(param_line(UnknownType::Todo), "---@param x <TODO>");
+        assert_eq!(param_line(UnknownType::parse("unknown")), "---@param x unknown");
+    }
+
+    #[test]
+    fn async_marks_only_yielding_functions() {
+        let source = "local function step()\n    coroutine.yield(1)\nend\n\
+                      local function wrap()\n    return \
+                      coroutine.wrap(function() coroutine.yield(2) end)\nend\n";
+        let output = docs(source);
+        assert!(output.contains("---@async\n---@function step\n"), "{}", output);
+        assert!(!output.contains("---@async\n---@function wrap\n"), "{}", output);
+        assert_eq!(output.matches("---@async").count(), 1);
+    }
+
+    #[test]
+    fn nodiscard_is_opt_in() {
+        use crate::project_context::ProjectContext;
+        use crate::type_inference::TypeAnalyzer;
+
+        let source = "local function is_ready()\n    return true\nend\n\
+                      local function log()\n    print(1)\nend\n";
+        // The parser leaves return types to inference
+        let mut ast = CodeParser::new(CodeTokenizer::new(source).tokenize()).parse();
+        let analyzer = TypeAnalyzer::new(ProjectContext::new());
+        for node in &mut ast {
+            if let CodeASTNode::FunctionDef { body, return_types, .. } = node {
+                *return_types = analyzer.infer_return_types(body);
+            }
+        }
+
+        let mut annotator = Annotator::new();
+        assert!(!annotator.generate_docs(&ast).contains("---@nodiscard"));
+        annotator.nodiscard = true;
+        let output = annotator.generate_docs(&ast);
+        assert!(output.contains("---@nodiscard\n---@function is_ready\n"), "{}", output);
+        assert!(output.contains("---@return boolean\n"), "{}", output);
+        assert_eq!(output.matches("---@nodiscard").count(), 1);
+    }
+
+    #[test]
+    fn deprecated_survives_a_round_trip() {
+        let source = "---@deprecated\n---@see new_api\nlocal function old_api()\nend\nold_api()";
+        let output = docs(source);
+        assert!(output.contains("---@deprecated\n"), "{}", output);
+        assert!(output.contains("---@see new_api\n"), "{}", output);
+
+        // Annotating the annotated output keeps the tag once
+        let again = docs(&format!("{}{}", output, "local function old_api()\nend\nold_api()"));
+        assert_eq!(again.matches("---@deprecated").count(), 1, "{}", again);
+
+        let mut annotator = Annotator::new();
+        annotator.preserve_existing = false;
+        assert!(!docs_with(annotator, source).contains("---@deprecated"));
+    }
+
+    #[test]
+    fn reorder_puts_annotations_in_canonical_order() {
+        let source = "---@overload fun(): number\n---@generic T\n\
+                      local function pick(x)\n    return x\nend\npick(1)";
+        // The first word of each line: `--` for the description, then the tags
+        let tags = |output: String| -> Vec<String> {
+            output
+                .lines()
+                .filter_map(|line| line.split_whitespace().next().map(str::to_string))
+                .collect()
+        };
+        assert_eq!(
+            tags(docs(source)),
+            ["--", "---@generic", "---@param", "---@overload", "---@function"]
+        );
+
+        let mut annotator = Annotator::new();
+        annotator.order = vec![AnnotationKind::Overload, AnnotationKind::Param];
+        assert_eq!(
+            tags(docs_with(annotator, source)),
+            ["---@overload", "---@param", "--", "---@generic", "---@function"]
+        );
     }
 }
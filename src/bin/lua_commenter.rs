@@ -1,11 +1,20 @@
 // src/bin/lua_commenter.rs
 
+use clap::parser::ValueSource;
 use clap::{Arg, ArgAction, Command};
+use lua_tools::config::{Config, OutputMode};
+use lua_tools::log::{self, Verbosity};
 use lua_tools::{annotator, parser, project_context, tokenizer, type_inference};
+use lua_tools::{log_message, log_verbose, LuaToolsError};
+use regex::Regex;
+use std::collections::HashMap;
 use std::env;
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 
 /// Walk upward from the given directory until a ".git" folder is found.
 /// If none is found, return the current working directory.
@@ -33,77 +42,809 @@ fn relative_path<P: AsRef<Path>>(file: P, root: P) -> String {
         .into_owned()
 }
 
-/// Process a single Lua file: tokenize, parse, infer types, and annotate.
-fn process_file(path: &Path, output_pattern: &str, overwrite: bool) -> String {
-    eprintln!("Processing file: {:?}", path);
-    let content = fs::read_to_string(path).expect("Failed to read file");
+/// What the tool generates for each source.
+#[derive(Clone, Copy, PartialEq)]
+enum OutputFormat {
+    /// LuaLS annotations
+    Annotations,
+    /// Markdown API documentation of the declared classes and functions
+    Markdown,
+}
+
+impl OutputFormat {
+    /// Format a header line naming the source.
+    fn header(self, path: &str) -> String {
+        match self {
+            OutputFormat::Annotations => format!("-- {}\n\n", path),
+            OutputFormat::Markdown => format!("# {}\n\n", path),
+        }
+    }
+}
+
+/// Generated text with the number of diagnostics the analysis of its source reported.
+struct Annotated {
+    text: String,
+    diagnostics: usize,
+}
 
+/// Tokenize, parse, infer types, and annotate or document Lua source.
+fn annotate_source(content: &str, options: &Options) -> Annotated {
     // Tokenize using our updated CodeTokenizer.
-    let mut code_tokenizer = tokenizer::CodeTokenizer::new(&content);
+    let mut code_tokenizer = tokenizer::CodeTokenizer::new(content);
     let tokens = code_tokenizer.tokenize();
-    println!("{}", tokenizer::token::pretty_print_tokens(&tokens));
 
     // Parse tokens into an AST using the code parser.
     let mut code_parser = parser::code_parser::CodeParser::new(tokens);
     let code_ast = code_parser.parse();
-    println!("{}", parser::pretty_print::pretty_print_code_ast(&code_ast));
 
     // Parse tokens into an AST using the annotations parser.
     // let mut annotation_parser = parser::annotation_parser::AnnotationParser::new(tokens);
     // let annotation_ast = annotation_parser.parse();
 
-    // Run type inference on the AST.
-    let proj_ctx = project_context::ProjectContext::new();
+    if options.format == OutputFormat::Markdown {
+        let mut proj_ctx = project_context::ProjectContext::new_with_version(options.config.lua_version());
+        proj_ctx.load_definitions(content);
+        return Annotated {
+            text: proj_ctx.generate_markdown(),
+            diagnostics: 0,
+        };
+    }
+
+    // Run type inference on the AST, starting from the loaded definitions.
+    let mut proj_ctx = project_context::ProjectContext::new_with_version(options.config.lua_version());
+    proj_ctx.type_registry = options.type_registry.clone();
     let mut type_analyzer = type_inference::TypeAnalyzer::new(proj_ctx);
     type_analyzer.analyze(&code_ast);
 
     // Generate annotations from the AST.
-    let mut ann = annotator::Annotator::new();
-    let annotations = ann.generate_docs(&code_ast);
+    let mut ann = options.config.annotator();
+    Annotated {
+        text: ann.generate_docs(&code_ast),
+        diagnostics: type_analyzer.diagnostics.len(),
+    }
+}
 
-    // Prepend the relative file path as a header.
+/// Settings from the command line that apply to every processed file.
+struct Options {
+    output_pattern: String,
+    overwrite: bool,
+    recursive: bool,
+    /// Only report files whose annotated output differs from their contents
+    check: bool,
+    /// Print a unified diff of the changes instead of writing them
+    diff: bool,
+    /// Paths skipped while walking directories and expanding globs
+    ignore: Vec<Glob>,
+    /// Number of files processed at once
+    jobs: usize,
+    format: OutputFormat,
+    /// The `.lua_tools.toml` settings with the command line flags applied; they choose the
+    /// Lua version and the annotation style
+    config: Config,
+    /// The standard library plus any definitions loaded with --definitions
+    type_registry: project_context::TypeRegistry,
+    /// Hash of the settings the generated output depends on, or None with --no-cache
+    cache_key: Option<u64>,
+}
+
+/// What processing one file printed and found, kept so that files processed in parallel
+/// are reported in input order.
+struct FileReport {
+    stdout: String,
+    /// Whether the annotated output differs from what was at the file's output path
+    changed: bool,
+    /// How many more `---@` annotation lines the output has than the file
+    annotations: usize,
+    diagnostics: usize,
+    /// Why the file could not be read or written, if it could not
+    error: Option<String>,
+}
+
+impl FileReport {
+    fn failed(error: String) -> Self {
+        FileReport {
+            stdout: String::new(),
+            changed: false,
+            annotations: 0,
+            diagnostics: 0,
+            error: Some(error),
+        }
+    }
+}
+
+/// Totals over the files of one run, reported once the run is done.
+#[derive(Default)]
+struct Summary {
+    scanned: usize,
+    /// Files whose annotated output differs from what was at their output path
+    modified: usize,
+    annotations: usize,
+    diagnostics: usize,
+    /// Files that could not be read or written
+    failed: usize,
+}
+
+impl Summary {
+    fn add(&mut self, report: &FileReport) {
+        self.scanned += 1;
+        self.modified += usize::from(report.changed);
+        self.annotations += report.annotations;
+        self.diagnostics += report.diagnostics;
+        self.failed += usize::from(report.error.is_some());
+    }
+
+    fn describe(&self, elapsed: Duration) -> String {
+        let failed = if self.failed > 0 {
+            format!(", {} failed", self.failed)
+        } else {
+            String::new()
+        };
+        format!(
+            "{} files scanned, {} modified, {} annotations added, {} diagnostics{} in {:.2?}",
+            self.scanned, self.modified, self.annotations, self.diagnostics, failed, elapsed
+        )
+    }
+}
+
+/// A shell-style path pattern: `*` and `?` stay within one path component, `**` spans any
+/// number of them.
+struct Glob {
+    regex: Regex,
+    /// A pattern without `/` matches any single component, so `vendor` ignores every
+    /// directory named vendor.
+    component: bool,
+}
+
+impl Glob {
+    fn new(pattern: &str) -> Self {
+        let mut regex = String::from("^");
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        regex.push_str("(?:.*/)?");
+                    } else {
+                        regex.push_str(".*");
+                    }
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                other => regex.push_str(&regex::escape(&other.to_string())),
+            }
+        }
+        regex.push('$');
+        Glob {
+            regex: Regex::new(&regex).expect("glob patterns always form a valid regex"),
+            component: !pattern.contains('/'),
+        }
+    }
+
+    /// Whether a pattern contains wildcards and so needs expanding.
+    fn is_pattern(text: &str) -> bool {
+        text.contains(['*', '?'])
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        let text = path.to_string_lossy();
+        let text = text.strip_prefix("./").unwrap_or(&text);
+        if self.regex.is_match(text) {
+            return true;
+        }
+        self.component
+            && path
+                .components()
+                .any(|part| self.regex.is_match(&part.as_os_str().to_string_lossy()))
+    }
+}
+
+/// Whether a directory walk from `root` should skip this path. Patterns are matched
+/// against the path relative to the root.
+fn is_ignored(path: &Path, root: &Path, options: &Options) -> bool {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    options.ignore.iter().any(|glob| glob.matches(relative))
+}
+
+/// Expand glob inputs into the Lua files they match; other inputs are kept as given.
+fn expand_inputs(inputs: Vec<String>, options: &Options) -> Vec<String> {
+    let mut expanded = Vec::new();
+    for input in inputs {
+        if !Glob::is_pattern(&input) {
+            expanded.push(input);
+            continue;
+        }
+        // Walk from the components before the first wildcard.
+        let base: PathBuf = Path::new(&input)
+            .components()
+            .take_while(|part| !Glob::is_pattern(&part.as_os_str().to_string_lossy()))
+            .collect();
+        let base = if base.as_os_str().is_empty() {
+            PathBuf::from(".")
+        } else {
+            base
+        };
+        let glob = Glob::new(input.strip_prefix("./").unwrap_or(&input));
+        let walker = walkdir::WalkDir::new(&base)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| {
+                entry.depth() == 0
+                    || !(entry.file_type().is_dir()
+                        && project_context::is_excluded_dir(&entry.file_name().to_string_lossy())
+                        || is_ignored(entry.path(), &base, options))
+            });
+        let before = expanded.len();
+        for entry in walker.flatten() {
+            if entry.file_type().is_file() && glob.matches(entry.path()) {
+                let path = entry.path().to_string_lossy();
+                expanded.push(path.strip_prefix("./").unwrap_or(&path).to_string());
+            }
+        }
+        if expanded.len() == before {
+            log_message!("No files match {}", input);
+        }
+    }
+    expanded
+}
+
+/// Lines of unchanged context around each diff hunk.
+const DIFF_CONTEXT: usize = 3;
+
+/// One line of a line-based diff.
+enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Diff two texts line by line using a longest common subsequence table.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffLine<'a>> {
+    // lcs[i][j] is the LCS length of old[i..] and new[j..].
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+/// Render the changes from `old` to `new` as a unified diff, or an empty string if equal.
+fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let lines = diff_lines(&old_lines, &new_lines);
+
+    // Group changes into hunks, merging those whose context overlaps.
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if matches!(line, DiffLine::Same(_)) {
+            continue;
+        }
+        let start = index.saturating_sub(DIFF_CONTEXT);
+        let end = (index + 1 + DIFF_CONTEXT).min(lines.len());
+        match hunks.last_mut() {
+            Some((_, hunk_end)) if start <= *hunk_end => *hunk_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    let mut output = format!("--- {}\n+++ {}\n", path.display(), path.display());
+    for (start, end) in hunks {
+        let counts = |lines: &[DiffLine]| {
+            let old = lines
+                .iter()
+                .filter(|line| !matches!(line, DiffLine::Added(_)))
+                .count();
+            let new = lines
+                .iter()
+                .filter(|line| !matches!(line, DiffLine::Removed(_)))
+                .count();
+            (old, new)
+        };
+        let (old_before, new_before) = counts(&lines[..start]);
+        let (old_len, new_len) = counts(&lines[start..end]);
+        // An empty range is numbered by the line it follows.
+        let old_start = if old_len == 0 {
+            old_before
+        } else {
+            old_before + 1
+        };
+        let new_start = if new_len == 0 {
+            new_before
+        } else {
+            new_before + 1
+        };
+        output.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start, old_len, new_start, new_len
+        ));
+        for line in &lines[start..end] {
+            match line {
+                DiffLine::Same(text) => output.push_str(&format!(" {}\n", text)),
+                DiffLine::Removed(text) => output.push_str(&format!("-{}\n", text)),
+                DiffLine::Added(text) => output.push_str(&format!("+{}\n", text)),
+            }
+        }
+    }
+    output
+}
+
+/// Where cached output is kept, relative to the project root.
+const CACHE_DIR: &str = ".lua_tools/cache";
+
+/// 64-bit FNV-1a. Cache entries outlive the binary that wrote them, so keys must not depend
+/// on the standard library's unspecified hashing.
+struct CacheHasher(u64);
+
+impl CacheHasher {
+    fn new() -> Self {
+        CacheHasher(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Hash a string with its length, so adjacent strings cannot run into each other.
+    fn write_str(&mut self, text: &str) {
+        self.write(&(text.len() as u64).to_le_bytes());
+        self.write(text.as_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// The cache entry for a source under the given settings key. Entries hold the diagnostics
+/// count on their first line and the generated text after it.
+fn cache_entry(project_root: &Path, key: u64, content: &str) -> PathBuf {
+    let mut hasher = CacheHasher::new();
+    hasher.write(&key.to_le_bytes());
+    hasher.write_str(content);
+    project_root
+        .join(CACHE_DIR)
+        .join(format!("{:016x}", hasher.finish()))
+}
+
+fn read_cache_entry(entry: &Path) -> Option<Annotated> {
+    let cached = fs::read_to_string(entry).ok()?;
+    let (diagnostics, text) = cached.split_once('\n')?;
+    Some(Annotated {
+        text: text.to_string(),
+        diagnostics: diagnostics.parse().ok()?,
+    })
+}
+
+/// Like `annotate_source`, but reuse the output cached for identical contents and settings
+/// instead of analyzing the source again. A cache that cannot be read or written is skipped.
+fn annotate_cached(
+    path: &Path,
+    project_root: &Path,
+    key: u64,
+    content: &str,
+    options: &Options,
+) -> Annotated {
+    let entry = cache_entry(project_root, key, content);
+    if let Some(annotated) = read_cache_entry(&entry) {
+        log_verbose!("Cache hit, skipping analysis: {:?}", path);
+        return annotated;
+    }
+    let annotated = annotate_source(content, options);
+    let stored = fs::create_dir_all(project_root.join(CACHE_DIR)).and_then(|_| {
+        fs::write(
+            &entry,
+            format!("{}\n{}", annotated.diagnostics, annotated.text),
+        )
+    });
+    if let Err(e) = stored {
+        log_verbose!("Failed to write cache entry {:?}: {}", entry, e);
+    }
+    annotated
+}
+
+/// The cache key for a run: everything besides a file's contents that changes its output.
+fn settings_key(
+    matches: &clap::ArgMatches,
+    config: &Config,
+    frameworks: &[(String, String)],
+) -> u64 {
+    let mut hasher = CacheHasher::new();
+    hasher.write_str(env!("CARGO_PKG_VERSION"));
+    for (name, version) in frameworks {
+        hasher.write_str(name);
+        hasher.write_str(version);
+    }
+    // Definition files change the types in the output, so their contents are part of the key
+    for dir in matches.get_many::<String>("definitions").into_iter().flatten() {
+        let files = walkdir::WalkDir::new(dir)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter(|entry| entry.path().extension().and_then(|ext| ext.to_str()) == Some("lua"));
+        for file in files {
+            hasher.write_str(&file.path().to_string_lossy());
+            hasher.write_str(&fs::read_to_string(file.path()).unwrap_or_default());
+        }
+    }
+    hasher.write_str(config.lua_version().as_str());
+    hasher.write_str(&format!(
+        "{:?} {:?} {:?} {:?} {:?}",
+        config.format, config.unknown_type, config.order, config.align, config.nodiscard
+    ));
+    hasher.finish()
+}
+
+/// Annotate a file's contents and prepend its path, relative to the project root, as a header.
+fn annotate_file(
+    path: &Path,
+    content: &str,
+    options: &Options,
+) -> Annotated {
     let abs_path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
     let parent = abs_path.parent().unwrap_or_else(|| Path::new("."));
     let project_root = find_project_root(parent);
+    let annotated = match options.cache_key {
+        Some(key) => annotate_cached(path, &project_root, key, content, options),
+        None => annotate_source(content, options),
+    };
+
+    // Prepend the relative file path as a header.
     let rel_path = relative_path(&abs_path, &project_root);
-    let header = format!("-- {}\n\n", rel_path);
-    let final_output = format!("{}{}", header, annotations);
-
-    // Write output based on CLI flags.
-    if std::env::args().len() > 2 {
-        if overwrite {
-            fs::write(path, &final_output).expect("Failed to write output file");
-            eprintln!("File overwritten: {:?}", path);
+    Annotated {
+        text: format!("{}{}", options.format.header(&rel_path), annotated.text),
+        diagnostics: annotated.diagnostics,
+    }
+}
+
+/// Where the output for a source is written: the source itself with `--overwrite`, else the
+/// file named by the output pattern (with a `.md` extension for Markdown).
+fn output_path(path: &Path, options: &Options) -> PathBuf {
+    if options.overwrite {
+        return path.to_path_buf();
+    }
+    let filename = path.file_name().unwrap().to_string_lossy().into_owned();
+    let new_filename = options.output_pattern.replace("{}", &filename);
+    let mut output_path = path.with_file_name(new_filename);
+    if options.format == OutputFormat::Markdown {
+        output_path.set_extension("md");
+    }
+    output_path
+}
+
+/// Process a single Lua file: annotate it, prepend its path as a header and write the
+/// result to its output path.
+fn process_file(path: &Path, content: &str, options: &Options) -> io::Result<Annotated> {
+    log_verbose!("Processing file: {:?}", path);
+    let annotated = annotate_file(path, content, options);
+    let output_path = output_path(path, options);
+    fs::write(&output_path, &annotated.text)?;
+    log_verbose!("Output written to: {:?}", output_path);
+    Ok(annotated)
+}
+
+/// Check whether processing a file would change what is at its output path, adding a diff
+/// (with `--diff`) or its name to `stdout` if so. Nothing is written.
+fn check_file(path: &Path, content: &str, options: &Options, stdout: &mut String) -> Annotated {
+    let annotated = annotate_file(path, content, options);
+    let output_path = output_path(path, options);
+    let previous = fs::read_to_string(&output_path).unwrap_or_default();
+    if annotated.text != previous {
+        if options.diff {
+            stdout.push_str(&unified_diff(&output_path, &previous, &annotated.text));
         } else {
-            let filename = path.file_name().unwrap().to_string_lossy().into_owned();
-            let new_filename = output_pattern.replace("{}", &filename);
-            let output_path = path.with_file_name(new_filename);
-            fs::write(&output_path, &final_output).expect("Failed to write output file");
-            eprintln!("Output written to: {:?}", output_path);
+            stdout.push_str(&format!("Would annotate: {}\n", path.display()));
+        }
+    }
+    annotated
+}
+
+/// Process or check one file according to the options.
+fn handle_file(path: &Path, options: &Options) -> FileReport {
+    let mut stdout = String::new();
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => return FileReport::failed(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let previous = fs::read_to_string(output_path(path, options)).ok();
+    let annotated = if options.check || options.diff {
+        check_file(path, &content, options, &mut stdout)
+    } else {
+        match process_file(path, &content, options) {
+            Ok(annotated) => annotated,
+            Err(e) => {
+                let output_path = output_path(path, options);
+                return FileReport::failed(format!(
+                    "Failed to write {}: {}",
+                    output_path.display(),
+                    e
+                ));
+            }
         }
+    };
+    FileReport {
+        stdout,
+        changed: previous.as_deref() != Some(annotated.text.as_str()),
+        annotations: count_annotations(&annotated.text).saturating_sub(count_annotations(&content)),
+        diagnostics: annotated.diagnostics,
+        error: None,
     }
-    final_output
 }
 
-/// Process all Lua files in a directory (recursively if specified).
-fn process_directory(dir: &Path, output_pattern: &str, overwrite: bool, recursive: bool) {
-    let entries = fs::read_dir(dir).expect("Failed to read directory");
-    for entry in entries.flatten() {
+/// The number of `---@` annotation lines in Lua source.
+fn count_annotations(text: &str) -> usize {
+    text.lines()
+        .filter(|line| line.trim_start().starts_with("---@"))
+        .count()
+}
+
+/// Annotate Lua read from stdin and write the result to stdout.
+/// There is no file to take a header from, so one is only added when `header_path` is given.
+fn process_stdin(header_path: Option<&String>, options: &Options) {
+    let mut content = String::new();
+    io::stdin()
+        .read_to_string(&mut content)
+        .expect("Failed to read stdin");
+    let annotated = annotate_source(&content, options);
+    match header_path {
+        Some(header_path) => print!("{}{}", options.format.header(header_path), annotated.text),
+        None => print!("{}", annotated.text),
+    }
+}
+
+/// The contents of each input, or of stdin for `-`, with the name to show for it.
+fn read_sources(inputs: &[String], options: &Options) -> Vec<(String, String)> {
+    if inputs.iter().any(|input| input == "-") {
+        let mut content = String::new();
+        io::stdin()
+            .read_to_string(&mut content)
+            .expect("Failed to read stdin");
+        vec![("-".to_string(), content)]
+    } else {
+        collect_inputs(inputs, options)
+            .into_iter()
+            .map(|path| {
+                let content = fs::read_to_string(&path).expect("Failed to read file");
+                (path.display().to_string(), content)
+            })
+            .collect()
+    }
+}
+
+/// Print the tokens of each input, for debugging the tokenizer. Nothing is annotated or
+/// written.
+fn emit_tokens(inputs: &[String], options: &Options) {
+    for (name, content) in read_sources(inputs, options) {
+        let tokens = tokenizer::CodeTokenizer::new(&content).tokenize();
+        println!("-- {}", name);
+        println!("{}", tokenizer::token::pretty_print_tokens(&tokens));
+    }
+}
+
+/// Print the code AST of each input, for debugging the parser. Nothing is annotated or
+/// written.
+fn emit_ast(inputs: &[String], options: &Options) {
+    for (name, content) in read_sources(inputs, options) {
+        let tokens = tokenizer::CodeTokenizer::new(&content).tokenize();
+        let code_ast = parser::code_parser::CodeParser::new(tokens).parse();
+        println!("-- {}", name);
+        println!("{}", parser::pretty_print::pretty_print_code_ast(&code_ast));
+    }
+}
+
+/// Collect the Lua files in a directory (recursively if specified). `root` is the directory
+/// the walk started from.
+fn collect_directory(root: &Path, dir: &Path, options: &Options, files: &mut Vec<PathBuf>) {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .expect("Failed to read directory")
+        .flatten()
+        .collect();
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
         let path = entry.path();
-        if path.is_file() && path.extension().map_or(false, |ext| ext == "lua") {
-            process_file(&path, output_pattern, overwrite);
-        } else if path.is_dir() && recursive {
-            process_directory(&path, output_pattern, overwrite, recursive);
+        if is_ignored(&path, root, options) {
+            continue;
+        }
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "lua") {
+            files.push(path);
+        } else if path.is_dir()
+            && options.recursive
+            && !project_context::is_excluded_dir(&entry.file_name().to_string_lossy())
+        {
+            collect_directory(root, &path, options, files);
         }
     }
 }
 
+/// Process every file, `options.jobs` at a time, printing their reports in order.
+fn process_files(files: &[PathBuf], options: &Options) -> Summary {
+    let reports: Vec<FileReport> = if options.jobs <= 1 || files.len() <= 1 {
+        files
+            .iter()
+            .map(|path| handle_file(path, options))
+            .collect()
+    } else {
+        // Each file gets its own ProjectContext in annotate_source, so workers share only
+        // the read-only options.
+        let next = AtomicUsize::new(0);
+        let mut reports: Vec<Option<FileReport>> = files.iter().map(|_| None).collect();
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..options.jobs.min(files.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(path) = files.get(index) else {
+                                break;
+                            };
+                            done.push((index, handle_file(path, options)));
+                        }
+                        done
+                    })
+                })
+                .collect();
+            for worker in workers {
+                for (index, report) in worker.join().expect("Worker thread panicked") {
+                    reports[index] = Some(report);
+                }
+            }
+        });
+        reports.into_iter().flatten().collect()
+    };
+
+    let mut summary = Summary::default();
+    for report in reports {
+        print!("{}", report.stdout);
+        if let Some(error) = &report.error {
+            eprintln!("{}", error);
+        }
+        summary.add(&report);
+    }
+    summary
+}
+
+/// The files named by the inputs, with directories expanded.
+fn collect_inputs(inputs: &[String], options: &Options) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for input in inputs {
+        let path = Path::new(input);
+        if path.is_file() {
+            files.push(path.to_path_buf());
+        } else if path.is_dir() {
+            collect_directory(path, path, options, &mut files);
+        }
+    }
+    files
+}
+
+/// How often watched inputs are polled for changes.
+const WATCH_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Modification times of the Lua files named by the inputs.
+fn modification_times(inputs: &[String], options: &Options) -> HashMap<PathBuf, SystemTime> {
+    collect_inputs(inputs, options)
+        .into_iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+/// The current time of day (UTC) as `HH:MM:SS`.
+fn timestamp() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() % 86_400);
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Re-process the Lua files under the inputs whenever they change, until interrupted.
+/// A change is only picked up once a poll sees no further changes, so an editor's burst
+/// of writes triggers a single run.
+fn watch(inputs: &[String], options: &Options) -> ! {
+    log_message!("Watching for changes (press Ctrl-C to stop)");
+    let mut known = modification_times(inputs, options);
+    loop {
+        thread::sleep(WATCH_INTERVAL);
+        let mut current = modification_times(inputs, options);
+        if current == known {
+            continue;
+        }
+        loop {
+            thread::sleep(WATCH_INTERVAL);
+            let next = modification_times(inputs, options);
+            if next == current {
+                break;
+            }
+            current = next;
+        }
+        let mut changed: Vec<PathBuf> = current
+            .iter()
+            .filter(|(path, modified)| known.get(*path) != Some(*modified))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.sort();
+
+        let start = Instant::now();
+        let summary = process_files(&changed, options);
+        log_message!("[{}] {}", timestamp(), summary.describe(start.elapsed()));
+
+        // Files written by the run, including ones overwritten in place, are not changes.
+        known = modification_times(inputs, options);
+    }
+}
+
+/// Override the settings of a `.lua_tools.toml` file with the flags given on the command line.
+fn apply_flags(config: &mut Config, matches: &clap::ArgMatches) {
+    if let Some(version) = matches.get_one::<String>("lua-version") {
+        config.lua_version = project_context::LuaVersion::parse(version);
+    }
+    if let Some(frameworks) = matches.get_many::<String>("framework") {
+        config.frameworks = frameworks.cloned().collect();
+    }
+    if let Some(patterns) = matches.get_many::<String>("ignore") {
+        config.ignore = patterns.cloned().collect();
+    }
+    match matches.get_one::<String>("format").map(String::as_str) {
+        Some("markdown") => config.format = Some(OutputMode::Markdown),
+        Some(_) => config.format = Some(OutputMode::Annotations),
+        None => {}
+    }
+    if matches.get_flag("align") {
+        config.align = Some(true);
+    }
+    if matches.get_flag("nodiscard") {
+        config.nodiscard = Some(true);
+    }
+    if let Some(text) = matches.get_one::<String>("unknown-type") {
+        config.unknown_type = Some(annotator::UnknownType::parse(text));
+    }
+}
+
 fn main() {
     let matches = Command::new("lua_commenter")
         .about("Annotates Lua files with Lua LSP annotations")
+        .after_help("Settings can also be given in a .lua_tools.toml file in the current directory or one of its parents; flags take precedence.")
         .arg(
             Arg::new("input")
-                .help("Input file(s) or directory")
+                .help("Input file(s), directories or glob patterns (e.g. 'src/**/*.lua'), or - to read from stdin")
                 .required(true)
                 .num_args(1..),
         )
@@ -111,7 +852,7 @@ fn main() {
             Arg::new("output")
                 .short('o')
                 .long("output")
-                .help("Output filename pattern, use {} as placeholder (default: annotated_{})")
+                .help("Output filename pattern, use {} as placeholder (default: annotated_{}). A single input file is printed to stdout unless this or --overwrite is given")
                 .value_name("pattern")
                 .default_value("annotated_{}"),
         )
@@ -129,33 +870,261 @@ fn main() {
                 .help("Recursively process directories")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .help("Write nothing; exit with 1 if any file would change")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("diff")
+                .long("diff")
+                .help("Write nothing; print a unified diff of the changes")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("ignore")
+                .long("ignore")
+                .help("Skip paths matching a glob pattern when walking directories (repeatable)")
+                .value_name("pattern")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("jobs")
+                .short('j')
+                .long("jobs")
+                .help("Number of files to process in parallel (default: 1)")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("1"),
+        )
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .help("After processing the inputs, re-process Lua files whenever they change")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("no-cache")
+                .long("no-cache")
+                .help("Analyze every file again instead of reusing output cached in .lua_tools/cache")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .help("Report detection details")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("quiet"),
+        )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .help("Report nothing but errors")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help("What to generate: LuaLS annotations or Markdown API docs (written as .md)")
+                .value_name("format")
+                .value_parser(["annotations", "markdown"]),
+        )
+        .arg(
+            Arg::new("align")
+                .long("align")
+                .help("Line up the names, types and descriptions of generated annotations")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("nodiscard")
+                .long("nodiscard")
+                .help("Mark functions that return a known type with ---@nodiscard")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("unknown-type")
+                .long("unknown-type")
+                .help("What to write for types that cannot be inferred: any, todo (a <TODO> marker) or custom text")
+                .value_name("text"),
+        )
+        .arg(
+            Arg::new("lua-version")
+                .long("lua-version")
+                .help("Lua version whose standard library is assumed (default: 5.4)")
+                .value_name("version")
+                .value_parser(["5.1", "5.2", "5.3", "5.4", "luajit"]),
+        )
+        .arg(
+            Arg::new("framework")
+                .long("framework")
+                .help("Apply a framework's definitions, e.g. neovim or wezterm:20240222 (may be repeated)")
+                .value_name("name[:version]")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("definitions")
+                .long("definitions")
+                .help("Load LuaLS meta definition files from this directory (may be repeated)")
+                .value_name("dir")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("emit-tokens")
+                .long("emit-tokens")
+                .help("Print the tokens of each input and exit without annotating")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("emit-ast")
+                .long("emit-ast")
+                .help("Print the syntax tree of each input and exit without annotating")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("emit-tokens"),
+        )
+        .arg(
+            Arg::new("stdin-path")
+                .long("stdin-path")
+                .help("Path to show in the header when reading from stdin (default: no header)")
+                .value_name("path"),
+        )
         .get_matches();
 
+    if matches.get_flag("verbose") {
+        log::set_verbosity(Verbosity::Verbose);
+    } else if matches.get_flag("quiet") {
+        log::set_verbosity(Verbosity::Quiet);
+    }
+
+    let cwd = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let mut config = match Config::find(&cwd) {
+        Ok(Some((path, config))) => {
+            log_verbose!("Using settings from {}", path.display());
+            config
+        }
+        Ok(None) => Config::default(),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    apply_flags(&mut config, &matches);
+    let mut definitions = config.project_context();
+    for dir in matches
+        .get_many::<String>("definitions")
+        .into_iter()
+        .flatten()
+    {
+        match definitions.load_definition_dir(Path::new(dir)) {
+            Ok(count) => log_verbose!("Loaded {} definition files from {}", count, dir),
+            Err(e) => {
+                eprintln!("Failed to load definitions: {}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    for framework in &config.frameworks {
+        let registry = definitions.framework_registry();
+        let (name, version) = match framework.split_once(':') {
+            Some((name, version)) => (
+                name,
+                registry
+                    .resolve_version(name, version)
+                    .map(|definition| definition.version.clone()),
+            ),
+            None => (framework.as_str(), registry.get_latest_version(framework)),
+        };
+        let Some(version) = version else {
+            let mut names = registry.get_framework_names();
+            names.sort();
+            eprintln!(
+                "{}; available frameworks: {}",
+                LuaToolsError::UnknownFramework(framework.clone()),
+                names.join(", ")
+            );
+            std::process::exit(2);
+        };
+        log_verbose!("Using framework {} {}", name, version);
+        definitions
+            .detected_frameworks
+            .push((name.to_string(), version));
+    }
+    definitions.apply_framework_definitions();
+    // Checking writes nothing, cache entries included
+    let checking = matches.get_flag("check") || matches.get_flag("diff");
+    let cache_key = (!matches.get_flag("no-cache") && !checking)
+        .then(|| settings_key(&matches, &config, &definitions.detected_frameworks));
+
     let inputs: Vec<String> = matches
         .get_many::<String>("input")
         .unwrap()
         .map(|s| s.to_string())
         .collect();
-    let output_pattern = matches.get_one::<String>("output").unwrap();
-    let overwrite = *matches.get_one::<bool>("overwrite").unwrap_or(&false);
-    let recursive = *matches.get_one::<bool>("recursive").unwrap_or(&false);
+    let options = Options {
+        output_pattern: matches.get_one::<String>("output").unwrap().clone(),
+        overwrite: *matches.get_one::<bool>("overwrite").unwrap_or(&false),
+        recursive: *matches.get_one::<bool>("recursive").unwrap_or(&false),
+        check: *matches.get_one::<bool>("check").unwrap_or(&false),
+        diff: *matches.get_one::<bool>("diff").unwrap_or(&false),
+        ignore: config.ignore.iter().map(|pattern| Glob::new(pattern)).collect(),
+        jobs: *matches.get_one::<usize>("jobs").unwrap(),
+        format: match config.format {
+            Some(OutputMode::Markdown) => OutputFormat::Markdown,
+            _ => OutputFormat::Annotations,
+        },
+        config,
+        type_registry: definitions.type_registry,
+        cache_key,
+    };
+    let inputs = expand_inputs(inputs, &options);
+    if matches.get_flag("emit-tokens") {
+        emit_tokens(&inputs, &options);
+        return;
+    }
+    if matches.get_flag("emit-ast") {
+        emit_ast(&inputs, &options);
+        return;
+    }
+    let watching = matches.get_flag("watch");
+    let writes_output = options.overwrite
+        || matches.value_source("output") == Some(ValueSource::CommandLine)
+        || watching;
 
-    if inputs.len() == 1 {
-        let path = Path::new(&inputs[0]);
-        if path.is_file() {
-            let annotated = process_file(path, output_pattern, overwrite);
-            println!("{}", annotated);
-        } else {
-            eprintln!("Expected a file but found a directory.");
+    if inputs.iter().any(|input| input == "-") {
+        if inputs.len() > 1 {
+            eprintln!("Reading from stdin (-) cannot be combined with other inputs.");
+            std::process::exit(2);
+        }
+        if watching {
+            eprintln!("Reading from stdin (-) cannot be watched.");
+            std::process::exit(2);
         }
+        process_stdin(matches.get_one::<String>("stdin-path"), &options);
+    } else if options.check || options.diff {
+        let start = Instant::now();
+        let summary = process_files(&collect_inputs(&inputs, &options), &options);
+        log_message!("{}", summary.describe(start.elapsed()));
+        if (options.check && summary.modified > 0 || summary.failed > 0) && !watching {
+            std::process::exit(1);
+        }
+    } else if inputs.len() == 1 && Path::new(&inputs[0]).is_file() && !writes_output {
+        // A single file is annotated to stdout unless told where to write
+        let path = Path::new(&inputs[0]);
+        let content = fs::read_to_string(path).expect("Failed to read file");
+        println!("{}", annotate_file(path, &content, &options).text);
     } else {
-        for input in inputs {
-            let path = Path::new(&input);
-            if path.is_file() {
-                process_file(path, output_pattern, overwrite);
-            } else if path.is_dir() {
-                process_directory(path, output_pattern, overwrite, recursive);
-            }
+        let start = Instant::now();
+        let summary = process_files(&collect_inputs(&inputs, &options), &options);
+        log_message!("{}", summary.describe(start.elapsed()));
+        if summary.failed > 0 && !watching {
+            std::process::exit(1);
         }
     }
+
+    if watching {
+        watch(&inputs, &options);
+    }
 }
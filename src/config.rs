@@ -0,0 +1,187 @@
+// src/config.rs
+//
+// Project settings read from a `.lua_tools.toml` file, so that they don't have to be
+// repeated on every command line.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::annotator::{AnnotationKind, Annotator, UnknownType};
+use crate::error::LuaToolsError;
+use crate::project_context::{LuaVersion, ProjectContext};
+
+/// Name of the project config file.
+pub const CONFIG_FILE: &str = ".lua_tools.toml";
+
+/// What the tool generates for each source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    /// LuaLS annotations
+    Annotations,
+    /// Markdown API documentation
+    Markdown,
+}
+
+/// Settings from a `.lua_tools.toml` file. Every setting is optional, and command line flags
+/// take precedence over the file. For example:
+///
+/// ```toml
+/// lua_version = "5.1"
+/// frameworks = ["neovim"]
+/// ignore = ["vendor/**"]
+/// format = "annotations"
+///
+/// [annotations]
+/// align = true
+/// unknown_type = "todo"
+/// nodiscard = false
+/// order = ["description", "generic", "param", "return", "overload", "other"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub lua_version: Option<LuaVersion>,
+    /// Frameworks to apply, each `name` or `name:version`
+    pub frameworks: Vec<String>,
+    /// Glob patterns of paths to skip when walking directories
+    pub ignore: Vec<String>,
+    pub format: Option<OutputMode>,
+    /// Line up the columns of generated annotation blocks
+    pub align: Option<bool>,
+    /// What to write for types that could not be inferred
+    pub unknown_type: Option<UnknownType>,
+    /// Mark functions that return a known type with `---@nodiscard`
+    pub nodiscard: Option<bool>,
+    /// The order of the lines of a function's annotation block
+    pub order: Option<Vec<AnnotationKind>>,
+}
+
+impl Config {
+    /// Parse the contents of a config file.
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let table = text
+            .parse::<toml::Table>()
+            .map_err(|e| e.message().to_string())?;
+        let mut config = Config::default();
+        for (key, value) in &table {
+            match key.as_str() {
+                "lua_version" => {
+                    let version = string_value(key, value)?;
+                    config.lua_version = Some(
+                        LuaVersion::parse(&version)
+                            .ok_or_else(|| format!("unknown Lua version `{}`", version))?,
+                    );
+                }
+                "frameworks" => config.frameworks = string_list(key, value)?,
+                "ignore" => config.ignore = string_list(key, value)?,
+                "format" => {
+                    config.format = Some(match string_value(key, value)?.as_str() {
+                        "annotations" => OutputMode::Annotations,
+                        "markdown" => OutputMode::Markdown,
+                        other => return Err(format!("unknown format `{}`", other)),
+                    })
+                }
+                "annotations" => {
+                    let toml::Value::Table(style) = value else {
+                        return Err("`annotations` must be a table".to_string());
+                    };
+                    for (key, value) in style {
+                        match key.as_str() {
+                            "align" => config.align = Some(bool_value(key, value)?),
+                            "nodiscard" => config.nodiscard = Some(bool_value(key, value)?),
+                            "unknown_type" => {
+                                config.unknown_type =
+                                    Some(UnknownType::parse(&string_value(key, value)?))
+                            }
+                            "order" => {
+                                let kinds = string_list(key, value)?
+                                    .iter()
+                                    .map(|kind| {
+                                        AnnotationKind::parse(kind).ok_or_else(|| {
+                                            format!("unknown annotation kind `{}`", kind)
+                                        })
+                                    })
+                                    .collect::<Result<_, _>>()?;
+                                config.order = Some(kinds);
+                            }
+                            _ => return Err(format!("unknown setting `annotations.{}`", key)),
+                        }
+                    }
+                }
+                _ => return Err(format!("unknown setting `{}`", key)),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Read and parse a config file.
+    pub fn load(path: &Path) -> Result<Config, LuaToolsError> {
+        let text = fs::read_to_string(path).map_err(|source| LuaToolsError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Config::parse(&text).map_err(|message| LuaToolsError::InvalidConfig {
+            path: path.to_path_buf(),
+            message,
+        })
+    }
+
+    /// Load the nearest `.lua_tools.toml`, looking in `start` and then in each of its
+    /// parents. Returns None if there is no config file.
+    pub fn find(start: &Path) -> Result<Option<(PathBuf, Config)>, LuaToolsError> {
+        for dir in start.ancestors() {
+            let path = dir.join(CONFIG_FILE);
+            if path.is_file() {
+                let config = Config::load(&path)?;
+                return Ok(Some((path, config)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The Lua version to assume, 5.4 unless set.
+    pub fn lua_version(&self) -> LuaVersion {
+        self.lua_version.unwrap_or(LuaVersion::Lua54)
+    }
+
+    /// A project context for the configured Lua version. Frameworks are not applied.
+    pub fn project_context(&self) -> ProjectContext {
+        ProjectContext::new_with_version(self.lua_version())
+    }
+
+    /// An annotator with the configured annotation style.
+    pub fn annotator(&self) -> Annotator {
+        let mut annotator = Annotator::new();
+        annotator.align = self.align.unwrap_or(annotator.align);
+        annotator.nodiscard = self.nodiscard.unwrap_or(annotator.nodiscard);
+        if let Some(unknown_type) = &self.unknown_type {
+            annotator.unknown_type = unknown_type.clone();
+        }
+        if let Some(order) = &self.order {
+            annotator.order = order.clone();
+        }
+        annotator
+    }
+}
+
+fn string_value(key: &str, value: &toml::Value) -> Result<String, String> {
+    value
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| format!("`{}` must be a string", key))
+}
+
+fn bool_value(key: &str, value: &toml::Value) -> Result<bool, String> {
+    value
+        .as_bool()
+        .ok_or_else(|| format!("`{}` must be true or false", key))
+}
+
+fn string_list(key: &str, value: &toml::Value) -> Result<Vec<String>, String> {
+    let error = || format!("`{}` must be a list of strings", key);
+    value
+        .as_array()
+        .ok_or_else(error)?
+        .iter()
+        .map(|item| item.as_str().map(str::to_string).ok_or_else(error))
+        .collect()
+}
@@ -0,0 +1,102 @@
+// src/diagnostics.rs
+//
+// Diagnostics reported by the analysis passes.
+
+use crate::parser::annotation_parser::AnnotationParser;
+use crate::parser::ast::AnnotationASTNode;
+use crate::tokenizer::token::{Span, Token};
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Severity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+/// A problem found in a Lua source, located at the offending token
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Diagnostic name, as used by `---@diagnostic` directives (e.g. `undefined-global`)
+    pub code: String,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: &str, message: String, span: Span) -> Self {
+        Self {
+            severity,
+            code: code.to_string(),
+            message,
+            span,
+        }
+    }
+
+    /// Create a warning diagnostic
+    pub fn warning(code: &str, message: String, span: Span) -> Self {
+        Self::new(Severity::Warning, code, message, span)
+    }
+}
+
+/// A `---@diagnostic` directive and the line it appears on
+struct Directive {
+    line: usize,
+    action: String,
+    /// Diagnostic names the directive applies to; None means all of them
+    codes: Option<Vec<String>>,
+}
+
+impl Directive {
+    fn applies_to(&self, code: &str) -> bool {
+        self.codes
+            .as_ref()
+            .is_none_or(|codes| codes.iter().any(|c| c == code))
+    }
+}
+
+/// Collect `---@diagnostic` directives from a token stream, in source order
+fn collect_directives(tokens: &[Token]) -> Vec<Directive> {
+    let annotations = AnnotationParser::new(tokens).parse_with_spans();
+    let mut directives = Vec::new();
+    for (annotation, span) in annotations {
+        if let AnnotationASTNode::Diagnostic { action, diagnostic } = annotation {
+            directives.push(Directive {
+                line: span.line,
+                action,
+                codes: diagnostic.map(|names| names.split(',').map(str::to_string).collect()),
+            });
+        }
+    }
+    directives
+}
+
+/// Whether the directives silence a diagnostic
+fn is_suppressed(diagnostic: &Diagnostic, directives: &[Directive]) -> bool {
+    let line = diagnostic.span.line;
+    let mut disabled = false;
+    for directive in directives.iter().filter(|d| d.applies_to(&diagnostic.code)) {
+        match directive.action.as_str() {
+            "disable-next-line" if directive.line + 1 == line => return true,
+            "disable-line" if directive.line == line => return true,
+            "disable" if directive.line <= line => disabled = true,
+            "enable" if directive.line <= line => disabled = false,
+            _ => {}
+        }
+    }
+    disabled
+}
+
+/// Drop diagnostics silenced by `---@diagnostic disable`, `disable-line`,
+/// `disable-next-line` and `enable` directives in the source
+pub fn apply_directives(diagnostics: &mut Vec<Diagnostic>, tokens: &[Token]) {
+    let directives = collect_directives(tokens);
+    if directives.is_empty() {
+        return;
+    }
+    diagnostics.retain(|diagnostic| !is_suppressed(diagnostic, &directives));
+}
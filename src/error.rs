@@ -0,0 +1,66 @@
+// src/error.rs
+//
+// Errors returned by the library.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// An error from scanning, loading, or generating project information.
+#[derive(Debug)]
+pub enum LuaToolsError {
+    /// Reading a file or directory failed
+    Io { path: PathBuf, source: io::Error },
+    /// The operation needs a project root, but none was detected
+    NoProjectRoot,
+    /// A type file was requested, but no custom types have been collected
+    NoCustomTypes,
+    /// A framework (or a dependency given as `name:version`) is not registered
+    UnknownFramework(String),
+    /// A framework depends on another framework that is not registered
+    MissingDependency {
+        dependency: String,
+        required_by: String,
+    },
+    /// Framework dependencies form a cycle; the path ends where it started
+    CircularDependency(Vec<String>),
+    /// A config file is not valid TOML or has a setting with the wrong type
+    InvalidConfig { path: PathBuf, message: String },
+}
+
+impl fmt::Display for LuaToolsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LuaToolsError::Io { path, source } => {
+                write!(f, "Failed to read {}: {}", path.display(), source)
+            }
+            LuaToolsError::NoProjectRoot => write!(f, "Project root not detected"),
+            LuaToolsError::NoCustomTypes => write!(f, "No custom types to generate"),
+            LuaToolsError::UnknownFramework(key) => write!(f, "unknown framework {}", key),
+            LuaToolsError::MissingDependency {
+                dependency,
+                required_by,
+            } => write!(
+                f,
+                "missing dependency {} required by {}",
+                dependency, required_by
+            ),
+            LuaToolsError::CircularDependency(path) => {
+                write!(f, "circular framework dependency: {}", path.join(" -> "))
+            }
+            LuaToolsError::InvalidConfig { path, message } => {
+                write!(f, "Invalid config {}: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+impl Error for LuaToolsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LuaToolsError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
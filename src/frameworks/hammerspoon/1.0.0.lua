@@ -0,0 +1,112 @@
+--[[
+  Hammerspoon API Type Definitions
+
+  This file provides type definitions for the Hammerspoon macOS automation API.
+  It enhances type checking and auto-completion for Hammerspoon configurations.
+  
+  Hammerspoon version: 1.0.0
+  lua_version = "5.4" -- Hammerspoon uses Lua 5.4
+]]--
+
+local hs = {}
+
+-- =====================
+-- Core Types
+-- =====================
+
+---@class Window A window of a running application
+---@field id fun(self: Window): number Get the window identifier
+---@field title fun(self: Window): string Get the window title
+---@field frame fun(self: Window): table Get the window frame
+---@field setFrame fun(self: Window, frame: table) Move and resize the window
+---@field focus fun(self: Window) Focus the window
+---@field application fun(self: Window): Application Get the owning application
+hs.Window = {}
+
+---@class Application A running application
+---@field name fun(self: Application): string Get the application name
+---@field bundleID fun(self: Application): string Get the bundle identifier
+---@field activate fun(self: Application): boolean Bring the application to the front
+---@field kill fun(self: Application) Quit the application
+hs.Application = {}
+
+---@class Hotkey A registered keyboard shortcut
+---@field enable fun(self: Hotkey): Hotkey Enable the hotkey
+---@field disable fun(self: Hotkey): Hotkey Disable the hotkey
+---@field delete fun(self: Hotkey) Disable and delete the hotkey
+hs.Hotkey = {}
+
+-- =====================
+-- Windows
+-- =====================
+
+hs.window = {}
+
+--- Get the currently focused window
+---@return Window
+hs.window.focusedWindow = function() end
+
+--- Get all visible windows
+---@return Window[]
+hs.window.allWindows = function() end
+
+--- Get a window by its identifier
+---@param id number Window identifier
+---@return Window
+hs.window.get = function(id) end
+
+-- =====================
+-- Hotkeys
+-- =====================
+
+hs.hotkey = {}
+
+--- Create and enable a hotkey
+---@param mods table Modifier keys (e.g. {"cmd", "alt"})
+---@param key string The key name
+---@param pressedfn function Function called when the hotkey is pressed
+---@return Hotkey
+hs.hotkey.bind = function(mods, key, pressedfn) end
+
+--- Create a hotkey without enabling it
+---@param mods table Modifier keys
+---@param key string The key name
+---@param pressedfn function Function called when the hotkey is pressed
+---@return Hotkey
+hs.hotkey.new = function(mods, key, pressedfn) end
+
+-- =====================
+-- Applications
+-- =====================
+
+hs.application = {}
+
+--- Get the frontmost application
+---@return Application
+hs.application.frontmostApplication = function() end
+
+--- Launch or focus an application by name
+---@param name string The application name
+---@return boolean
+hs.application.launchOrFocus = function(name) end
+
+--- Find running applications matching a hint
+---@param hint string Name, bundle id or pid
+---@return Application
+hs.application.find = function(hint) end
+
+-- =====================
+-- Utilities
+-- =====================
+
+hs.alert = {}
+
+--- Show a brief on-screen message
+---@param message string The message to show
+---@param seconds number How long to show the message
+hs.alert.show = function(message, seconds) end
+
+--- Reload the Hammerspoon configuration
+hs.reload = function() end
+
+return hs
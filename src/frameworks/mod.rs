@@ -6,8 +6,14 @@
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use crate::error::LuaToolsError;
+use crate::{log_message, log_verbose};
 use crate::project_context::{LuaVersion, ProjectContext};
 
+mod version;
+
+pub use version::FrameworkVersionNumber;
+
 /// Framework definition with version information
 pub struct FrameworkVersion {
     /// Name of the framework
@@ -61,6 +67,12 @@ pub struct FrameworkRegistry {
     project_dir: Option<PathBuf>,
 }
 
+impl Default for FrameworkRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FrameworkRegistry {
     /// Create a new framework registry
     pub fn new() -> Self {
@@ -82,6 +94,11 @@ impl FrameworkRegistry {
     /// Initialize the registry with built-in framework definitions
     fn initialize_builtin_frameworks(&mut self) {
         // Neovim
+        self.register_framework(
+            FrameworkVersion::new("neovim", "0.8.0", LuaVersion::Lua51)
+                .with_description("Neovim API for version 0.8.x")
+        );
+        
         self.register_framework(
             FrameworkVersion::new("neovim", "0.9.0", LuaVersion::Lua51)
                 .with_description("Neovim API for version 0.9.x")
@@ -97,6 +114,11 @@ impl FrameworkRegistry {
                 .with_description("Neovim API for version 0.11.x")
         );
         
+        self.register_framework(
+            FrameworkVersion::new("neovim", "0.12.0-dev", LuaVersion::Lua51)
+                .with_description("Neovim API for the 0.12 nightly builds")
+        );
+        
         // WezTerm
         self.register_framework(
             FrameworkVersion::new("wezterm", "20230712", LuaVersion::Lua54)
@@ -119,11 +141,34 @@ impl FrameworkRegistry {
                 .with_description("LÖVE2D API for version 11.5")
         );
         
+        self.register_framework(
+            FrameworkVersion::new("love2d", "11.6", LuaVersion::Lua53)
+                .with_description("LÖVE2D API for version 11.6")
+        );
+        
         // Yazi
         self.register_framework(
             FrameworkVersion::new("yazi", "0.1.5", LuaVersion::Lua54)
                 .with_description("Yazi file manager API")
         );
+        
+        // Roblox (Luau is derived from Lua 5.1, so its definitions target 5.1)
+        self.register_framework(
+            FrameworkVersion::new("roblox", "1.0", LuaVersion::Lua51)
+                .with_description("Roblox engine API (Luau)")
+        );
+        
+        // OpenResty
+        self.register_framework(
+            FrameworkVersion::new("openresty", "1.25.3", LuaVersion::LuaJIT)
+                .with_description("OpenResty ngx_lua API for version 1.25.x")
+        );
+        
+        // Hammerspoon
+        self.register_framework(
+            FrameworkVersion::new("hammerspoon", "1.0.0", LuaVersion::Lua54)
+                .with_description("Hammerspoon macOS automation API")
+        );
     }
     
     /// Register a framework version in the registry
@@ -133,7 +178,7 @@ impl FrameworkRegistry {
         // Update the versions list for this framework
         self.versions
             .entry(framework.name.clone())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(framework.version.clone());
             
         // Add to the frameworks map
@@ -143,7 +188,8 @@ impl FrameworkRegistry {
     /// Discover framework definitions in standard locations
     pub fn discover_frameworks(&mut self) {
         // Check built-in frameworks directory
-        self.discover_in_directory(&self.base_dir);
+        let base_dir = self.base_dir.clone();
+        self.discover_in_directory(&base_dir);
         
         // Check user config directory
         if let Some(config_dir) = dirs::config_dir() {
@@ -244,6 +290,8 @@ impl FrameworkRegistry {
                     return Some(LuaVersion::Lua53);
                 } else if content.contains("\"5.4\"") || content.contains("'5.4'") {
                     return Some(LuaVersion::Lua54);
+                } else if content.contains("\"LuaJIT\"") || content.contains("'LuaJIT'") {
+                    return Some(LuaVersion::LuaJIT);
                 }
             }
         }
@@ -269,16 +317,21 @@ impl FrameworkRegistry {
             return None;
         }
         
-        // Simple version comparison (assumes semantic versioning or similar)
-        // A more robust solution would use a proper version parsing library
-        let mut latest = versions[0].clone();
-        for version in &versions[1..] {
-            if version_is_newer(version, &latest) {
-                latest = version.clone();
-            }
-        }
-        
-        Some(latest)
+        // Prereleases and unparseable versions are only chosen when nothing else is available
+        let parsed: Vec<_> = versions
+            .iter()
+            .filter_map(|v| FrameworkVersionNumber::parse(v).map(|parsed| (parsed, v)))
+            .collect();
+        let is_release = |version: &FrameworkVersionNumber| {
+            !matches!(version, FrameworkVersionNumber::Semver { prerelease: Some(_), .. })
+        };
+        parsed
+            .iter()
+            .filter(|(version, _)| is_release(version))
+            .max_by(|a, b| a.0.cmp(&b.0))
+            .or_else(|| parsed.iter().max_by(|a, b| a.0.cmp(&b.0)))
+            .map(|(_, v)| (*v).clone())
+            .or_else(|| versions.first().cloned())
     }
     
     /// Get a framework definition by name and version
@@ -287,6 +340,29 @@ impl FrameworkRegistry {
         self.frameworks.get(&key)
     }
     
+    /// Get the definition closest to a requested version: the highest registered version not
+    /// above it, else the lowest. A request that is not a version number must match exactly
+    pub fn resolve_version(&self, name: &str, requested: &str) -> Option<&FrameworkVersion> {
+        match FrameworkVersionNumber::parse(requested) {
+            Some(requested) => self.nearest_version(name, &requested),
+            None => self.get_framework(name, requested),
+        }
+    }
+    
+    /// The registered definition of a framework closest to `requested`, as for `resolve_version`
+    fn nearest_version(&self, name: &str, requested: &FrameworkVersionNumber) -> Option<&FrameworkVersion> {
+        let mut available: Vec<(FrameworkVersionNumber, String)> = self.get_framework_versions(name)
+            .into_iter()
+            .filter_map(|v| FrameworkVersionNumber::parse(&v).map(|parsed| (parsed, v)))
+            .collect();
+        available.sort_by(|a, b| a.0.cmp(&b.0));
+        let (_, version) = available.iter()
+            .rev()
+            .find(|(parsed, _)| parsed <= requested)
+            .or_else(|| available.first())?;
+        self.get_framework(name, version)
+    }
+    
     /// Get the latest version of a framework
     pub fn get_latest_framework(&self, name: &str) -> Option<&FrameworkVersion> {
         let version = self.get_latest_version(name)?;
@@ -312,6 +388,29 @@ impl FrameworkRegistry {
         fs::read_to_string(definition_path).ok()
     }
     
+    /// The global names a framework version provides: the top-level tables of its definition
+    pub fn framework_globals(&self, name: &str, version: &str) -> Vec<String> {
+        let Some(framework) = self.resolve_version(name, version) else {
+            return Vec::new();
+        };
+        let Some(definition) = self.read_framework_definition(name, &framework.version) else {
+            return Vec::new();
+        };
+        let tokens = crate::tokenizer::CodeTokenizer::new(&definition).tokenize();
+        let ast = crate::parser::code_parser::CodeParser::new(tokens).parse();
+        
+        let mut globals: Vec<String> = Vec::new();
+        for node in &ast {
+            if let crate::parser::ast::CodeASTNode::ModuleDeclaration { name, .. } = node {
+                // `vim.api = {}` adds to a table rather than defining a global
+                if !name.contains('.') && !globals.contains(name) {
+                    globals.push(name.clone());
+                }
+            }
+        }
+        globals
+    }
+    
     /// Detect if a directory is using a specific framework
     pub fn detect_framework_usage(&self, dir: &Path) -> Vec<(String, Option<String>)> {
         let mut results = Vec::new();
@@ -340,6 +439,24 @@ impl FrameworkRegistry {
             results.push(("yazi".to_string(), version));
         }
         
+        // Check for Roblox
+        if self.is_roblox_project(dir) {
+            results.push(("roblox".to_string(), self.get_latest_version("roblox")));
+        }
+        
+        // Check for OpenResty
+        if self.is_openresty_project(dir) {
+            results.push(("openresty".to_string(), self.get_latest_version("openresty")));
+        }
+        
+        // Check for Hammerspoon
+        if self.is_hammerspoon_project(dir) {
+            results.push(("hammerspoon".to_string(), self.get_latest_version("hammerspoon")));
+        }
+        
+        // Libraries and globals configured for the Lua Language Server
+        self.detect_framework_from_luarc(dir, &mut results);
+        
         // Attempt to detect additional frameworks from dependencies
         self.detect_framework_from_dependencies(dir, &mut results);
         
@@ -365,10 +482,14 @@ impl FrameworkRegistry {
             .into_iter()
             .filter_entry(|e| {
                 let path = e.path();
-                // Skip hidden directories, node_modules, etc.
+                // Skip hidden directories, node_modules, etc., but always scan the directory
+                // itself, which may be hidden (`~/.hammerspoon`)
+                if e.depth() == 0 {
+                    return true;
+                }
                 !path.to_string_lossy().contains("node_modules") &&
                 !path.to_string_lossy().contains("/.git/") &&
-                !path.file_name().map_or(false, |n| n.to_string_lossy().starts_with('.'))
+                !path.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.'))
             });
         
         for entry in walker.filter_map(|e| e.ok()) {
@@ -377,7 +498,8 @@ impl FrameworkRegistry {
             }
             
             let path = entry.path();
-            if !path.is_file() || path.extension().and_then(|e| e.to_str()) != Some("lua") {
+            // Roblox projects commonly use the .luau extension
+            if !path.is_file() || !matches!(path.extension().and_then(|e| e.to_str()), Some("lua") | Some("luau")) {
                 continue;
             }
             
@@ -397,18 +519,67 @@ impl FrameworkRegistry {
         for framework in frameworks_detected {
             // Avoid duplicates
             if !results.iter().any(|(name, _)| name == &framework) {
-                let version = match framework.as_str() {
-                    "neovim" => self.detect_neovim_version(dir),
-                    "wezterm" => self.detect_wezterm_version(dir),
-                    "love2d" => self.detect_love2d_version(dir),
-                    "yazi" => self.detect_yazi_version(dir),
-                    _ => self.get_latest_version(&framework),
-                };
+                let version = self.detect_framework_version(&framework, dir);
                 results.push((framework, version));
             }
         }
     }
     
+    /// Detect frameworks from the `workspace.library`, `workspace.userThirdParty` and
+    /// `diagnostics.globals` settings of a `.luarc.json`
+    fn detect_framework_from_luarc(&self, dir: &Path, results: &mut Vec<(String, Option<String>)>) {
+        let Ok(content) = fs::read_to_string(dir.join(".luarc.json")) else {
+            return;
+        };
+        let libraries: Vec<String> = ["workspace.library", "library", "workspace.userThirdParty", "userThirdParty"]
+            .iter()
+            .flat_map(|key| json_string_array(&content, key))
+            .map(|library| library.to_lowercase())
+            .collect();
+        let globals: Vec<String> = ["diagnostics.globals", "globals"]
+            .iter()
+            .flat_map(|key| json_string_array(&content, key))
+            .collect();
+        
+        let mut names = self.get_framework_names();
+        names.sort();
+        for framework in names {
+            if results.iter().any(|(name, _)| *name == framework) {
+                continue;
+            }
+            let markers: &[&str] = match framework.as_str() {
+                "neovim" => &["nvim", "neovim", "vimruntime", "lazydev", "neodev"],
+                "love2d" => &["love2d", "love-api"],
+                other => &[other],
+            };
+            let in_library = libraries.iter()
+                .any(|library| markers.iter().any(|marker| library.contains(marker)));
+            let in_globals = || {
+                self.get_latest_version(&framework)
+                    .is_some_and(|version| {
+                        self.framework_globals(&framework, &version).iter().any(|global| globals.contains(global))
+                    })
+            };
+            if in_library || in_globals() {
+                log_verbose!("Detected {} from .luarc.json", framework);
+                let version = self.detect_framework_version(&framework, dir);
+                results.push((framework, version));
+            }
+        }
+    }
+    
+    /// The version of a framework a project uses, by the framework's own detection when it
+    /// has one, else the latest registered version
+    fn detect_framework_version(&self, framework: &str, dir: &Path) -> Option<String> {
+        match framework {
+            "neovim" => self.detect_neovim_version(dir),
+            "wezterm" => self.detect_wezterm_version(dir),
+            "love2d" => self.detect_love2d_version(dir),
+            "yazi" => self.detect_yazi_version(dir),
+            _ => self.get_latest_version(framework),
+        }
+    }
+    
     /// Scan file content for framework imports and require statements
     fn scan_for_framework_imports(&self, content: &str, detected: &mut std::collections::HashSet<String>) -> bool {
         // Framework-specific modules and patterns
@@ -447,7 +618,31 @@ impl FrameworkRegistry {
             ("require(\"yazi", "yazi"),
             ("require('yazi", "yazi"),
             ("ya.manager", "yazi"),
-            ("ya.preview", "yazi")
+            ("ya.preview", "yazi"),
+            
+            // Roblox
+            ("game:GetService(", "roblox"),
+            ("script.Parent", "roblox"),
+            ("Instance.new(", "roblox"),
+            
+            // OpenResty
+            ("require'resty.", "openresty"),
+            ("require 'resty.", "openresty"),
+            ("require(\"resty.", "openresty"),
+            ("require('resty.", "openresty"),
+            ("ngx.say", "openresty"),
+            ("ngx.req.", "openresty"),
+            ("ngx.var.", "openresty"),
+            ("ngx.socket.", "openresty"),
+            ("ngx.log(", "openresty"),
+            ("ngx.exit(", "openresty"),
+            
+            // Hammerspoon
+            ("hs.hotkey", "hammerspoon"),
+            ("hs.window", "hammerspoon"),
+            ("hs.application", "hammerspoon"),
+            ("hs.alert", "hammerspoon"),
+            ("hs.reload", "hammerspoon")
         ];
         
         let lines = content.lines().collect::<Vec<_>>();
@@ -468,7 +663,7 @@ impl FrameworkRegistry {
             return true;
         }
         
-        return false;
+        false
     }
     
     /// Check if a directory is a Neovim plugin project
@@ -485,6 +680,11 @@ impl FrameworkRegistry {
     
     /// Try to detect Neovim version from project files
     fn detect_neovim_version(&self, dir: &Path) -> Option<String> {
+        // A `vim.fn.has('nvim-0.x')` guard states the version outright
+        if let Some(version) = self.scan_for_neovim_has_version(dir) {
+            return Some(version);
+        }
+        
         // Check for explicit version in documentation
         let doc_files = vec!["README.md", "doc/help.txt", "doc/plugin.txt", "plugin/plugin.lua", "plugin/init.lua"];
         for file in doc_files {
@@ -492,7 +692,12 @@ impl FrameworkRegistry {
             if file_path.exists() {
                 if let Ok(content) = fs::read_to_string(&file_path) {
                     // Look for version requirements with more patterns
-                    if content.contains("requires Neovim 0.11") || 
+                    if content.contains("requires Neovim 0.12") || 
+                       content.contains("requires nvim 0.12") || 
+                       content.contains("neovim >= 0.12") ||
+                       content.contains("nvim >= 0.12") {
+                        return Some("0.12.0-dev".to_string());
+                    } else if content.contains("requires Neovim 0.11") || 
                        content.contains("requires nvim 0.11") || 
                        content.contains("requires \"neovim >= 0.11") || 
                        content.contains("requires 'neovim >= 0.11") ||
@@ -517,8 +722,7 @@ impl FrameworkRegistry {
                             content.contains("requires nvim 0.8") ||
                             content.contains("neovim >= 0.8") ||
                             content.contains("nvim >= 0.8") {
-                        // Though we don't have 0.8 definitions, fallback to 0.9
-                        return Some("0.9.0".to_string());
+                        return Some("0.8.0".to_string());
                     }
                 }
             }
@@ -640,6 +844,13 @@ impl FrameworkRegistry {
         None
     }
     
+    /// The definition version for the highest `has('nvim-X.Y')` guard in a directory's Lua files
+    fn scan_for_neovim_has_version(&self, dir: &Path) -> Option<String> {
+        let highest = neovim_has_versions(dir).into_iter().max()?;
+        self.nearest_version("neovim", &highest)
+            .map(|framework| framework.version.clone())
+    }
+
     /// Scan Lua files in a directory for specific API usage
     fn scan_for_neovim_api_usage(&self, dir: &Path, patterns: Vec<&str>) -> bool {
         if let Ok(entries) = fs::read_dir(dir) {
@@ -673,6 +884,7 @@ impl FrameworkRegistry {
     /// Try to detect WezTerm version from project files
     fn detect_wezterm_version(&self, dir: &Path) -> Option<String> {
         let config_files = vec!["wezterm.lua", ".wezterm.lua"];
+        let mut evidence = Vec::new();
         for file in config_files {
             let file_path = dir.join(file);
             if file_path.exists() {
@@ -688,30 +900,25 @@ impl FrameworkRegistry {
                         return Some("20230712".to_string());
                     }
                     
-                    // Look for version-specific features
+                    // Collect the versions implied by the APIs used anywhere in the file
+                    evidence.extend(
+                        WEZTERM_FEATURES
+                            .iter()
+                            .filter(|(feature, _)| content.contains(feature))
+                            .map(|(_, version)| *version),
+                    );
                     if content.contains("wezterm.mux") && content.contains("wezterm.gui") {
-                        // Features introduced in 20240222
-                        return Some("20240222".to_string());
-                    }
-                    
-                    // Check for other features specific to 20240222
-                    if content.contains("wezterm.color.parse") || 
-                       content.contains("wezterm.color.gradient") || 
-                       content.contains("wezterm.procinfo") || 
-                       content.contains("background_blur_radius") {
-                        return Some("20240222".to_string());
-                    }
-                    
-                    // Check for APIs that existed in 20230712
-                    if content.contains("wezterm.action") || 
-                       content.contains("wezterm.format") {
-                        // These features existed in 20230712
-                        // but we only return this if we haven't already identified newer features
-                        return Some("20230712".to_string());
+                        // Scripting both the multiplexer and the GUI needs 20240222
+                        evidence.push("20240222");
                     }
                 }
             }
         }
+
+        // The newest version implied by any of the evidence
+        if let Some(version) = evidence.iter().max_by_key(|v| FrameworkVersionNumber::parse(v)) {
+            return Some(version.to_string());
+        }
         
         // Check if there are any comments mentioning specific WezTerm versions in any Lua files
         let mut max_files = 5;
@@ -750,20 +957,11 @@ impl FrameworkRegistry {
         let conf_path = dir.join("conf.lua");
         if conf_path.exists() {
             if let Ok(content) = fs::read_to_string(&conf_path) {
-                // Look for LÖVE version in configuration with more patterns
-                if content.contains("t.version = \"11.5") || 
-                   content.contains("t.version = '11.5") {
-                    return Some("11.5".to_string());
-                } else if content.contains("t.version = \"11.4") || 
-                           content.contains("t.version = '11.4") {
-                    return Some("11.4".to_string());
-                } else if content.contains("t.version = \"11.3") || 
-                           content.contains("t.version = '11.3") {
-                    // Fall back to 11.4 for 11.3 (which we don't have specific support for)
-                    return Some("11.4".to_string());
-                } else if content.contains("t.version = \"11.") || 
-                           content.contains("t.version = '11.") {
-                    return Some("11.4".to_string()); // Default for LÖVE 11.x
+                // `t.version` names the release the game was made for
+                if let Some(version) = love_conf_version(&content) {
+                    if let Some(framework) = self.nearest_version("love2d", &version) {
+                        return Some(framework.version.clone());
+                    }
                 }
                 
                 // Check for feature requirements mentioned in comments
@@ -894,27 +1092,221 @@ impl FrameworkRegistry {
         self.get_latest_version("yazi")
     }
     
+    /// Check if a directory is a Roblox project (Rojo project file or saved place)
+    fn is_roblox_project(&self, dir: &Path) -> bool {
+        if dir.join("default.project.json").exists() {
+            return true;
+        }
+        
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_file() && matches!(path.extension().and_then(|e| e.to_str()), Some("rbxl") | Some("rbxlx")) {
+                    return true;
+                }
+            }
+        }
+        
+        false
+    }
+    
+    /// Check if a directory is an OpenResty project (nginx config with embedded Lua handlers)
+    fn is_openresty_project(&self, dir: &Path) -> bool {
+        let config_files = ["nginx.conf", "conf/nginx.conf"];
+        config_files.iter().any(|file| {
+            fs::read_to_string(dir.join(file))
+                .map(|content| content.contains("content_by_lua"))
+                .unwrap_or(false)
+        })
+    }
+    
+    /// Check if a directory is a Hammerspoon configuration (`~/.hammerspoon/init.lua` layout)
+    fn is_hammerspoon_project(&self, dir: &Path) -> bool {
+        let is_config_dir = dir.file_name().is_some_and(|n| n == ".hammerspoon") && dir.join("init.lua").exists();
+        is_config_dir || dir.join(".hammerspoon").join("init.lua").exists()
+    }
+    
     /// Apply a framework's type definitions to a project context
+    /// Dependencies are applied first, so a framework's definitions can build on theirs
     pub fn apply_framework_to_context(&self, context: &mut ProjectContext, name: &str, version: &str) -> bool {
-        // Get the framework definition
-        let definition = match self.read_framework_definition(name, version) {
-            Some(content) => content,
-            None => return false,
+        let order = match self.resolve_dependency_order(name, version) {
+            Ok(order) => order,
+            Err(e) => {
+                log_message!("Cannot apply {} {}: {}", name, version, e);
+                return false;
+            }
         };
         
-        // Create a temporary file with the definition
-        let temp_dir = tempfile::tempdir().ok()?;
-        let temp_file = temp_dir.path().join(format!("{}.lua", name));
+        let mut applied = true;
+        for (dep_name, dep_version) in &order {
+            // Get the framework definition
+            match self.read_framework_definition(dep_name, dep_version) {
+                // Register the definition's types and functions with the project context
+                Some(definition) => context.load_definitions(&definition),
+                None => applied = false,
+            }
+        }
+        applied
+    }
+    
+    /// Resolve a framework and its transitive dependencies into the order they should be applied
+    pub fn resolve_dependency_order(&self, name: &str, version: &str) -> Result<Vec<(String, String)>, LuaToolsError> {
+        let mut order = Vec::new();
+        let mut visiting = Vec::new();
+        self.visit_dependencies(name, version, &mut visiting, &mut order)?;
+        Ok(order)
+    }
+    
+    /// Depth-first walk of the dependency graph, appending each framework after its dependencies
+    fn visit_dependencies(
+        &self,
+        name: &str,
+        version: &str,
+        visiting: &mut Vec<String>,
+        order: &mut Vec<(String, String)>,
+    ) -> Result<(), LuaToolsError> {
+        let key = format!("{}:{}", name, version);
+        if order.iter().any(|(n, v)| n == name && v == version) {
+            return Ok(());
+        }
+        if visiting.contains(&key) {
+            visiting.push(key);
+            return Err(LuaToolsError::CircularDependency(visiting.clone()));
+        }
         
-        if fs::write(&temp_file, definition).is_err() {
-            return false;
+        let framework = self.get_framework(name, version)
+            .ok_or_else(|| LuaToolsError::UnknownFramework(key.clone()))?;
+        
+        visiting.push(key.clone());
+        for dependency in &framework.dependencies {
+            let (dep_name, dep_version) = self.resolve_dependency(dependency)
+                .ok_or_else(|| LuaToolsError::MissingDependency {
+                    dependency: dependency.clone(),
+                    required_by: key.clone(),
+                })?;
+            self.visit_dependencies(&dep_name, &dep_version, visiting, order)?;
         }
+        visiting.pop();
         
-        // Process the definition file using the project context
-        context.process_single_type_file(&temp_file).is_ok()
+        order.push((name.to_string(), version.to_string()));
+        Ok(())
+    }
+    
+    /// Resolve a dependency given as "name" (latest version) or "name:version" (nearest version)
+    fn resolve_dependency(&self, dependency: &str) -> Option<(String, String)> {
+        match dependency.split_once(':') {
+            Some((name, version)) => {
+                let framework = self.resolve_version(name, version)?;
+                Some((name.to_string(), framework.version.clone()))
+            }
+            None => {
+                let version = self.get_latest_version(dependency)?;
+                Some((dependency.to_string(), version))
+            }
+        }
     }
 }
 
+/// WezTerm APIs and settings, each with the oldest registered release that has it
+const WEZTERM_FEATURES: &[(&str, &str)] = &[
+    ("wezterm.action", "20230712"),
+    ("wezterm.format", "20230712"),
+    ("wezterm.config_builder", "20230712"),
+    ("wezterm.mux", "20230712"),
+    ("wezterm.gui", "20230712"),
+    ("wezterm.color.parse", "20240222"),
+    ("wezterm.color.gradient", "20240222"),
+    ("wezterm.procinfo", "20240222"),
+    ("wezterm.serde", "20240222"),
+    ("background_blur_radius", "20240222"),
+];
+
+/// The strings of every JSON array stored under `key`, found by scanning the text; enough
+/// for the flat string lists of a `.luarc.json`
+fn json_string_array(content: &str, key: &str) -> Vec<String> {
+    let quoted_key = format!("\"{}\"", key);
+    let mut strings = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find(&quoted_key) {
+        rest = &rest[start + quoted_key.len()..];
+        let Some(array) = rest.trim_start().strip_prefix(':').map(str::trim_start) else {
+            continue;
+        };
+        let Some(array) = array.strip_prefix('[') else {
+            continue;
+        };
+        let array = &array[..array.find(']').unwrap_or(array.len())];
+        let mut chars = array.chars();
+        while let Some(c) = chars.next() {
+            if c != '"' {
+                continue;
+            }
+            let mut string = String::new();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => string.extend(chars.next()),
+                    c => string.push(c),
+                }
+            }
+            strings.push(string);
+        }
+    }
+    strings
+}
+
+/// The version assigned to `t.version` in a LÖVE `conf.lua`
+fn love_conf_version(content: &str) -> Option<FrameworkVersionNumber> {
+    content.lines().find_map(|line| {
+        let value = line.trim().strip_prefix("t.version")?.trim_start().strip_prefix('=')?;
+        let value = value.trim_start();
+        let quote = value.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+        let value = &value[1..];
+        FrameworkVersionNumber::parse(&value[..value.find(quote)?])
+    })
+}
+
+/// The versions named by `has('nvim-X.Y')` / `has("nvim-X.Y")` calls in the Lua files under
+/// a directory
+fn neovim_has_versions(dir: &Path) -> Vec<FrameworkVersionNumber> {
+    let mut versions = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return versions;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if !crate::project_context::is_excluded_dir(&entry.file_name().to_string_lossy()) {
+                versions.extend(neovim_has_versions(&path));
+            }
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("lua") {
+            if let Ok(content) = fs::read_to_string(&path) {
+                versions.extend(has_guard_versions(&content));
+            }
+        }
+    }
+    versions
+}
+
+/// The versions in the `has('nvim-X.Y')` calls of a Lua source
+fn has_guard_versions(content: &str) -> Vec<FrameworkVersionNumber> {
+    let mut versions = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("has(") {
+        rest = rest[start + 4..].trim_start();
+        let Some(quote) = rest.chars().next().filter(|c| *c == '\'' || *c == '"') else {
+            continue;
+        };
+        let Some(argument) = rest[1..].split(quote).next() else {
+            continue;
+        };
+        if let Some(version) = argument.strip_prefix("nvim-").and_then(FrameworkVersionNumber::parse) {
+            versions.push(version);
+        }
+    }
+    versions
+}
+
 /// Extract description from framework definition file content
 fn extract_description_from_content(content: &str) -> Option<String> {
     // Look for description in header comment
@@ -932,110 +1324,6 @@ fn extract_description_from_content(content: &str) -> Option<String> {
     None
 }
 
-/// Compare two version strings to determine which is newer
-/// This is a more robust implementation that handles various versioning schemes
-fn version_is_newer(version1: &str, version2: &str) -> bool {
-    // Special case for date-based versions (like WezTerm's YYYYMMDD format)
-    if version1.len() == 8 && version2.len() == 8 && 
-       version1.chars().all(|c| c.is_digit(10)) && 
-       version2.chars().all(|c| c.is_digit(10)) {
-        return version1 > version2;
-    }
-    
-    // Handle semver with prefixes (v1.2.3)
-    let clean_v1 = version1.trim_start_matches('v');
-    let clean_v2 = version2.trim_start_matches('v');
-    
-    // Try different separators (., _, -, etc.)
-    let separators = ['.', '-', '_', ' '];
-    let mut components1 = Vec::new();
-    let mut components2 = Vec::new();
-    
-    // Find the first separator that produces a valid split
-    for &sep in &separators {
-        let split1: Vec<&str> = clean_v1.split(sep).collect();
-        let split2: Vec<&str> = clean_v2.split(sep).collect();
-        
-        if split1.len() > 1 || split2.len() > 1 {
-            components1 = split1;
-            components2 = split2;
-            break;
-        }
-    }
-    
-    // If no separator worked, treat as single components
-    if components1.is_empty() {
-        components1 = vec![clean_v1];
-        components2 = vec![clean_v2];
-    }
-    
-    // Compare components
-    for (i, c1) in components1.iter().enumerate() {
-        // If we've run out of components in version2, version1 is newer
-        // But only if the additional component is not "0" (1.2.3 > 1.2)
-        if i >= components2.len() {
-            return c1 != &"0";
-        }
-        
-        let c2 = components2[i];
-        
-        // Try to parse components as integers for numeric comparison
-        match (c1.parse::<u64>(), c2.parse::<u64>()) {
-            (Ok(n1), Ok(n2)) => {
-                if n1 > n2 {
-                    return true;
-                } else if n1 < n2 {
-                    return false;
-                }
-                // If equal, continue to the next component
-            },
-            _ => {
-                // Special handling for prerelease suffixes
-                if c1.starts_with(|c: char| c.is_digit(10)) && !c2.starts_with(|c: char| c.is_digit(10)) {
-                    // Numeric is newer than alpha/beta/etc (1.0 > 1.0-beta)
-                    return true;
-                } else if !c1.starts_with(|c: char| c.is_digit(10)) && c2.starts_with(|c: char| c.is_digit(10)) {
-                    // Alpha/beta/etc is older than numeric (1.0-beta < 1.0)
-                    return false;
-                }
-                
-                // Prerelease order: dev < alpha < beta < rc < (nothing)
-                let prerelease_order = |s: &str| -> u8 {
-                    let lower = s.to_lowercase();
-                    if lower.contains("dev") { 1 }
-                    else if lower.contains("alpha") { 2 } 
-                    else if lower.contains("beta") { 3 }
-                    else if lower.contains("rc") { 4 }
-                    else { 5 }
-                };
-                
-                let order1 = prerelease_order(c1);
-                let order2 = prerelease_order(c2);
-                
-                if order1 != order2 {
-                    return order1 > order2;
-                }
-                
-                // If all else fails, compare as strings
-                if c1 > c2 {
-                    return true;
-                } else if c1 < c2 {
-                    return false;
-                }
-            }
-        }
-    }
-    
-    // If we've exhausted version1's components and version2 has more, it might be newer
-    // But only if those extra components aren't all zeros (1.2 == 1.2.0)
-    if components1.len() < components2.len() {
-        return !components2[components1.len()..].iter().all(|c| c == &"0");
-    }
-    
-    // If all components are equal, the versions are equal
-    false
-}
-
 /// Create a framework definition file with specified version
 pub fn create_framework_template(name: &str, version: &str, lua_version: LuaVersion) -> Option<String> {
     match name.to_lowercase().as_str() {
@@ -1043,6 +1331,9 @@ pub fn create_framework_template(name: &str, version: &str, lua_version: LuaVers
         "wezterm" => Some(create_wezterm_template(version, lua_version)),
         "love2d" => Some(create_love2d_template(version, lua_version)),
         "yazi" => Some(create_yazi_template(version, lua_version)),
+        "roblox" => Some(create_roblox_template(version, lua_version)),
+        "openresty" => Some(create_openresty_template(version, lua_version)),
+        "hammerspoon" => Some(create_hammerspoon_template(version, lua_version)),
         _ => None,
     }
 }
@@ -1059,7 +1350,7 @@ fn create_neovim_template(version: &str, _lua_version: LuaVersion) -> String {
   lua_version = "5.1" -- Neovim uses Lua 5.1
 ]]--
 
-local Neovim = {{}}
+local vim = {{}}
 
 -- =====================
 -- Core API Types
@@ -1070,7 +1361,7 @@ local Neovim = {{}}
 ---@field name string Buffer name
 ---@field lines table List of lines in the buffer
 ---@field options table Buffer-local options
-Neovim.Buffer = {{}}
+vim.Buffer = {{}}
 
 ---@class Window Represents a Neovim window
 ---@field id number Window handle ID
@@ -1078,43 +1369,45 @@ Neovim.Buffer = {{}}
 ---@field height number Window height
 ---@field width number Window width
 ---@field options table Window-local options
-Neovim.Window = {{}}
+vim.Window = {{}}
 
 ---@class Tabpage Represents a Neovim tabpage
 ---@field id number Tabpage handle ID
 ---@field windows table List of windows in this tabpage
-Neovim.Tabpage = {{}}
+vim.Tabpage = {{}}
 
 -- =====================
 -- API Functions
 -- =====================
 
+vim.api = {{}}
+
 -- Buffer Operations
 
 --- Gets current buffer
 ---@return Buffer
-Neovim.api.get_current_buf = function() end
+vim.api.get_current_buf = function() end
 
 --- Gets buffer line
 ---@param buffer Buffer Buffer handle
 ---@param index number Line index (0-based)
 ---@return string
-Neovim.api.buf_get_line = function(buffer, index) end
+vim.api.buf_get_line = function(buffer, index) end
 
 --- Sets buffer line
 ---@param buffer Buffer Buffer handle
 ---@param index number Line index (0-based)
 ---@param line string New line content
 ---@return boolean
-Neovim.api.buf_set_line = function(buffer, index, line) end
+vim.api.buf_set_line = function(buffer, index, line) end
 
 -- Command Operations
 
 --- Execute a Vim command
 ---@param command string Command to execute
-Neovim.api.command = function(command) end
+vim.api.command = function(command) end
 
-return Neovim
+return vim
 "#, version)
 }
 
@@ -1329,4 +1622,179 @@ yazi.run = function(command, callback) end
 
 return yazi
 "#, version)
-}
\ No newline at end of file
+}
+
+/// Create a Roblox framework definition template
+fn create_roblox_template(version: &str, _lua_version: LuaVersion) -> String {
+    include_str!("roblox/1.0.lua").replacen(
+        "Roblox version: 1.0",
+        &format!("Roblox version: {}", version),
+        1,
+    )
+}
+
+/// Create an OpenResty framework definition template
+fn create_openresty_template(version: &str, _lua_version: LuaVersion) -> String {
+    include_str!("openresty/1.25.3.lua").replacen(
+        "OpenResty version: 1.25.3",
+        &format!("OpenResty version: {}", version),
+        1,
+    )
+}
+
+/// Create a Hammerspoon framework definition template
+fn create_hammerspoon_template(version: &str, _lua_version: LuaVersion) -> String {
+    include_str!("hammerspoon/1.0.0.lua").replacen(
+        "Hammerspoon version: 1.0.0",
+        &format!("Hammerspoon version: {}", version),
+        1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The frameworks detected in a temporary directory holding the given files.
+    fn detect(files: &[(&str, &str)]) -> Vec<(String, Option<String>)> {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, content) in files {
+            let path = dir.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        FrameworkRegistry::new().detect_framework_usage(dir.path())
+    }
+
+    #[test]
+    fn dependencies_are_applied_with_the_framework() {
+        let dir = tempfile::tempdir().unwrap();
+        let base_definition = dir.path().join("base.lua");
+        let plugin_definition = dir.path().join("plugin.lua");
+        fs::write(&base_definition, "function base_greet(name) end\n").unwrap();
+        fs::write(&plugin_definition, "function plugin_run(command) end\n").unwrap();
+
+        let mut registry = FrameworkRegistry::new();
+        let mut base = FrameworkVersion::new("base", "1.0", LuaVersion::Lua54);
+        base.definition_path = Some(base_definition);
+        registry.register_framework(base);
+        let mut plugin = FrameworkVersion::new("plugin", "1.0", LuaVersion::Lua54)
+            .with_dependencies(vec!["base"]);
+        plugin.definition_path = Some(plugin_definition);
+        registry.register_framework(plugin);
+
+        let mut context = ProjectContext::new();
+        assert!(registry.apply_framework_to_context(&mut context, "plugin", "1.0"));
+        let signatures = &context.type_registry.function_signatures;
+        assert!(signatures.contains_key("base_greet"));
+        assert!(signatures.contains_key("plugin_run"));
+    }
+
+    #[test]
+    fn roblox_is_detected_from_its_project_file() {
+        let detected = detect(&[("default.project.json", "{ \"name\": \"game\" }")]);
+        assert_eq!(detected, [("roblox".to_string(), Some("1.0".to_string()))]);
+    }
+
+    #[test]
+    fn openresty_is_detected_from_ngx_calls() {
+        let detected = detect(&[("app/handler.lua", "ngx.say(\"hello\")\n")]);
+        assert_eq!(detected, [("openresty".to_string(), Some("1.25.3".to_string()))]);
+    }
+
+    #[test]
+    fn hammerspoon_is_detected_from_hotkey_bindings() {
+        let source = "hs.hotkey.bind({ \"cmd\" }, \"R\", function() hs.reload() end)\n";
+        let detected = detect(&[("config/bindings.lua", source)]);
+        assert_eq!(detected, [("hammerspoon".to_string(), Some("1.0.0".to_string()))]);
+    }
+
+    #[test]
+    fn neovim_version_comes_from_has_guards() {
+        let source = "if vim.fn.has('nvim-0.9') == 1 then\n  vim.cmd('echo 1')\nend\n";
+        let detected = detect(&[("lua/plugin/init.lua", source)]);
+        assert_eq!(detected, [("neovim".to_string(), Some("0.9.0".to_string()))]);
+    }
+
+    #[test]
+    fn requested_versions_resolve_to_the_nearest_definition() {
+        let registry = FrameworkRegistry::new();
+        let resolve = |name: &str, requested: &str| {
+            registry.resolve_version(name, requested).map(|framework| framework.version.as_str())
+        };
+        assert_eq!(resolve("neovim", "0.10.4"), Some("0.10.0"));
+        assert_eq!(resolve("neovim", "0.11"), Some("0.11.0"));
+        // Below every definition, the oldest one is used
+        assert_eq!(resolve("neovim", "0.7"), Some("0.8.0"));
+        assert_eq!(resolve("wezterm", "20240101"), Some("20230712"));
+        assert_eq!(resolve("love2d", "11.5.1"), Some("11.5"));
+        assert_eq!(resolve("unknown", "1.0"), None);
+    }
+
+    #[test]
+    fn neovim_defines_the_vim_global() {
+        let globals = FrameworkRegistry::new().framework_globals("neovim", "0.10.0");
+        assert!(globals.contains(&"vim".to_string()), "{:?}", globals);
+        assert!(!globals.iter().any(|global| global.contains('.')), "{:?}", globals);
+    }
+
+    #[test]
+    fn wezterm_version_comes_from_the_apis_used() {
+        let source = "local wezterm = require('wezterm')\n\
+                      local config = wezterm.config_builder()\n\
+                      wezterm.on('gui-startup', function()\n\
+                        local tab, pane, window = wezterm.mux.spawn_window({})\n\
+                        window:gui_window():maximize()\n\
+                        return wezterm.gui.screens()\n\
+                      end)\n\
+                      return config\n";
+        let detected = detect(&[("wezterm.lua", source)]);
+        assert_eq!(detected, [("wezterm".to_string(), Some("20240222".to_string()))]);
+
+        // The config builder alone is available from the older release
+        let source = "local wezterm = require('wezterm')\nreturn wezterm.config_builder()\n";
+        let detected = detect(&[("wezterm.lua", source)]);
+        assert_eq!(detected, [("wezterm".to_string(), Some("20230712".to_string()))]);
+    }
+
+    #[test]
+    fn love_version_comes_from_conf_lua() {
+        let detect_love = |version: &str| {
+            let conf = format!("function love.conf(t)\n    t.version = \"{}\"\nend\n", version);
+            detect(&[("main.lua", "function love.draw() end\n"), ("conf.lua", &conf)])
+        };
+        assert_eq!(detect_love("11.6"), [("love2d".to_string(), Some("11.6".to_string()))]);
+        assert_eq!(detect_love("11.5"), [("love2d".to_string(), Some("11.5".to_string()))]);
+        // Releases newer than every definition use the newest one
+        assert_eq!(detect_love("12.0"), [("love2d".to_string(), Some("11.6".to_string()))]);
+    }
+
+    #[test]
+    fn neovim_definitions_cover_0_8_through_0_12_dev() {
+        let registry = FrameworkRegistry::new();
+        for version in ["0.8.0", "0.12.0-dev"] {
+            let mut context = ProjectContext::new();
+            let applied = registry.apply_framework_to_context(&mut context, "neovim", version);
+            assert!(applied, "{}", version);
+            let signatures = &context.type_registry.function_signatures;
+            assert!(signatures.contains_key("vim.cmd"), "{}", version);
+        }
+        // The nightly is only used when asked for
+        assert_eq!(registry.get_latest_version("neovim"), Some("0.11.0".to_string()));
+        assert_eq!(
+            registry.resolve_version("neovim", "0.12").map(|framework| framework.version.as_str()),
+            Some("0.12.0-dev")
+        );
+    }
+
+    #[test]
+    fn neovim_is_detected_from_the_luarc_library() {
+        let luarc = "{\n  \"workspace.library\": [\"$VIMRUNTIME/lua\", \"${3rd}/luv/library\"]\n}\n";
+        let detected = detect(&[(".luarc.json", luarc), ("plugin.lua", "return {}\n")]);
+        let names: Vec<&str> = detected.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["neovim"]);
+
+        let luarc = "{ \"workspace.library\": [\"${3rd}/luv/library\"] }";
+        assert!(detect(&[(".luarc.json", luarc), ("plugin.lua", "return {}\n")]).is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+--[[
+  OpenResty (ngx_lua) API Type Definitions
+
+  This file provides type definitions for the OpenResty ngx_lua API.
+  It enhances type checking and auto-completion for OpenResty web applications.
+  
+  OpenResty version: 1.25.3
+  lua_version = "LuaJIT" -- OpenResty runs on LuaJIT
+]]--
+
+local ngx = {}
+
+-- =====================
+-- Core Types
+-- =====================
+
+---@class Cosocket Non-blocking TCP socket (cosocket) object
+---@field connect fun(self: Cosocket, host: string, port: number): boolean, string Connect to a remote host
+---@field send fun(self: Cosocket, data: string): number, string Send data
+---@field receive fun(self: Cosocket, pattern: string): string, string Receive data
+---@field close fun(self: Cosocket): boolean, string Close the socket
+---@field settimeout fun(self: Cosocket, ms: number) Set the timeout for subsequent operations
+---@field setkeepalive fun(self: Cosocket, timeout: number, size: number): boolean, string Put the connection into the pool
+ngx.Cosocket = {}
+
+-- =====================
+-- Constants
+-- =====================
+
+ngx.OK = 0
+ngx.ERROR = -1
+ngx.HTTP_OK = 200
+ngx.HTTP_NOT_FOUND = 404
+ngx.HTTP_INTERNAL_SERVER_ERROR = 500
+
+-- =====================
+-- Output
+-- =====================
+
+--- Emit arguments to the response body followed by a newline
+---@param ... any Values to write
+ngx.say = function(...) end
+
+--- Emit arguments to the response body
+---@param ... any Values to write
+ngx.print = function(...) end
+
+--- Write a message to the nginx error log
+---@param level number Log level (e.g. ngx.ERR)
+---@param ... any Values to log
+ngx.log = function(level, ...) end
+
+--- Finish the current request with the given status
+---@param status number HTTP status code
+ngx.exit = function(status) end
+
+--- Issue an HTTP redirect
+---@param uri string Target URI
+---@param status number HTTP status code
+ngx.redirect = function(uri, status) end
+
+-- =====================
+-- Request API
+-- =====================
+
+ngx.req = {}
+
+--- Get the request method
+---@return string
+ngx.req.get_method = function() end
+
+--- Get the request headers
+---@param max_headers number Maximum number of headers to return
+---@return table
+ngx.req.get_headers = function(max_headers) end
+
+--- Get the URI query arguments
+---@param max_args number Maximum number of arguments to return
+---@return table
+ngx.req.get_uri_args = function(max_args) end
+
+--- Read the request body
+ngx.req.read_body = function() end
+
+--- Get the request body data read by ngx.req.read_body
+---@return string
+ngx.req.get_body_data = function() end
+
+-- =====================
+-- Cosockets and Timers
+-- =====================
+
+ngx.socket = {}
+
+--- Create a TCP cosocket
+---@return Cosocket
+ngx.socket.tcp = function() end
+
+ngx.timer = {}
+
+--- Run a callback after a delay
+---@param delay number Delay in seconds
+---@param callback function The function to run
+---@return boolean
+ngx.timer.at = function(delay, callback) end
+
+--- Sleep without blocking the nginx worker
+---@param seconds number Time to sleep
+ngx.sleep = function(seconds) end
+
+--- Get the current time stamp from the nginx cache
+---@return number
+ngx.now = function() end
+
+return ngx
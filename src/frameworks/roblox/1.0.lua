@@ -0,0 +1,106 @@
+--[[
+  Roblox Engine API Type Definitions
+
+  This file provides type definitions for the Roblox engine API.
+  It enhances type checking and auto-completion for Roblox scripts.
+  
+  Roblox version: 1.0
+  lua_version = "5.1" -- Roblox uses Luau, which is derived from Lua 5.1
+]]--
+
+-- =====================
+-- Core Types
+-- =====================
+
+---@class Instance Base class of every object in the Roblox data model
+---@field Name string The name of the instance
+---@field ClassName string The class of the instance
+---@field Parent Instance The parent of the instance
+Instance = {}
+
+---@class DataModel The root of the data model (the `game` global)
+---@field Workspace Workspace The workspace service
+game = {}
+
+---@class Workspace Service holding all objects rendered in the 3D world
+---@field Gravity number The world gravity
+---@field CurrentCamera Instance The camera used by the local client
+workspace = {}
+
+---@class LuaSourceContainer The script currently running
+---@field Parent Instance The instance containing the script
+script = {}
+
+---@class Vector3 A three-dimensional vector
+---@field X number The x component
+---@field Y number The y component
+---@field Z number The z component
+Vector3 = {}
+
+---@class CFrame A coordinate frame (position and orientation)
+---@field Position Vector3 The position component
+CFrame = {}
+
+-- =====================
+-- Constructors
+-- =====================
+
+--- Create a new instance of the given class
+---@param className string The class to create
+---@param parent Instance The parent of the new instance
+---@return Instance
+Instance.new = function(className, parent) end
+
+--- Create a new vector
+---@param x number The x component
+---@param y number The y component
+---@param z number The z component
+---@return Vector3
+Vector3.new = function(x, y, z) end
+
+--- Create a new coordinate frame at a position
+---@param x number The x position
+---@param y number The y position
+---@param z number The z position
+---@return CFrame
+CFrame.new = function(x, y, z) end
+
+-- =====================
+-- Services
+-- =====================
+
+--- Get a service by class name (e.g. "Players", "RunService")
+---@param className string The service class name
+---@return Instance
+game.GetService = function(self, className) end
+
+--- Find the first child with the given name
+---@param name string The child name
+---@return Instance
+game.FindFirstChild = function(self, name) end
+
+--- Yield until a child with the given name exists
+---@param name string The child name
+---@param timeout number Maximum time to wait in seconds
+---@return Instance
+game.WaitForChild = function(self, name, timeout) end
+
+-- =====================
+-- Global Functions
+-- =====================
+
+--- Yield the current thread for the given number of seconds
+---@param seconds number The time to wait
+---@return number
+wait = function(seconds) end
+
+--- Print a warning to the output
+---@param message string The message to print
+warn = function(message) end
+
+--- Get the class name of a value
+---@param value any The value to inspect
+---@return string
+typeof = function(value) end
+
+return game
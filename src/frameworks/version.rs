@@ -0,0 +1,138 @@
+// src/frameworks/version.rs
+//
+// Ordered version numbers for framework definitions.
+
+use std::cmp::Ordering;
+
+/// A parsed framework version that can be ordered.
+///
+/// Date-based releases and dotted versions are kept distinct rather than guessed
+/// from the string length; a dotted version always sorts before a date-based one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameworkVersionNumber {
+    /// Date-based release in `YYYYMMDD` form (e.g. WezTerm's `20240222`)
+    Date(u32),
+    /// Dotted numeric version with an optional prerelease tag (e.g. `0.10.0`, `1.0-beta`)
+    Semver {
+        /// Numeric components with trailing zeros removed, so `1.2` equals `1.2.0`
+        components: Vec<u64>,
+        /// Prerelease tag following a `-`, if any
+        prerelease: Option<String>,
+    },
+}
+
+impl FrameworkVersionNumber {
+    /// Parse a version string, returning None if it is neither a date nor a dotted version
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.trim();
+
+        // Date-based versions: exactly eight digits
+        if version.len() == 8 && version.chars().all(|c| c.is_ascii_digit()) {
+            return version.parse().ok().map(FrameworkVersionNumber::Date);
+        }
+
+        // Dotted versions, optionally prefixed with `v` and suffixed with `-tag`
+        let version = version.trim_start_matches('v');
+        let (numbers, prerelease) = match version.split_once('-') {
+            Some((numbers, tag)) if !tag.is_empty() => (numbers, Some(tag.to_lowercase())),
+            Some((numbers, _)) => (numbers, None),
+            None => (version, None),
+        };
+
+        let mut components = numbers
+            .split('.')
+            .map(|part| part.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        // Normalize so that equal versions compare equal regardless of trailing zeros
+        while components.len() > 1 && components.last() == Some(&0) {
+            components.pop();
+        }
+
+        Some(FrameworkVersionNumber::Semver { components, prerelease })
+    }
+}
+
+/// Rank a prerelease tag: dev < alpha < beta < rc < any other tag
+fn prerelease_rank(tag: &str) -> u8 {
+    if tag.contains("dev") {
+        1
+    } else if tag.contains("alpha") {
+        2
+    } else if tag.contains("beta") {
+        3
+    } else if tag.contains("rc") {
+        4
+    } else {
+        5
+    }
+}
+
+impl Ord for FrameworkVersionNumber {
+    fn cmp(&self, other: &Self) -> Ordering {
+        use FrameworkVersionNumber::*;
+        match (self, other) {
+            (Date(a), Date(b)) => a.cmp(b),
+            (Semver { .. }, Date(_)) => Ordering::Less,
+            (Date(_), Semver { .. }) => Ordering::Greater,
+            (
+                Semver { components: c1, prerelease: p1 },
+                Semver { components: c2, prerelease: p2 },
+            ) => {
+                // Missing components count as zero (1.2 vs 1.2.1)
+                let len = c1.len().max(c2.len());
+                for i in 0..len {
+                    let n1 = c1.get(i).copied().unwrap_or(0);
+                    let n2 = c2.get(i).copied().unwrap_or(0);
+                    match n1.cmp(&n2) {
+                        Ordering::Equal => continue,
+                        ordering => return ordering,
+                    }
+                }
+
+                // A release is newer than any of its prereleases (1.0-beta < 1.0)
+                match (p1, p2) {
+                    (None, None) => Ordering::Equal,
+                    (None, Some(_)) => Ordering::Greater,
+                    (Some(_), None) => Ordering::Less,
+                    (Some(t1), Some(t2)) => prerelease_rank(t1)
+                        .cmp(&prerelease_rank(t2))
+                        .then_with(|| t1.cmp(t2)),
+                }
+            }
+        }
+    }
+}
+
+impl PartialOrd for FrameworkVersionNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(text: &str) -> FrameworkVersionNumber {
+        FrameworkVersionNumber::parse(text).unwrap()
+    }
+
+    #[test]
+    fn ordering() {
+        let cases = [
+            ("0.9.0", "0.10.0", Ordering::Less),
+            ("20230712", "20240222", Ordering::Less),
+            ("0.12.0", "20230712", Ordering::Less),
+            ("1.0-beta", "1.0", Ordering::Less),
+            ("1.0-alpha", "1.0-beta", Ordering::Less),
+            ("1.2", "1.2.0", Ordering::Equal),
+            ("v1.2.1", "1.2", Ordering::Greater),
+        ];
+        for (a, b, expected) in cases {
+            assert_eq!(version(a).cmp(&version(b)), expected, "{} vs {}", a, b);
+        }
+        assert_eq!(version("1.2"), version("1.2.0"));
+        assert_eq!(FrameworkVersionNumber::parse("latest"), None);
+    }
+}
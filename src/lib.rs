@@ -1,6 +1,14 @@
+pub mod analysis;
 pub mod annotator;
+pub mod config;
+pub mod diagnostics;
+pub mod error;
 pub mod frameworks;
+pub mod log;
 pub mod parser;
 pub mod project_context;
 pub mod tokenizer;
 pub mod type_inference;
+
+pub use analysis::{analyze_file, analyze_str, Analysis, Symbol, SymbolKind};
+pub use error::LuaToolsError;
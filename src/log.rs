@@ -0,0 +1,56 @@
+// src/log.rs
+//
+// Progress and detection messages, written to stderr so they never mix with
+// generated output on stdout.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much the library reports while it works.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Nothing is reported
+    Quiet,
+    /// Problems such as framework definitions that could not be applied
+    Normal,
+    /// Also detection and loading details (Lua version, frameworks, type files)
+    Verbose,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+/// Set the verbosity for the whole process.
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+/// The current verbosity.
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        _ => Verbosity::Verbose,
+    }
+}
+
+/// Write a message to stderr if the current verbosity is at least `level`.
+pub fn write(level: Verbosity, message: std::fmt::Arguments) {
+    if verbosity() >= level {
+        eprintln!("{}", message);
+    }
+}
+
+/// Report something the user should know about unless running quietly.
+#[macro_export]
+macro_rules! log_message {
+    ($($arg:tt)*) => {
+        $crate::log::write($crate::log::Verbosity::Normal, format_args!($($arg)*))
+    };
+}
+
+/// Report a detail that is only shown with verbose output.
+#[macro_export]
+macro_rules! log_verbose {
+    ($($arg:tt)*) => {
+        $crate::log::write($crate::log::Verbosity::Verbose, format_args!($($arg)*))
+    };
+}
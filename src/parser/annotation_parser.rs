@@ -1,45 +1,61 @@
 // src/parser/annotation_parser.rs
 
 use crate::parser::ast::AnnotationASTNode;
-use crate::tokenizer::token::{AnnotationSubToken, Token};
+use crate::parser::lua_type::LuaType;
+use crate::parser::parser_helpers;
+use crate::tokenizer::annotation_tokenizer::parse_annotation_subtokens;
+use crate::tokenizer::token::{AnnotationSubToken, Span, Token};
 
-pub struct AnnotationParser {
-    tokens: Vec<Token>,
+pub struct AnnotationParser<'a> {
+    tokens: &'a [Token],
     pos: usize,
 }
 
-impl AnnotationParser {
-    pub fn new(tokens: Vec<Token>) -> Self {
+impl<'a> AnnotationParser<'a> {
+    pub fn new(tokens: &'a [Token]) -> Self {
         Self { tokens, pos: 0 }
     }
 
     /// Iterates over the unified token stream and processes tokens of variant Annotation,
     /// returning a vector of parsed AnnotationASTNodes.
     pub fn parse(&mut self) -> Vec<AnnotationASTNode> {
+        self.parse_with_spans()
+            .into_iter()
+            .map(|(annotation, _)| annotation)
+            .collect()
+    }
+
+    /// Like `parse`, but pairs each annotation with the span of its token.
+    pub fn parse_with_spans(&mut self) -> Vec<(AnnotationASTNode, Span)> {
         let mut annotations = Vec::new();
-        while self.pos < self.tokens.len() {
-            if let Some(token) = self.peek() {
-                match token {
-                    Token::Annotation(subtokens, _) => {
-                        if let Some(ann) = self.parse_annotation_token(subtokens.clone()) {
-                            annotations.push(ann);
-                        }
-                        self.advance();
-                    }
-                    _ => {
+        while let Some(token) = self.peek() {
+            if let Token::Annotation(subtokens, span) = token {
+                // `---| value` lines continue the variants of the `---@alias` above them.
+                if let (
+                    Some((AnnotationSubToken::Prefix(prefix), rest)),
+                    Some((AnnotationASTNode::Alias { variants, .. }, _)),
+                ) = (subtokens.split_first(), annotations.last_mut())
+                {
+                    if prefix == "---|" {
+                        variants.extend(self.parse_variants(rest, &mut 0));
                         self.advance();
+                        continue;
                     }
                 }
+                if let Some(ann) = self.parse_annotation_token(subtokens) {
+                    annotations.push((ann, span.clone()));
+                }
             }
+            self.advance();
         }
         annotations
     }
 
-    fn peek(&self) -> Option<&Token> {
+    fn peek(&self) -> Option<&'a Token> {
         self.tokens.get(self.pos)
     }
 
-    fn advance(&mut self) -> Option<&Token> {
+    fn advance(&mut self) -> Option<&'a Token> {
         let tok = self.tokens.get(self.pos);
         self.pos += 1;
         tok
@@ -48,49 +64,46 @@ impl AnnotationParser {
     /// Parses an annotation token (given its AnnotationSubToken vector) into an AnnotationASTNode.
     fn parse_annotation_token(
         &self,
-        subtokens: Vec<AnnotationSubToken>,
+        subtokens: &[AnnotationSubToken],
     ) -> Option<AnnotationASTNode> {
-        // Create a mutable local copy of the subtokens for parsing.
-        let mut tokens = subtokens;
-        // If the first token is a prefix, remove it.
-        if let Some(token) = tokens.get(0) {
-            if let AnnotationSubToken::Prefix(_) = token {
-                tokens.remove(0);
-            }
-        }
+        // If the first token is a prefix, skip it.
+        let tokens = match subtokens.split_first() {
+            Some((AnnotationSubToken::Prefix(_), rest)) => rest,
+            _ => subtokens,
+        };
         // Expect the first token to be an Identifier representing the keyword.
-        let keyword = match tokens.get(0) {
+        let keyword = match tokens.first() {
             Some(AnnotationSubToken::Identifier(parts)) => parts.join("."),
-            _ => return self.parse_generic(&tokens),
+            _ => return self.parse_other(tokens),
         };
 
         match keyword.as_str() {
-            "alias" => self.parse_alias(&tokens),
-            "as" => self.parse_as(&tokens),
-            "async" => self.parse_async(&tokens),
-            "cast" => self.parse_cast(&tokens),
-            "class" => self.parse_class(&tokens),
-            "deprecated" => self.parse_deprecated(&tokens),
-            "diagnostic" => self.parse_diagnostic(&tokens),
-            "enum" => self.parse_enum(&tokens),
-            "field" => self.parse_field(&tokens),
-            "generic" => self.parse_generic(&tokens),
-            "meta" => self.parse_meta(&tokens),
-            "module" => self.parse_module(&tokens),
-            "nodiscard" => self.parse_nondiscard(&tokens),
-            "operator" => self.parse_operator(&tokens),
-            "overload" => self.parse_overload(&tokens),
-            "package" => self.parse_package(&tokens),
-            "param" => self.parse_param(&tokens),
-            "private" => self.parse_private(&tokens),
-            "protected" => self.parse_protected(&tokens),
-            "return" => self.parse_return(&tokens),
-            "see" => self.parse_see(&tokens),
-            "source" => self.parse_source(&tokens),
-            "type" => self.parse_type(&tokens),
-            "vararg" => self.parse_vararg(&tokens),
-            "version" => self.parse_version(&tokens),
-            _ => self.parse_generic(&tokens),
+            "alias" => self.parse_alias(tokens),
+            "as" => self.parse_as(tokens),
+            "async" => self.parse_async(tokens),
+            "cast" => self.parse_cast(tokens),
+            "class" => self.parse_class(tokens),
+            "deprecated" => self.parse_deprecated(tokens),
+            "diagnostic" => self.parse_diagnostic(tokens),
+            "enum" => self.parse_enum(tokens),
+            "field" => self.parse_field(tokens),
+            "generic" => self.parse_generic(tokens),
+            "meta" => self.parse_meta(tokens),
+            "module" => self.parse_module(tokens),
+            "nodiscard" => self.parse_nondiscard(tokens),
+            "operator" => self.parse_operator(tokens),
+            "overload" => self.parse_overload(tokens),
+            "package" => self.parse_package(tokens),
+            "param" => self.parse_param(tokens),
+            "private" => self.parse_private(tokens),
+            "protected" => self.parse_protected(tokens),
+            "return" => self.parse_return(tokens),
+            "see" => self.parse_see(tokens),
+            "source" => self.parse_source(tokens),
+            "type" => self.parse_type(tokens),
+            "vararg" => self.parse_vararg(tokens),
+            "version" => self.parse_version(tokens),
+            _ => self.parse_other(tokens),
         }
     }
 
@@ -106,48 +119,14 @@ impl AnnotationParser {
         } else {
             return None;
         };
-        let mut variants = Vec::new();
-        while pos < tokens.len() {
-            match tokens.get(pos) {
-                Some(AnnotationSubToken::Operator(op)) if op == "|" => {
-                    pos += 1; // consume '|'
-                    let variant =
-                        if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-                            pos += 1;
-                            parts.join(".")
-                        } else if let Some(AnnotationSubToken::Text(text)) = tokens.get(pos) {
-                            pos += 1;
-                            text.clone()
-                        } else {
-                            "".to_string()
-                        };
-                    let mut desc = None;
-                    if let Some(AnnotationSubToken::Operator(op)) = tokens.get(pos) {
-                        if op == "#" {
-                            pos += 1;
-                            if let Some(AnnotationSubToken::Text(text)) = tokens.get(pos) {
-                                desc = Some(text.clone());
-                                pos += 1;
-                            }
-                        }
-                    }
-                    variants.push((variant, desc));
-                }
-                _ => break,
-            }
-        }
+        let variants = self.parse_variants(tokens, &mut pos);
         Some(AnnotationASTNode::Alias { name, variants })
     }
 
     fn parse_as(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
         let mut pos = 1; // skip "as"
-        if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-            Some(AnnotationASTNode::As {
-                target: parts.join("."),
-            })
-        } else {
-            None
-        }
+        let target = self.parse_type_text(tokens, &mut pos)?;
+        Some(AnnotationASTNode::As { target })
     }
 
     fn parse_async(&self, _tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
@@ -162,21 +141,36 @@ impl AnnotationParser {
         } else {
             return None;
         };
+        // Each cast is `+type`, `-type`, or a bare type. The sign arrives glued to the type
+        // as text (`+string`), so split it off and subtokenize the rest again.
+        let tokens: Vec<AnnotationSubToken> = tokens
+            .iter()
+            .flat_map(|token| match token {
+                AnnotationSubToken::Text(text)
+                    if text.len() > 1 && (text.starts_with('+') || text.starts_with('-')) =>
+                {
+                    let mut split = vec![AnnotationSubToken::Text(text[..1].to_string())];
+                    split.extend(parse_annotation_subtokens(&text[1..]));
+                    split
+                }
+                other => vec![other.clone()],
+            })
+            .collect();
         let mut casts = Vec::new();
         while pos < tokens.len() {
-            if let Some(AnnotationSubToken::Operator(op)) = tokens.get(pos) {
-                if op == "+" || op == "-" {
-                    let add = op == "+";
-                    pos += 1;
-                    if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-                        casts.push((parts.join("."), add));
-                        pos += 1;
-                    }
-                } else if op == "," {
+            let add = match tokens.get(pos) {
+                Some(AnnotationSubToken::Text(sign)) if sign == "+" || sign == "-" => {
                     pos += 1;
-                } else {
-                    break;
+                    sign == "+"
                 }
+                _ => true,
+            };
+            match self.parse_type_text(&tokens, &mut pos) {
+                Some(type_field) => casts.push((type_field, add)),
+                None => break,
+            }
+            if let Some(AnnotationSubToken::Comma) = tokens.get(pos) {
+                pos += 1;
             } else {
                 break;
             }
@@ -184,71 +178,76 @@ impl AnnotationParser {
         Some(AnnotationASTNode::Cast { variable, casts })
     }
 
-    // --- Generic Annotation Parser ---
-    fn parse_generic(&mut self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
-        // Consume the keyword if not already consumed.
-        let keyword = if let Some(AnnotationSubToken::Identifier(parts)) = self.advance() {
-            parts.join(".")
-        } else {
-            return None;
-        };
-        let mut content = String::new();
-        while let Some(tok) = self.peek() {
-            if let AnnotationSubToken::Text(text) = tok {
-                content.push_str(text);
-                content.push(' ');
-                self.advance();
+    fn parse_generic(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
+        let mut pos = 1; // skip "generic"
+        let mut params = Vec::new();
+        while let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
+            let name = parts.join(".");
+            pos += 1;
+            let constraint = if let Some(AnnotationSubToken::Colon) = tokens.get(pos) {
+                pos += 1;
+                self.parse_type_text(tokens, &mut pos)
+            } else {
+                None
+            };
+            params.push((name, constraint));
+            if let Some(AnnotationSubToken::Comma) = tokens.get(pos) {
+                pos += 1;
             } else {
                 break;
             }
         }
-        Some(AnnotationASTNode::Generic {
-            keyword,
-            content: content.trim().to_string(),
-        })
+        if params.is_empty() {
+            return None;
+        }
+        Some(AnnotationASTNode::Generic { params })
+    }
+
+    // --- Fallback Parser for Unrecognized Annotations ---
+    fn parse_other(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
+        // The keyword is the first subtoken.
+        let keyword = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.first() {
+            parts.join(".")
+        } else {
+            return None;
+        };
+        let content = self.parse_description(tokens, 1).unwrap_or_default();
+        Some(AnnotationASTNode::Other { keyword, content })
     }
 
     fn parse_class(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
         let mut pos = 1; // skip "class"
+                         // `---@class (exact) Name`
+        let mut exact = false;
+        if let Some(AnnotationSubToken::OpenParen) = tokens.get(pos) {
+            pos += 1;
+            if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
+                if parts.join(".").to_lowercase() == "exact" {
+                    exact = true;
+                }
+                pos += 1;
+            }
+            if let Some(AnnotationSubToken::CloseParen) = tokens.get(pos) {
+                pos += 1;
+            }
+        }
         let name = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
             pos += 1;
             parts.join(".")
         } else {
             return None;
         };
+        // `---@class Name : Parent, Other`
         let mut parents = Vec::new();
-        if let Some(AnnotationSubToken::Operator(op)) = tokens.get(pos) {
-            if op == ":" {
-                pos += 1;
-                while let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-                    parents.push(parts.join("."));
-                    pos += 1;
-                    if let Some(AnnotationSubToken::Operator(op)) = tokens.get(pos) {
-                        if op == "," {
-                            pos += 1;
-                        } else {
-                            break;
-                        }
-                    } else {
-                        break;
-                    }
-                }
-            }
-        }
-        let mut exact = false;
-        if let Some(AnnotationSubToken::Operator(op)) = tokens.get(pos) {
-            if op == "(" {
+        if let Some(AnnotationSubToken::Colon) = tokens.get(pos) {
+            pos += 1;
+            while let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
+                parents.push(parts.join("."));
                 pos += 1;
-                if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-                    if parts.join(".").to_lowercase() == "exact" {
-                        exact = true;
-                    }
+                if let Some(AnnotationSubToken::Comma) = tokens.get(pos) {
                     pos += 1;
-                }
-                if let Some(AnnotationSubToken::Operator(op)) = tokens.get(pos) {
-                    if op == ")" {
-                        pos += 1;
-                    }
+                } else {
+                    break;
                 }
             }
         }
@@ -263,13 +262,14 @@ impl AnnotationParser {
                 let mut type_field = "any".to_string();
                 if let Some(AnnotationSubToken::Colon) = tokens.get(pos) {
                     pos += 1;
-                    if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-                        type_field = parts.join(".");
-                        pos += 1;
+                    if let Some(text) = self.parse_type_text(tokens, &mut pos) {
+                        type_field = text;
                     }
                 }
-                // For simplicity, we store the type field as a string.
-                fields.push((field_name, type_field));
+                let type_info = LuaType::parse(&type_field)
+                    .map(|lua_type| lua_type.to_type_info())
+                    .unwrap_or_else(|| parser_helpers::type_info_from_name(&type_field));
+                fields.push((field_name, type_info));
             } else {
                 break;
             }
@@ -286,93 +286,89 @@ impl AnnotationParser {
         Some(AnnotationASTNode::Deprecated)
     }
 
+    /// Parses `---@diagnostic <action>[: <name>, ...]`. Hyphenated words such as
+    /// `disable-next-line` arrive split into identifier and text subtokens, so they are
+    /// glued back together; several names are kept comma-separated.
     fn parse_diagnostic(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
-        let mut pos = 1; // skip "diagnostic"
-        let action = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-            pos += 1;
-            parts.join(".")
-        } else {
-            "".to_string()
-        };
-        let diagnostic = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-            pos += 1;
-            Some(parts.join("."))
-        } else {
+        let mut action = String::new();
+        let mut names: Vec<String> = Vec::new();
+        let mut after_colon = false;
+        for token in tokens.iter().skip(1) {
+            // skip "diagnostic"
+            match token {
+                AnnotationSubToken::Colon => {
+                    after_colon = true;
+                    names.push(String::new());
+                }
+                AnnotationSubToken::Comma if after_colon => names.push(String::new()),
+                AnnotationSubToken::Identifier(parts) => {
+                    let word = parts.join(".");
+                    match names.last_mut() {
+                        Some(name) => name.push_str(&word),
+                        None => action.push_str(&word),
+                    }
+                }
+                AnnotationSubToken::Text(text) => match names.last_mut() {
+                    Some(name) => name.push_str(text),
+                    None => action.push_str(text),
+                },
+                _ => {}
+            }
+        }
+        names.retain(|name| !name.is_empty());
+        let diagnostic = if names.is_empty() {
             None
+        } else {
+            Some(names.join(","))
         };
         Some(AnnotationASTNode::Diagnostic { action, diagnostic })
     }
 
     fn parse_enum(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
         let mut pos = 1; // skip "enum"
+                         // `---@enum (key) Name`
+        let mut key = false;
+        if let Some(AnnotationSubToken::OpenParen) = tokens.get(pos) {
+            pos += 1;
+            if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
+                key = parts.join(".").to_lowercase() == "key";
+                pos += 1;
+            }
+            if let Some(AnnotationSubToken::CloseParen) = tokens.get(pos) {
+                pos += 1;
+            }
+        }
         let name = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
             pos += 1;
             parts.join(".")
         } else {
             return None;
         };
-        let mut key = false;
-        if let Some(AnnotationSubToken::Operator(op)) = tokens.get(pos) {
-            if op.to_lowercase() == "(key)" {
-                key = true;
-                pos += 1;
-            }
-        }
-        let mut members = Vec::new();
-        while pos < tokens.len() {
-            if let Some(AnnotationSubToken::Operator(op)) = tokens.get(pos) {
-                if op == "|" {
-                    pos += 1;
-                    let member =
-                        if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-                            pos += 1;
-                            parts.join(".")
-                        } else {
-                            "".to_string()
-                        };
-                    let mut desc = None;
-                    if let Some(AnnotationSubToken::Operator(op)) = tokens.get(pos) {
-                        if op == "#" {
-                            pos += 1;
-                            if let Some(AnnotationSubToken::Text(text)) = tokens.get(pos) {
-                                desc = Some(text.clone());
-                                pos += 1;
-                            }
-                        }
-                    }
-                    members.push((member, desc));
-                } else {
-                    break;
-                }
-            } else {
-                break;
-            }
-        }
-        Some(AnnotationASTNode::Enum { name, key, members })
+        let members = self.parse_variants(tokens, &mut pos);
+        Some(AnnotationASTNode::Enum {
+            name,
+            key,
+            members,
+            values: Vec::new(),
+        })
     }
 
     fn parse_field(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
         let mut pos = 1; // skip "field"
-        let scope = if let Some(AnnotationSubToken::Operator(op)) = tokens.get(pos) {
-            if op == "[" {
+                         // An optional visibility precedes the name: `---@field private name type`.
+        let scope = match (tokens.get(pos), tokens.get(pos + 1)) {
+            (
+                Some(AnnotationSubToken::Identifier(parts)),
+                Some(AnnotationSubToken::Identifier(_)),
+            ) if matches!(
+                parts.join(".").as_str(),
+                "private" | "protected" | "public" | "package"
+            ) =>
+            {
                 pos += 1;
-                let s = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-                    pos += 1;
-                    Some(parts.join("."))
-                } else {
-                    None
-                };
-                if let Some(AnnotationSubToken::Operator(op)) = tokens.get(pos) {
-                    if op == "]" {
-                        pos += 1;
-                    }
-                }
-                s
-            } else {
-                None
+                Some(parts.join("."))
             }
-        } else {
-            None
+            _ => None,
         };
         let name = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
             pos += 1;
@@ -380,39 +376,22 @@ impl AnnotationParser {
         } else {
             return None;
         };
-        let type_field = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-            pos += 1;
-            parts.join(".")
-        } else {
-            "any".to_string()
-        };
-        let mut description = String::new();
-        while pos < tokens.len() {
-            if let AnnotationSubToken::Text(text) = &tokens[pos] {
-                description.push_str(text);
-                description.push(' ');
-                pos += 1;
-            } else {
-                break;
-            }
-        }
-        let description = if description.trim().is_empty() {
-            None
-        } else {
-            Some(description.trim().to_string())
-        };
+        let type_field = self
+            .parse_trailing_type_text(tokens, &mut pos)
+            .unwrap_or_else(|| "any".to_string());
+        let description = self.parse_description(tokens, pos);
         Some(AnnotationASTNode::Field {
             scope,
             name,
+            lua_type: LuaType::parse(&type_field),
             type_field,
             description,
         })
     }
 
     fn parse_meta(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
-        let mut pos = 1; // skip "meta"
+        let pos = 1; // skip "meta"
         let name = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-            pos += 1;
             Some(parts.join("."))
         } else {
             None
@@ -421,9 +400,8 @@ impl AnnotationParser {
     }
 
     fn parse_module(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
-        let mut pos = 1; // skip "module"
+        let pos = 1; // skip "module"
         if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-            pos += 1;
             Some(AnnotationASTNode::Module {
                 module_name: parts.join("."),
             })
@@ -440,13 +418,15 @@ impl AnnotationParser {
         let mut pos = 1; // skip "operator"
         if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
             pos += 1;
-            let operator = parts.join(".");
-            let signature = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
+            // The operand type stays with the operator: `add(Vector): Vector`
+            let mut operator = parts.join(".");
+            if let Some(AnnotationSubToken::OpenParen) = tokens.get(pos) {
+                operator.push_str(&self.collect_group(tokens, &mut pos));
+            }
+            if let Some(AnnotationSubToken::Colon) = tokens.get(pos) {
                 pos += 1;
-                Some(parts.join("."))
-            } else {
-                None
-            };
+            }
+            let signature = self.parse_type_text(tokens, &mut pos);
             Some(AnnotationASTNode::Operator {
                 operator,
                 signature,
@@ -458,14 +438,13 @@ impl AnnotationParser {
 
     fn parse_overload(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
         let mut pos = 1; // skip "overload"
-        if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-            pos += 1;
-            Some(AnnotationASTNode::Overload {
-                signature: parts.join("."),
-            })
-        } else {
-            None
-        }
+        let signature = self.parse_trailing_type_text(tokens, &mut pos)?;
+        let lua_type = LuaType::parse(&signature)
+            .filter(|lua_type| matches!(lua_type, LuaType::Function { .. }));
+        Some(AnnotationASTNode::Overload {
+            signature,
+            lua_type,
+        })
     }
 
     fn parse_package(&self, _tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
@@ -474,35 +453,27 @@ impl AnnotationParser {
 
     fn parse_param(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
         let mut pos = 1; // skip "param"
-        let name = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-            pos += 1;
-            parts.join(".")
-        } else {
-            return None;
+        let name = match tokens.get(pos) {
+            Some(AnnotationSubToken::Identifier(parts)) => parts.join("."),
+            // `...` or a named vararg such as `...args`
+            Some(AnnotationSubToken::Text(text)) if text.starts_with("...") => text.clone(),
+            _ => return None,
         };
-        let type_field = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
+        pos += 1;
+        // `---@param name? type` is shorthand for `---@param name type?`.
+        let optional_name =
+            matches!(tokens.get(pos), Some(AnnotationSubToken::Operator(op)) if op == "?");
+        if optional_name {
             pos += 1;
-            parts.join(".")
-        } else {
-            return None;
-        };
-        let mut description = String::new();
-        while pos < tokens.len() {
-            if let AnnotationSubToken::Text(text) = &tokens[pos] {
-                description.push_str(text);
-                description.push(' ');
-                pos += 1;
-            } else {
-                break;
-            }
         }
-        let description = if description.trim().is_empty() {
-            None
-        } else {
-            Some(description.trim().to_string())
-        };
+        let mut type_field = self.parse_type_text(tokens, &mut pos)?;
+        if optional_name && !type_field.ends_with('?') {
+            type_field.push('?');
+        }
+        let description = self.parse_description(tokens, pos);
         Some(AnnotationASTNode::Param {
             name,
+            lua_type: LuaType::parse(&type_field),
             type_field,
             description,
         })
@@ -518,33 +489,30 @@ impl AnnotationParser {
 
     fn parse_return(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
         let mut pos = 1; // skip "return"
-        let type_field = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-            pos += 1;
-            parts.join(".")
-        } else {
-            return None;
-        };
-        let mut name = None;
-        if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-            name = Some(parts.join("."));
+
+        // Several values may share one annotation: `---@return string, number`.
+        let mut type_field = self.parse_type_text(tokens, &mut pos)?;
+        while let Some(AnnotationSubToken::Comma) = tokens.get(pos) {
             pos += 1;
+            type_field.push_str(", ");
+            type_field.push_str(&self.parse_type_text(tokens, &mut pos)?);
         }
-        let mut description = String::new();
-        while pos < tokens.len() {
-            if let AnnotationSubToken::Text(text) = &tokens[pos] {
-                description.push_str(text);
-                description.push(' ');
+        // The name may be `...` to mark the values as variadic (`---@return string ...`).
+        let mut name = None;
+        match tokens.get(pos) {
+            Some(AnnotationSubToken::Identifier(parts)) => {
+                name = Some(parts.join("."));
+                pos += 1;
+            }
+            Some(AnnotationSubToken::Text(text)) if text == "..." => {
+                name = Some(text.clone());
                 pos += 1;
-            } else {
-                break;
             }
+            _ => {}
         }
-        let description = if description.trim().is_empty() {
-            None
-        } else {
-            Some(description.trim().to_string())
-        };
+        let description = self.parse_description(tokens, pos);
         Some(AnnotationASTNode::Return {
+            lua_type: LuaType::parse(&type_field),
             type_field,
             name,
             description,
@@ -552,9 +520,8 @@ impl AnnotationParser {
     }
 
     fn parse_see(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
-        let mut pos = 1; // skip "see"
+        let pos = 1; // skip "see"
         if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-            pos += 1;
             Some(AnnotationASTNode::See {
                 reference: parts.join("."),
             })
@@ -564,55 +531,382 @@ impl AnnotationParser {
     }
 
     fn parse_source(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
-        let mut pos = 1; // skip "source"
-        if let Some(AnnotationSubToken::Text(text)) = tokens.get(pos) {
-            pos += 1;
-            Some(AnnotationASTNode::Source { path: text.clone() })
-        } else {
+        // A path such as `lib/file.lua:10` spans several subtokens.
+        let path: String = tokens.iter().skip(1).map(subtoken_text).collect();
+        if path.is_empty() {
             None
+        } else {
+            Some(AnnotationASTNode::Source { path })
         }
     }
 
     fn parse_type(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
         let mut pos = 1; // skip "type"
-        if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-            pos += 1;
-            Some(AnnotationASTNode::Type {
-                type_field: parts.join("."),
-            })
-        } else {
-            None
-        }
+        let type_field = self.parse_type_text(tokens, &mut pos)?;
+        Some(AnnotationASTNode::Type { type_field })
     }
 
     fn parse_vararg(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
         let mut pos = 1; // skip "vararg"
-        let type_field = if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
-            pos += 1;
-            Some(parts.join("."))
-        } else {
-            None
-        };
+        let type_field = self.parse_type_text(tokens, &mut pos);
         Some(AnnotationASTNode::Vararg { type_field })
     }
 
     fn parse_version(&self, tokens: &[AnnotationSubToken]) -> Option<AnnotationASTNode> {
         let mut pos = 1; // skip "version"
-        let comparison = if let Some(AnnotationSubToken::Operator(op)) = tokens.get(pos) {
-            pos += 1;
-            Some(op.clone())
-        } else {
-            None
+        let comparison = match tokens.get(pos) {
+            Some(AnnotationSubToken::GreaterThan) => Some(">".to_string()),
+            Some(AnnotationSubToken::LessThan) => Some("<".to_string()),
+            Some(AnnotationSubToken::Operator(op)) => Some(op.clone()),
+            _ => None,
         };
-        if let Some(AnnotationSubToken::Identifier(parts)) = tokens.get(pos) {
+        if comparison.is_some() {
             pos += 1;
-            let version = parts.join(".");
-            Some(AnnotationASTNode::Version {
-                version,
-                comparison,
-            })
-        } else {
+        }
+        // Numeric versions such as `5.1` arrive as text, names such as `JIT` as identifiers.
+        let version = match tokens.get(pos) {
+            Some(AnnotationSubToken::Identifier(parts)) => parts.join("."),
+            Some(AnnotationSubToken::Text(text)) => text.clone(),
+            _ => return None,
+        };
+        Some(AnnotationASTNode::Version {
+            version,
+            comparison,
+        })
+    }
+
+    // --- Type Expressions ---
+
+    /// Reassembles the type expression starting at `pos` (e.g. `table<string, number>`,
+    /// `string[]`, `fun(x: number): boolean`, `string|nil`) from its subtokens, advancing
+    /// `pos` past it.
+    fn parse_type_text(&self, tokens: &[AnnotationSubToken], pos: &mut usize) -> Option<String> {
+        let mut text = self.parse_type_member(tokens, pos)?;
+        while let Some(AnnotationSubToken::Operator(op)) = tokens.get(*pos) {
+            if op != "|" {
+                break;
+            }
+            *pos += 1;
+            text.push('|');
+            text.push_str(&self.parse_type_member(tokens, pos)?);
+        }
+        Some(text)
+    }
+
+    /// Like `parse_type_text`, for a type that ends its annotation (a field's or an
+    /// overload's): a `fun(...)` type there takes every comma-separated return type after it,
+    /// as in `fun(path: string): boolean, string`.
+    fn parse_trailing_type_text(
+        &self,
+        tokens: &[AnnotationSubToken],
+        pos: &mut usize,
+    ) -> Option<String> {
+        let mut text = self.parse_type_text(tokens, pos)?;
+        let has_returns = matches!(
+            LuaType::parse(&text),
+            Some(LuaType::Function { returns, .. }) if !returns.is_empty()
+        );
+        if has_returns {
+            while let Some(AnnotationSubToken::Comma) = tokens.get(*pos) {
+                let mut next = *pos + 1;
+                let Some(returns) = self.parse_type_text(tokens, &mut next) else {
+                    break;
+                };
+                text.push_str(", ");
+                text.push_str(&returns);
+                *pos = next;
+            }
+        }
+        Some(text)
+    }
+
+    /// A single member of a union, including any `[]` and `?` suffixes.
+    fn parse_type_member(&self, tokens: &[AnnotationSubToken], pos: &mut usize) -> Option<String> {
+        let mut text = match tokens.get(*pos) {
+            Some(AnnotationSubToken::Identifier(parts)) => {
+                *pos += 1;
+                parts.join(".")
+            }
+            // Any number of values of any type (`---@return ...`)
+            Some(AnnotationSubToken::Text(text)) if text == "..." => {
+                *pos += 1;
+                text.clone()
+            }
+            // An inline table type: `{ timeout: number, retries: integer }`
+            Some(AnnotationSubToken::OpenBrace) => self.collect_group(tokens, pos),
+            _ => return None,
+        };
+        match tokens.get(*pos) {
+            Some(AnnotationSubToken::OpenParen) if text == "fun" => {
+                text.push_str(&self.collect_group(tokens, pos));
+                if let Some(AnnotationSubToken::Colon) = tokens.get(*pos) {
+                    *pos += 1;
+                    let returns = self.parse_type_text(tokens, pos)?;
+                    text.push_str(": ");
+                    text.push_str(&returns);
+                }
+            }
+            Some(AnnotationSubToken::LessThan) => text.push_str(&self.collect_group(tokens, pos)),
+            _ => {}
+        }
+        // Array suffixes arrive as a text subtoken such as `[]`.
+        if let Some(AnnotationSubToken::Text(suffix)) = tokens.get(*pos) {
+            if suffix.starts_with("[]") {
+                text.push_str(suffix);
+                *pos += 1;
+            }
+        }
+        if let Some(AnnotationSubToken::Operator(op)) = tokens.get(*pos) {
+            if op == "?" {
+                text.push('?');
+                *pos += 1;
+            }
+        }
+        Some(text)
+    }
+
+    /// Collects a bracketed group starting at `pos` (`(...)`, `<...>` or `{...}`) back into
+    /// text.
+    fn collect_group(&self, tokens: &[AnnotationSubToken], pos: &mut usize) -> String {
+        let mut text = String::new();
+        let mut depth = 0;
+        while let Some(token) = tokens.get(*pos) {
+            *pos += 1;
+            match token {
+                AnnotationSubToken::OpenParen
+                | AnnotationSubToken::LessThan
+                | AnnotationSubToken::OpenBrace => depth += 1,
+                AnnotationSubToken::CloseParen
+                | AnnotationSubToken::GreaterThan
+                | AnnotationSubToken::CloseBrace => depth -= 1,
+                _ => {}
+            }
+            match token {
+                AnnotationSubToken::OpenBrace => text.push_str("{ "),
+                AnnotationSubToken::CloseBrace if text.ends_with("{ ") => {
+                    text.pop();
+                    text.push('}');
+                }
+                AnnotationSubToken::CloseBrace => text.push_str(" }"),
+                AnnotationSubToken::Colon => text.push_str(": "),
+                AnnotationSubToken::Comma => text.push_str(", "),
+                other => text.push_str(&subtoken_text(other)),
+            }
+            if depth == 0 {
+                break;
+            }
+        }
+        text
+    }
+
+    /// Parse `| value # description` entries, as used by `---@alias` and `---@enum`.
+    fn parse_variants(
+        &self,
+        tokens: &[AnnotationSubToken],
+        pos: &mut usize,
+    ) -> Vec<(String, Option<String>)> {
+        // The first variant may omit its `|`: `---@alias Mode "a"|"b"`.
+        let mut variants = Vec::new();
+        while *pos < tokens.len() {
+            match tokens.get(*pos) {
+                Some(AnnotationSubToken::Operator(op)) if op == "|" => *pos += 1,
+                _ if variants.is_empty() => {}
+                _ => break,
+            }
+            let variant = match tokens.get(*pos) {
+                Some(
+                    token @ (AnnotationSubToken::Identifier(_)
+                    | AnnotationSubToken::StringLiteral(_)
+                    | AnnotationSubToken::NumberLiteral(_)
+                    | AnnotationSubToken::Text(_)),
+                ) => {
+                    *pos += 1;
+                    subtoken_text(token)
+                }
+                _ => "".to_string(),
+            };
+            // A description runs from `#` up to the next variant.
+            let mut desc = None;
+            if let Some(AnnotationSubToken::Operator(op)) = tokens.get(*pos) {
+                if op == "#" {
+                    let end = tokens[*pos..]
+                        .iter()
+                        .position(|t| matches!(t, AnnotationSubToken::Operator(op) if op == "|"))
+                        .map_or(tokens.len(), |offset| *pos + offset);
+                    desc = self.parse_description(&tokens[..end], *pos);
+                    *pos = end;
+                }
+            }
+            variants.push((variant, desc));
+        }
+        variants
+    }
+
+    /// The remaining subtokens from `pos` as free text, dropping a leading `#`.
+    fn parse_description(&self, tokens: &[AnnotationSubToken], pos: usize) -> Option<String> {
+        let mut rest = tokens.get(pos..).unwrap_or_default();
+        if let Some(AnnotationSubToken::Operator(op)) = rest.first() {
+            if op == "#" {
+                rest = &rest[1..];
+            }
+        }
+        let mut description = String::new();
+        for token in rest {
+            if !description.is_empty()
+                && !matches!(token, AnnotationSubToken::Colon | AnnotationSubToken::Comma)
+            {
+                description.push(' ');
+            }
+            description.push_str(&subtoken_text(token));
+        }
+        if description.is_empty() {
             None
+        } else {
+            Some(description)
+        }
+    }
+}
+
+/// The source text of a single annotation subtoken.
+fn subtoken_text(token: &AnnotationSubToken) -> String {
+    match token {
+        AnnotationSubToken::Identifier(parts) => parts.join("."),
+        AnnotationSubToken::Colon => ":".to_string(),
+        AnnotationSubToken::Comma => ",".to_string(),
+        AnnotationSubToken::LessThan => "<".to_string(),
+        AnnotationSubToken::GreaterThan => ">".to_string(),
+        AnnotationSubToken::OpenParen => "(".to_string(),
+        AnnotationSubToken::CloseParen => ")".to_string(),
+        AnnotationSubToken::OpenBrace => "{".to_string(),
+        AnnotationSubToken::CloseBrace => "}".to_string(),
+        AnnotationSubToken::StringLiteral(s) => format!("\"{}\"", s),
+        AnnotationSubToken::NumberLiteral(n) => n.clone(),
+        AnnotationSubToken::Operator(op) => op.clone(),
+        AnnotationSubToken::Text(text) => text.clone(),
+        AnnotationSubToken::Prefix(_) => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::pretty_print::emit_annotation;
+    use crate::tokenizer::CodeTokenizer;
+
+    fn parse(source: &str) -> Vec<AnnotationASTNode> {
+        AnnotationParser::new(&CodeTokenizer::new(source).tokenize()).parse()
+    }
+
+    fn named(name: &str) -> LuaType {
+        LuaType::Named(name.to_string())
+    }
+
+    #[test]
+    fn optional_and_union_types() {
+        match parse("---@param x integer?\n").as_slice() {
+            [AnnotationASTNode::Param { name, type_field, lua_type, .. }] => {
+                assert_eq!(name, "x");
+                assert_eq!(type_field, "integer?");
+                assert_eq!(lua_type, &Some(LuaType::Optional(Box::new(named("integer")))));
+            }
+            other => panic!("expected a param, got {:?}", other),
+        }
+        match parse("---@return string|nil\n").as_slice() {
+            [AnnotationASTNode::Return { type_field, lua_type, .. }] => {
+                assert_eq!(type_field, "string|nil");
+                assert_eq!(lua_type, &Some(LuaType::Union(vec![named("string"), named("nil")])));
+            }
+            other => panic!("expected a return, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn generic_parameters() {
+        let generic = |source: &str| match parse(source).as_slice() {
+            [AnnotationASTNode::Generic { params }] => params.clone(),
+            other => panic!("expected a generic, got {:?}", other),
+        };
+        assert_eq!(generic("---@generic T\n"), vec![("T".to_string(), None)]);
+        assert_eq!(
+            generic("---@generic T : Comparable\n"),
+            vec![("T".to_string(), Some("Comparable".to_string()))]
+        );
+        assert_eq!(
+            generic("---@generic K, V\n"),
+            vec![("K".to_string(), None), ("V".to_string(), None)]
+        );
+    }
+
+    #[test]
+    fn overload_signature() {
+        match parse("---@overload fun(x: number, y: string?): boolean\n").as_slice() {
+            [AnnotationASTNode::Overload { signature, lua_type }] => {
+                assert_eq!(signature, "fun(x: number, y: string?): boolean");
+                assert_eq!(
+                    lua_type,
+                    &Some(LuaType::Function {
+                        params: vec![
+                            ("x".to_string(), named("number")),
+                            ("y".to_string(), LuaType::Optional(Box::new(named("string")))),
+                        ],
+                        returns: vec![named("boolean")],
+                    })
+                );
+            }
+            other => panic!("expected an overload, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn varargs_round_trip() {
+        let source = "---@param ... string\n---@return ...\n";
+        let parsed = parse(source);
+        match &parsed[0] {
+            AnnotationASTNode::Param { name, type_field, .. } => {
+                assert_eq!(name, "...");
+                assert_eq!(type_field, "string");
+            }
+            other => panic!("expected a param, got {:?}", other),
+        }
+        match &parsed[1] {
+            AnnotationASTNode::Return { type_field, .. } => assert_eq!(type_field, "..."),
+            other => panic!("expected a return, got {:?}", other),
+        }
+        let emitted: Vec<String> = parsed.iter().map(emit_annotation).collect();
+        assert_eq!(emitted, ["---@param ... string", "---@return ..."]);
+    }
+
+    #[test]
+    fn function_field_type() {
+        match parse("---@field set_title fun(title: string)\n").as_slice() {
+            [AnnotationASTNode::Field { name, lua_type, .. }] => {
+                assert_eq!(name, "set_title");
+                assert_eq!(
+                    lua_type,
+                    &Some(LuaType::Function {
+                        params: vec![("title".to_string(), named("string"))],
+                        returns: vec![],
+                    })
+                );
+            }
+            other => panic!("expected a field, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn inline_table_type() {
+        match parse("---@param opts { timeout: number, retries: integer }\n").as_slice() {
+            [AnnotationASTNode::Param { name, lua_type, .. }] => {
+                assert_eq!(name, "opts");
+                assert_eq!(
+                    lua_type,
+                    &Some(LuaType::Struct(vec![
+                        ("timeout".to_string(), named("number")),
+                        ("retries".to_string(), named("integer")),
+                    ]))
+                );
+            }
+            other => panic!("expected a param, got {:?}", other),
         }
     }
 }
@@ -1,19 +1,156 @@
 // src/ast.rs
 
+use std::fmt;
+
+use crate::parser::lua_type::LuaType;
+use crate::parser::parser_helpers;
+use crate::project_context::ProjectContext;
+use crate::tokenizer::token::Span;
+
 /// Centralized type information for Lua values.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TypeInfo {
     Unknown,
+    Nil,
     String,
     Number,
+    /// A number known to be whole, `integer` (Lua 5.3+)
+    Integer,
     Boolean,
     Table,
     Function,
-    // Additional types (e.g. Union, Optional) can be added later.
+    /// A class or alias declared with annotations, kept by name
+    Named(String),
+    /// A table used as a list, `T[]`
+    Array(Box<TypeInfo>),
+    /// A table used as a map, `table<K, V>`
+    Dict(Box<TypeInfo>, Box<TypeInfo>),
+    /// Any one of several types, `A|B`
+    Union(Vec<TypeInfo>),
+    /// A literal value used as a type (e.g. `"fast"`), kept as source text
+    Literal(String),
+    /// A type or `nil`, `T?`
+    Optional(Box<TypeInfo>),
+}
+
+impl TypeInfo {
+    /// The type of a table constructor: `T[]` when it only has positional entries,
+    /// `table<string, T>` when it only has named ones, and plain `table` otherwise.
+    /// `T` is the entries' common type, or `any` when they differ.
+    pub fn table_of(exports: &[ExportItem], items: &[TypeInfo]) -> TypeInfo {
+        match (exports.is_empty(), items.is_empty()) {
+            (true, false) => TypeInfo::Array(Box::new(common_type(items.iter()))),
+            (false, true) => TypeInfo::Dict(
+                Box::new(TypeInfo::String),
+                Box::new(common_type(exports.iter().map(|export| &export.type_info))),
+            ),
+            _ => TypeInfo::Table,
+        }
+    }
+
+    /// Whether a value of this type may be stored where `other` is expected. The check is
+    /// conservative, so that it only rejects assignments that are certainly wrong:
+    ///
+    /// - `Unknown` (`any`) fits everywhere and accepts everything. A `nil` literal infers as
+    ///   `Unknown`, so it fits too; the declared `Nil` type only fits optionals.
+    /// - `integer` widens to `number`, and a `number` may hold an integer.
+    /// - `T?` is `T|nil`.
+    /// - A value fits a union that has a member it fits; a union fits where all of its
+    ///   members do.
+    /// - A class fits itself and its ancestors. Aliases and names the registry doesn't know
+    ///   are assumed to fit.
+    /// - A literal fits an equal literal and the type of its value. A value of that type
+    ///   may hold the literal, so it fits the literal type too.
+    /// - Any table (a class, `T[]`, `table<K, V>` or plain `table`) fits any table type, as
+    ///   their entries are not compared.
+    pub fn is_assignable_to(&self, other: &TypeInfo, ctx: &ProjectContext) -> bool {
+        let registry = &ctx.type_registry;
+        let is_class = |name: &str| {
+            registry
+                .custom_types
+                .get(name)
+                .is_some_and(|custom_type| !custom_type.is_alias)
+        };
+        let is_table = |type_info: &TypeInfo| match type_info {
+            TypeInfo::Table | TypeInfo::Array(_) | TypeInfo::Dict(..) => true,
+            TypeInfo::Named(name) => is_class(name),
+            _ => false,
+        };
+        match (self, other) {
+            _ if self == other => true,
+            (TypeInfo::Unknown, _) | (_, TypeInfo::Unknown) => true,
+            (TypeInfo::Named(name), _) | (_, TypeInfo::Named(name)) if !is_class(name) => true,
+            (TypeInfo::Integer, TypeInfo::Number) | (TypeInfo::Number, TypeInfo::Integer) => true,
+            (TypeInfo::Optional(inner), _) => {
+                TypeInfo::Union(vec![(**inner).clone(), TypeInfo::Nil]).is_assignable_to(other, ctx)
+            }
+            (_, TypeInfo::Optional(inner)) => {
+                self.is_assignable_to(&TypeInfo::Union(vec![(**inner).clone(), TypeInfo::Nil]), ctx)
+            }
+            // Split a union on the left first, so each of its members can then be matched
+            // against the right one: `string|number` fits `string|number|boolean`.
+            (TypeInfo::Union(members), _) => members
+                .iter()
+                .all(|member| member.is_assignable_to(other, ctx)),
+            (_, TypeInfo::Union(members)) => members
+                .iter()
+                .any(|member| self.is_assignable_to(member, ctx)),
+            (TypeInfo::Literal(_), TypeInfo::Literal(_)) => false,
+            (TypeInfo::Literal(text), _) => {
+                parser_helpers::literal_type(text).is_assignable_to(other, ctx)
+            }
+            (_, TypeInfo::Literal(text)) => {
+                self.is_assignable_to(&parser_helpers::literal_type(text), ctx)
+            }
+            (TypeInfo::Named(class), TypeInfo::Named(ancestor)) => {
+                registry.is_subclass(class, ancestor)
+            }
+            _ => is_table(self) && is_table(other),
+        }
+    }
+}
+
+/// The type shared by every element, or `Unknown` if they differ.
+fn common_type<'a>(mut types: impl Iterator<Item = &'a TypeInfo>) -> TypeInfo {
+    match types.next() {
+        Some(first) if types.all(|t| t == first) => first.clone(),
+        _ => TypeInfo::Unknown,
+    }
+}
+
+/// The annotation spelling of the type (`any` for `Unknown`).
+impl fmt::Display for TypeInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TypeInfo::Unknown => "any",
+            TypeInfo::Nil => "nil",
+            TypeInfo::String => "string",
+            TypeInfo::Number => "number",
+            TypeInfo::Integer => "integer",
+            TypeInfo::Boolean => "boolean",
+            TypeInfo::Table => "table",
+            TypeInfo::Function => "function",
+            TypeInfo::Named(name) => name,
+            TypeInfo::Array(element) => return write!(f, "{}[]", element),
+            TypeInfo::Dict(key, value) => return write!(f, "table<{}, {}>", key, value),
+            TypeInfo::Union(members) => {
+                let members: Vec<String> = members.iter().map(TypeInfo::to_string).collect();
+                return f.write_str(&members.join("|"));
+            }
+            TypeInfo::Literal(text) => text,
+            TypeInfo::Optional(inner) => match **inner {
+                TypeInfo::Union(_) => return write!(f, "({})?", inner),
+                _ => return write!(f, "{}?", inner),
+            },
+        };
+        f.write_str(name)
+    }
 }
 
 /// Represents an export item in a module.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExportItem {
     pub name: String,
     pub type_info: TypeInfo,
@@ -21,45 +158,127 @@ pub struct ExportItem {
 
 /// A simple expression node.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expression {
-    Identifier(String),
-    Literal(String), // For now, literals are represented as strings.
+    /// A name reference, with the span of its token.
+    Identifier(String, Span),
+    /// Literals are kept as source text (strings keep their quotes), with the span of
+    /// their token.
+    Literal(String, Span),
     FunctionCall {
         callee: String,
+        /// Span of the callee name.
+        callee_span: Span,
+        args: Vec<Expression>,
+        /// Whether arguments that are not simple expressions were left out of `args`.
+        skipped_args: bool,
+        /// From the callee through the closing parenthesis.
+        span: Span,
+    },
+    /// A method call, `receiver:method(args)`, which passes the receiver as `self`.
+    MethodCall {
+        receiver: Box<Expression>,
+        method: String,
         args: Vec<Expression>,
+        /// From the receiver through the closing parenthesis.
+        span: Span,
+    },
+    /// A binary operation such as `a + b`, `s .. "x"` or `a and b`.
+    BinaryOp {
+        op: String,
+        lhs: Box<Expression>,
+        rhs: Box<Expression>,
+    },
+    /// A unary operation such as `not a`.
+    UnaryOp {
+        op: String,
+        operand: Box<Expression>,
+        /// From the operator through the operand.
+        span: Span,
+    },
+    /// A table constructor: its `name = value` entries and its positional entries.
+    TableConstructor {
+        fields: Vec<(String, Expression)>,
+        items: Vec<Expression>,
+        /// From the opening brace through the closing one.
+        span: Span,
+    },
+    /// A field read: `table.name`, or `table[key]` with a computed key.
+    TableFieldAccess {
+        table: Box<Expression>,
+        key: FieldKey,
+        /// From the table through the field name or closing bracket.
+        span: Span,
+    },
+    /// An anonymous function, `function(params) ... end`.
+    Function {
+        params: Vec<(String, TypeInfo)>,
+        /// Span of each parameter name, in the same order as `params`.
+        param_spans: Vec<Span>,
+        body: Vec<CodeASTNode>,
+        /// From the `function` keyword through `end`.
+        span: Span,
     },
     // More expression types (e.g. binary operations) can be added here.
 }
 
+/// How a `TableFieldAccess` names its field.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FieldKey {
+    /// `table.name`
+    Name(String),
+    /// `table[expr]`
+    Index(Box<Expression>),
+}
+
 /// AST nodes for Lua code.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CodeASTNode {
     /// A module declaration (e.g. `local M = { ... }`).
     ModuleDeclaration {
         name: String,
+        /// The named entries of the table constructor.
         exports: Vec<ExportItem>,
+        /// Types of the table constructor's positional (array) entries.
+        items: Vec<TypeInfo>,
         /// Optional documentation comment attached to the module.
         doc: Option<String>,
         /// Annotations attached to the module.
         annotations: Vec<AnnotationASTNode>,
+        /// From the first token of the construct through its last.
+        span: Span,
     },
     /// A function definition.
     FunctionDef {
         name: String,
+        /// Whether the function was declared `local`.
+        is_local: bool,
         params: Vec<(String, TypeInfo)>,
+        /// Span of each parameter name, in the same order as `params`.
+        param_spans: Vec<Span>,
         return_types: Vec<TypeInfo>,
         /// Optional documentation comment.
         doc: Option<String>,
         /// Annotations (e.g. @param, @return) attached to the function.
         annotations: Vec<AnnotationASTNode>,
+        /// Span of each annotation, in the same order as `annotations`.
+        annotation_spans: Vec<Span>,
         body: Vec<CodeASTNode>,
+        /// From the `function` (or `local`) keyword through `end`.
+        span: Span,
     },
     /// A variable declaration.
     VariableDeclaration {
         name: String,
+        /// Span of the declared name.
+        name_span: Span,
         value: Option<Box<CodeASTNode>>,
         doc: Option<String>,
         annotations: Vec<AnnotationASTNode>,
+        /// From the `local` keyword through the end of the declaration.
+        span: Span,
     },
     /// A return statement.
     ReturnStatement(Vec<Expression>),
@@ -69,10 +288,14 @@ pub enum CodeASTNode {
     TableConstructor(Vec<(String, Expression)>),
     /// An assignment statement.
     Assignment {
-        lhs: Vec<String>, // multiple identifiers
+        /// The targets: plain or dotted names as `Identifier`, indexed fields (`t[k]`) as
+        /// `TableFieldAccess`.
+        lhs: Vec<Expression>,
         rhs: Vec<Expression>,
         doc: Option<String>,
         annotations: Vec<AnnotationASTNode>,
+        /// From the first token of the construct through its last.
+        span: Span,
     },
     /// An if statement.
     IfStatement {
@@ -81,6 +304,8 @@ pub enum CodeASTNode {
         else_block: Option<Vec<CodeASTNode>>,
         doc: Option<String>,
         annotations: Vec<AnnotationASTNode>,
+        /// From the first token of the construct through its last.
+        span: Span,
     },
     /// A while loop.
     WhileLoop {
@@ -88,22 +313,39 @@ pub enum CodeASTNode {
         body: Vec<CodeASTNode>,
         doc: Option<String>,
         annotations: Vec<AnnotationASTNode>,
+        /// From the first token of the construct through its last.
+        span: Span,
     },
     /// A numeric for loop.
     ForNumeric {
         var: String,
-        start: Expression,
-        end: Expression,
-        step: Option<Expression>,
+        start: Box<Expression>,
+        end: Box<Expression>,
+        step: Option<Box<Expression>>,
         body: Vec<CodeASTNode>,
         doc: Option<String>,
         annotations: Vec<AnnotationASTNode>,
+        /// From the first token of the construct through its last.
+        span: Span,
+    },
+    /// A generic for loop, `for k, v in pairs(t) do`.
+    ForGeneric {
+        vars: Vec<String>,
+        /// The expressions after `in`, usually a single iterator call
+        exprs: Vec<Expression>,
+        body: Vec<CodeASTNode>,
+        doc: Option<String>,
+        annotations: Vec<AnnotationASTNode>,
+        /// From the first token of the construct through its last.
+        span: Span,
     },
     /// A do block.
     DoBlock {
         body: Vec<CodeASTNode>,
         doc: Option<String>,
         annotations: Vec<AnnotationASTNode>,
+        /// From the first token of the construct through its last.
+        span: Span,
     },
     /// A repeat-until loop.
     RepeatUntil {
@@ -111,17 +353,116 @@ pub enum CodeASTNode {
         condition: Expression,
         doc: Option<String>,
         annotations: Vec<AnnotationASTNode>,
+        /// From the first token of the construct through its last.
+        span: Span,
     },
     /// A function call statement.
     FunctionCallStmt {
         call: Expression,
         doc: Option<String>,
         annotations: Vec<AnnotationASTNode>,
+        /// From the first token of the construct through its last.
+        span: Span,
     },
+    /// A `break` statement.
+    Break,
+    /// A `goto` statement, with the label it jumps to.
+    Goto(String),
+    /// A `::name::` label, the target of a `goto`.
+    Label(String),
+}
+
+impl Expression {
+    /// The source span of the expression. Enclosing parentheses are not included.
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::Identifier(_, span)
+            | Expression::Literal(_, span)
+            | Expression::FunctionCall { span, .. }
+            | Expression::MethodCall { span, .. }
+            | Expression::UnaryOp { span, .. }
+            | Expression::TableConstructor { span, .. }
+            | Expression::TableFieldAccess { span, .. }
+            | Expression::Function { span, .. } => span.clone(),
+            Expression::BinaryOp { lhs, rhs, .. } => lhs.span().through(&rhs.span()),
+        }
+    }
+}
+
+impl CodeASTNode {
+    /// The source span of the node, if it records one.
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            CodeASTNode::ModuleDeclaration { span, .. }
+            | CodeASTNode::FunctionDef { span, .. }
+            | CodeASTNode::VariableDeclaration { span, .. }
+            | CodeASTNode::Assignment { span, .. }
+            | CodeASTNode::IfStatement { span, .. }
+            | CodeASTNode::WhileLoop { span, .. }
+            | CodeASTNode::ForNumeric { span, .. }
+            | CodeASTNode::ForGeneric { span, .. }
+            | CodeASTNode::DoBlock { span, .. }
+            | CodeASTNode::RepeatUntil { span, .. }
+            | CodeASTNode::FunctionCallStmt { span, .. } => Some(span),
+            CodeASTNode::ReturnStatement(_)
+            | CodeASTNode::Comment(_)
+            | CodeASTNode::TableConstructor(_)
+            | CodeASTNode::Break
+            | CodeASTNode::Goto(_)
+            | CodeASTNode::Label(_) => None,
+        }
+    }
+
+    /// The annotations attached to the node; empty for nodes that can't carry any.
+    pub fn annotations(&self) -> &[AnnotationASTNode] {
+        match self {
+            CodeASTNode::ModuleDeclaration { annotations, .. }
+            | CodeASTNode::FunctionDef { annotations, .. }
+            | CodeASTNode::VariableDeclaration { annotations, .. }
+            | CodeASTNode::Assignment { annotations, .. }
+            | CodeASTNode::IfStatement { annotations, .. }
+            | CodeASTNode::WhileLoop { annotations, .. }
+            | CodeASTNode::ForNumeric { annotations, .. }
+            | CodeASTNode::ForGeneric { annotations, .. }
+            | CodeASTNode::DoBlock { annotations, .. }
+            | CodeASTNode::RepeatUntil { annotations, .. }
+            | CodeASTNode::FunctionCallStmt { annotations, .. } => annotations,
+            CodeASTNode::ReturnStatement(_)
+            | CodeASTNode::Comment(_)
+            | CodeASTNode::TableConstructor(_)
+            | CodeASTNode::Break
+            | CodeASTNode::Goto(_)
+            | CodeASTNode::Label(_) => &[],
+        }
+    }
+
+    /// The annotations attached to the node, if it can carry any.
+    pub fn annotations_mut(&mut self) -> Option<&mut Vec<AnnotationASTNode>> {
+        match self {
+            CodeASTNode::ModuleDeclaration { annotations, .. }
+            | CodeASTNode::FunctionDef { annotations, .. }
+            | CodeASTNode::VariableDeclaration { annotations, .. }
+            | CodeASTNode::Assignment { annotations, .. }
+            | CodeASTNode::IfStatement { annotations, .. }
+            | CodeASTNode::WhileLoop { annotations, .. }
+            | CodeASTNode::ForNumeric { annotations, .. }
+            | CodeASTNode::ForGeneric { annotations, .. }
+            | CodeASTNode::DoBlock { annotations, .. }
+            | CodeASTNode::RepeatUntil { annotations, .. }
+            | CodeASTNode::FunctionCallStmt { annotations, .. } => Some(annotations),
+            CodeASTNode::ReturnStatement(_)
+            | CodeASTNode::Comment(_)
+            | CodeASTNode::TableConstructor(_)
+            | CodeASTNode::Break
+            | CodeASTNode::Goto(_)
+            | CodeASTNode::Label(_) => None,
+        }
+    }
 }
 
 /// AST nodes for annotations.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AnnotationASTNode {
     Alias {
         name: String,
@@ -152,16 +493,21 @@ pub enum AnnotationASTNode {
         name: String,
         key: bool,
         members: Vec<(String, Option<String>)>,
+        /// The `Name = value` entries of the table the enum annotates, with the values kept
+        /// as source text (strings with their quotes).
+        values: Vec<(String, String)>,
     },
     Field {
         scope: Option<String>,
         name: String,
         type_field: String,
+        /// `type_field` parsed into a structured type, if it is well formed.
+        lua_type: Option<LuaType>,
         description: Option<String>,
     },
+    /// Type parameters with their optional constraints (`---@generic K, V : Comparable`).
     Generic {
-        keyword: String,
-        content: String,
+        params: Vec<(String, Option<String>)>,
     },
     Meta {
         name: Option<String>,
@@ -176,17 +522,23 @@ pub enum AnnotationASTNode {
     },
     Overload {
         signature: String,
+        /// `signature` parsed into a `LuaType::Function`, if it is well formed.
+        lua_type: Option<LuaType>,
     },
     Package,
     Param {
         name: String,
         type_field: String,
+        /// `type_field` parsed into a structured type, if it is well formed.
+        lua_type: Option<LuaType>,
         description: Option<String>,
     },
     Private,
     Protected,
     Return {
         type_field: String,
+        /// `type_field` parsed into a structured type, if it is well formed.
+        lua_type: Option<LuaType>,
         name: Option<String>,
         description: Option<String>,
     },
@@ -206,4 +558,114 @@ pub enum AnnotationASTNode {
         version: String,
         comparison: Option<String>,
     },
+    /// An annotation with an unrecognized keyword, kept as text.
+    Other {
+        keyword: String,
+        content: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_context::CustomType;
+    use std::collections::HashMap;
+
+    fn class(name: &str, parents: &[&str]) -> CustomType {
+        CustomType {
+            name: name.to_string(),
+            fields: Vec::new(),
+            methods: HashMap::new(),
+            description: None,
+            is_alias: false,
+            variants: Vec::new(),
+            parents: parents.iter().map(|parent| parent.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn assignability_rules() {
+        let mut ctx = ProjectContext::new();
+        for custom_type in [class("Animal", &[]), class("Dog", &["Animal"])] {
+            ctx.type_registry.custom_types.insert(custom_type.name.clone(), custom_type);
+        }
+        let named = |name: &str| TypeInfo::Named(name.to_string());
+        let optional = |inner: TypeInfo| TypeInfo::Optional(Box::new(inner));
+        let union = |members: &[TypeInfo]| TypeInfo::Union(members.to_vec());
+        let cases = [
+            // `any` is the top type both ways
+            (TypeInfo::String, TypeInfo::Unknown, true),
+            (TypeInfo::Unknown, TypeInfo::Number, true),
+            // nil only fits optionals
+            (TypeInfo::Nil, optional(TypeInfo::String), true),
+            (TypeInfo::Nil, union(&[TypeInfo::String, TypeInfo::Nil]), true),
+            (TypeInfo::Nil, TypeInfo::String, false),
+            (TypeInfo::String, optional(TypeInfo::String), true),
+            (optional(TypeInfo::String), TypeInfo::String, false),
+            (TypeInfo::Number, optional(TypeInfo::String), false),
+            // integer widens to number
+            (TypeInfo::Integer, TypeInfo::Number, true),
+            (TypeInfo::Integer, optional(TypeInfo::Number), true),
+            (TypeInfo::String, TypeInfo::Number, false),
+            // unions
+            (TypeInfo::String, union(&[TypeInfo::String, TypeInfo::Number]), true),
+            (TypeInfo::Boolean, union(&[TypeInfo::String, TypeInfo::Number]), false),
+            (
+                union(&[TypeInfo::String, TypeInfo::Number]),
+                union(&[TypeInfo::String, TypeInfo::Number, TypeInfo::Boolean]),
+                true,
+            ),
+            (
+                union(&[TypeInfo::String, TypeInfo::Boolean]),
+                union(&[TypeInfo::String, TypeInfo::Number]),
+                false,
+            ),
+            // classes and their ancestors
+            (named("Dog"), named("Animal"), true),
+            (named("Animal"), named("Dog"), false),
+            (named("Dog"), TypeInfo::Table, true),
+            (named("Dog"), TypeInfo::String, false),
+            (named("Unregistered"), TypeInfo::String, true),
+            // literals
+            (TypeInfo::Literal("\"fast\"".to_string()), TypeInfo::String, true),
+            (TypeInfo::Literal("1".to_string()), TypeInfo::String, false),
+        ];
+        for (value, target, expected) in cases {
+            assert_eq!(
+                value.is_assignable_to(&target, &ctx),
+                expected,
+                "{} assignable to {}",
+                value,
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn display_uses_annotation_spelling() {
+        let optional = TypeInfo::Union(vec![TypeInfo::String, TypeInfo::Nil]);
+        assert_eq!(optional.to_string(), "string|nil");
+        assert_eq!(TypeInfo::Integer.to_string(), "integer");
+        assert_eq!(TypeInfo::Optional(Box::new(TypeInfo::Number)).to_string(), "number?");
+        assert_eq!(TypeInfo::Optional(Box::new(optional)).to_string(), "(string|nil)?");
+        assert_eq!(TypeInfo::Array(Box::new(TypeInfo::Unknown)).to_string(), "any[]");
+    }
+
+    #[test]
+    fn table_constructor_types() {
+        use crate::parser::code_parser::CodeParser;
+        use crate::tokenizer::CodeTokenizer;
+
+        let table_type = |source: &str| {
+            match CodeParser::new(CodeTokenizer::new(source).tokenize()).parse().as_slice() {
+                [CodeASTNode::ModuleDeclaration { exports, items, .. }] => {
+                    TypeInfo::table_of(exports, items).to_string()
+                }
+                other => panic!("expected a table declaration, got {:?}", other),
+            }
+        };
+        assert_eq!(table_type("local list = { 1, 2, 3 }"), "number[]");
+        assert_eq!(table_type("local counts = { a = 1 }"), "table<string, number>");
+        assert_eq!(table_type("local mixed = { 1, a = 1 }"), "table");
+    }
 }
@@ -59,7 +59,12 @@ pub fn pretty_print_annotation_node(ann: &AnnotationASTNode, indent: usize) -> S
             let diag = diagnostic.clone().unwrap_or_default();
             format!("{}Diagnostic: {} - {}\n", indent_str, action, diag)
         }
-        AnnotationASTNode::Enum { name, key, members } => {
+        AnnotationASTNode::Enum {
+            name,
+            key,
+            members,
+            values,
+        } => {
             let mut s = format!(
                 "{}Enum: {}{}\n",
                 indent_str,
@@ -73,6 +78,9 @@ pub fn pretty_print_annotation_node(ann: &AnnotationASTNode, indent: usize) -> S
                 }
                 s.push('\n');
             }
+            for (member, value) in values {
+                s.push_str(&format!("{}  Value: {} = {}\n", indent_str, member, value));
+            }
             s
         }
         AnnotationASTNode::Field {
@@ -80,6 +88,7 @@ pub fn pretty_print_annotation_node(ann: &AnnotationASTNode, indent: usize) -> S
             name,
             type_field,
             description,
+            ..
         } => {
             let scope_str = scope.as_deref().unwrap_or("default");
             let mut s = format!(
@@ -92,8 +101,15 @@ pub fn pretty_print_annotation_node(ann: &AnnotationASTNode, indent: usize) -> S
             s.push('\n');
             s
         }
-        AnnotationASTNode::Generic { keyword, content } => {
-            format!("{}Generic: {} - {}\n", indent_str, keyword, content)
+        AnnotationASTNode::Generic { params } => {
+            let params: Vec<String> = params
+                .iter()
+                .map(|(name, constraint)| match constraint {
+                    Some(constraint) => format!("{} : {}", name, constraint),
+                    None => name.clone(),
+                })
+                .collect();
+            format!("{}Generic: {}\n", indent_str, params.join(", "))
         }
         AnnotationASTNode::Meta { name } => format!("{}Meta: {:?}\n", indent_str, name),
         AnnotationASTNode::Module { module_name } => {
@@ -107,7 +123,7 @@ pub fn pretty_print_annotation_node(ann: &AnnotationASTNode, indent: usize) -> S
             let sig = signature.clone().unwrap_or_default();
             format!("{}Operator: {} - {}\n", indent_str, operator, sig)
         }
-        AnnotationASTNode::Overload { signature } => {
+        AnnotationASTNode::Overload { signature, .. } => {
             format!("{}Overload: {}\n", indent_str, signature)
         }
         AnnotationASTNode::Package => format!("{}Package\n", indent_str),
@@ -115,6 +131,7 @@ pub fn pretty_print_annotation_node(ann: &AnnotationASTNode, indent: usize) -> S
             name,
             type_field,
             description,
+            ..
         } => {
             let mut s = format!("{}Param: {} : {}", indent_str, name, type_field);
             if let Some(desc) = description {
@@ -129,6 +146,7 @@ pub fn pretty_print_annotation_node(ann: &AnnotationASTNode, indent: usize) -> S
             type_field,
             name,
             description,
+            ..
         } => {
             let mut s = format!("{}Return: {}", indent_str, type_field);
             if let Some(n) = name {
@@ -154,6 +172,9 @@ pub fn pretty_print_annotation_node(ann: &AnnotationASTNode, indent: usize) -> S
             let comp = comparison.clone().unwrap_or_default();
             format!("{}Version: {} {}\n", indent_str, comp, version)
         }
+        AnnotationASTNode::Other { keyword, content } => {
+            format!("{}Other: {} - {}\n", indent_str, keyword, content)
+        }
     }
 }
 
@@ -20,6 +20,7 @@ fn pretty_print_code_node(node: &CodeASTNode, indent: usize) -> String {
             exports,
             doc,
             annotations,
+            ..
         } => {
             let mut s = format!("{}ModuleDeclaration: {}\n", indent_str, name);
             if let Some(d) = doc {
@@ -54,6 +55,7 @@ fn pretty_print_code_node(node: &CodeASTNode, indent: usize) -> String {
             doc,
             annotations,
             body,
+            ..
         } => {
             let mut s = format!("{}FunctionDef: {}\n", indent_str, name);
             if let Some(d) = doc {
@@ -95,6 +97,7 @@ fn pretty_print_code_node(node: &CodeASTNode, indent: usize) -> String {
             value,
             doc,
             annotations,
+            ..
         } => {
             let mut s = format!("{}VariableDeclaration: {}\n", indent_str, name);
             if let Some(d) = doc {
@@ -139,6 +142,7 @@ fn pretty_print_code_node(node: &CodeASTNode, indent: usize) -> String {
             rhs,
             doc,
             annotations,
+            ..
         } => {
             let mut s = format!("{}Assignment:\n", indent_str);
             s.push_str(&format!("{}  LHS: {:?}\n", indent_str, lhs));
@@ -165,6 +169,7 @@ fn pretty_print_code_node(node: &CodeASTNode, indent: usize) -> String {
             else_block,
             doc,
             annotations,
+            ..
         } => {
             let mut s = format!("{}IfStatement:\n", indent_str);
             s.push_str(&format!("{}  Condition: {:?}\n", indent_str, condition));
@@ -199,6 +204,7 @@ fn pretty_print_code_node(node: &CodeASTNode, indent: usize) -> String {
             body,
             doc,
             annotations,
+            ..
         } => {
             let mut s = format!("{}WhileLoop:\n", indent_str);
             s.push_str(&format!("{}  Condition: {:?}\n", indent_str, condition));
@@ -230,6 +236,7 @@ fn pretty_print_code_node(node: &CodeASTNode, indent: usize) -> String {
             body,
             doc,
             annotations,
+            ..
         } => {
             let mut s = format!("{}ForNumeric: {}\n", indent_str, var);
             s.push_str(&format!("{}  Start: {:?}\n", indent_str, start));
@@ -257,10 +264,43 @@ fn pretty_print_code_node(node: &CodeASTNode, indent: usize) -> String {
             }
             s
         }
+        CodeASTNode::ForGeneric {
+            vars,
+            exprs,
+            body,
+            doc,
+            annotations,
+            ..
+        } => {
+            let mut s = format!("{}ForGeneric: {}\n", indent_str, vars.join(", "));
+            for expr in exprs {
+                s.push_str(&format!("{}  In: {:?}\n", indent_str, expr));
+            }
+            if let Some(d) = doc {
+                s.push_str(&format!("{}  Doc: {}\n", indent_str, d));
+            }
+            if !annotations.is_empty() {
+                s.push_str(&format!("{}  Annotations:\n", indent_str));
+                for ann in annotations {
+                    s.push_str(
+                        &crate::parser::ast_annotations_printer::pretty_print_annotation_node(
+                            ann,
+                            indent + 2,
+                        ),
+                    );
+                }
+            }
+            s.push_str(&format!("{}  Body:\n", indent_str));
+            for node in body {
+                s.push_str(&pretty_print_code_node(node, indent + 2));
+            }
+            s
+        }
         CodeASTNode::DoBlock {
             body,
             doc,
             annotations,
+            ..
         } => {
             let mut s = format!("{}DoBlock:\n", indent_str);
             if let Some(d) = doc {
@@ -288,6 +328,7 @@ fn pretty_print_code_node(node: &CodeASTNode, indent: usize) -> String {
             condition,
             doc,
             annotations,
+            ..
         } => {
             let mut s = format!("{}RepeatUntil:\n", indent_str);
             if let Some(d) = doc {
@@ -315,6 +356,7 @@ fn pretty_print_code_node(node: &CodeASTNode, indent: usize) -> String {
             call,
             doc,
             annotations,
+            ..
         } => {
             let mut s = format!("{}FunctionCallStmt:\n", indent_str);
             s.push_str(&format!("{}  Call: {:?}\n", indent_str, call));
@@ -334,5 +376,8 @@ fn pretty_print_code_node(node: &CodeASTNode, indent: usize) -> String {
             }
             s
         }
+        CodeASTNode::Break => format!("{}Break\n", indent_str),
+        CodeASTNode::Goto(label) => format!("{}Goto: {}\n", indent_str, label),
+        CodeASTNode::Label(label) => format!("{}Label: {}\n", indent_str, label),
     }
 }
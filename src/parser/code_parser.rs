@@ -1,6 +1,9 @@
 // src/parser/code_parser.rs
 
-use crate::parser::ast::{CodeASTNode, ExportItem, Expression, TypeInfo};
+use crate::parser::annotation_parser::AnnotationParser;
+use crate::parser::ast::{
+    AnnotationASTNode, CodeASTNode, ExportItem, Expression, FieldKey, TypeInfo,
+};
 use crate::parser::parser_helpers;
 use crate::tokenizer::token::{Span, Token};
 
@@ -17,6 +20,9 @@ impl CodeParser {
     pub fn parse(&mut self) -> Vec<CodeASTNode> {
         let mut nodes = Vec::new();
         while self.pos < self.tokens.len() {
+            if self.eat_operator(";") {
+                continue;
+            }
             if let Some(node) = self.parse_node() {
                 nodes.push(node);
             } else {
@@ -36,46 +42,126 @@ impl CodeParser {
         tok
     }
 
-    /// Skip any annotation tokens, returning when the next token is a code token.
-    fn skip_annotation_tokens(&mut self) {
-        while let Some(token) = self.peek() {
-            if parser_helpers::extract_annotation_token(token).is_some() {
-                self.advance();
-            } else {
-                break;
-            }
+    /// Span from `start` through the last consumed token.
+    fn span_from(&self, start: &Span) -> Span {
+        match self.pos.checked_sub(1).and_then(|pos| self.tokens.get(pos)) {
+            Some(token) => start.through(token.span()),
+            None => start.clone(),
         }
     }
 
-    /// If the next token is a Comment, consume it and return its text.
-    fn parse_doc(&mut self) -> Option<String> {
-        if let Some(Token::Comment(text, _)) = self.peek().cloned() {
+    /// Consume the run of comments and annotations before a node.
+    fn parse_doc(&mut self) -> LeadingDoc {
+        let start = self.pos;
+        let mut doc = None;
+        let mut doc_lines = Vec::new();
+        let mut block_doc = None;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Comment(text, _) => {
+                    doc = Some(text.clone());
+                    block_doc = None;
+                }
+                Token::DocComment(text, _) => doc_lines.push(format!("-{}", text)),
+                Token::BlockComment(text, _) => block_doc = Some(block_comment_doc(text)),
+                Token::Annotation(_, _) => {}
+                _ => break,
+            }
             self.advance();
-            Some(text.clone())
-        } else {
-            None
+        }
+        if !doc_lines.is_empty() {
+            doc = Some(doc_lines.join("\n"));
+        }
+        // The annotation parser skips the comments in between.
+        let annotations = AnnotationParser::new(&self.tokens[start..self.pos]).parse_with_spans();
+        LeadingDoc {
+            doc,
+            block_doc,
+            annotations,
         }
     }
 
-    /// Main dispatch: first skip annotation tokens, then decide how to parse the next code node.
+    /// Main dispatch: collect the leading doc and annotations, then decide how to parse
+    /// the next code node and attach the annotations to it.
     fn parse_node(&mut self) -> Option<CodeASTNode> {
-        self.skip_annotation_tokens();
-        let doc = self.parse_doc();
-        let token = self.peek()?.clone();
-        match token {
-            Token::Keyword(ref s, _) if s == "function" => self.parse_function_def(doc),
-            Token::Keyword(ref s, _) if s == "local" => self.parse_variable_declaration(doc),
-            Token::Keyword(ref s, _) if s == "return" => self.parse_return_statement(doc),
-            Token::Keyword(ref s, _) if s == "if" => self.parse_if_statement(doc),
-            Token::Keyword(ref s, _) if s == "while" => self.parse_while_loop(doc),
-            Token::Keyword(ref s, _) if s == "for" => self.parse_for_numeric(doc),
-            Token::Keyword(ref s, _) if s == "do" => self.parse_do_block(doc),
-            Token::Keyword(ref s, _) if s == "repeat" => self.parse_repeat_until(doc),
+        let LeadingDoc {
+            doc,
+            block_doc,
+            annotations,
+        } = self.parse_doc();
+        let start = self.pos;
+        let mut node = self.parse_statement(doc)?;
+        // A block comment right before a module table or function (typically a file's header)
+        // documents it.
+        if let CodeASTNode::ModuleDeclaration { doc, .. } | CodeASTNode::FunctionDef { doc, .. } =
+            &mut node
+        {
+            if doc.is_none() {
+                *doc = block_doc;
+            }
+        }
+        let (mut annotations, spans): (Vec<_>, Vec<_>) = annotations.into_iter().unzip();
+        // An enum's members are the entries of the table it annotates.
+        if let CodeASTNode::ModuleDeclaration { .. } = node {
+            for annotation in &mut annotations {
+                if let AnnotationASTNode::Enum { values, .. } = annotation {
+                    *values = table_entry_values(&self.tokens[start..self.pos]);
+                }
+            }
+        }
+        if let CodeASTNode::FunctionDef {
+            annotation_spans, ..
+        } = &mut node
+        {
+            *annotation_spans = spans;
+        }
+        if let Some(slot) = node.annotations_mut() {
+            *slot = annotations;
+        }
+        Some(node)
+    }
+
+    fn parse_statement(&mut self, doc: Option<String>) -> Option<CodeASTNode> {
+        match self.peek()? {
+            Token::Keyword(s, _) => match s.as_str() {
+                "function" => self.parse_function_def(doc),
+                "local" => self.parse_variable_declaration(doc),
+                "return" => self.parse_return_statement(doc),
+                "if" => self.parse_if_statement(doc),
+                "while" => self.parse_while_loop(doc),
+                "for" => self.parse_for(doc),
+                "do" => self.parse_do_block(doc),
+                "repeat" => self.parse_repeat_until(doc),
+                "break" => {
+                    self.advance();
+                    Some(CodeASTNode::Break)
+                }
+                "goto" => {
+                    self.advance();
+                    let (label, _) = self.eat_identifier()?;
+                    Some(CodeASTNode::Goto(label))
+                }
+                "require" if self.peek_function_call() => self.parse_function_call_stmt(doc),
+                _ => None,
+            },
+            Token::Operator(op, _) if op == "::" => {
+                self.advance();
+                let (label, _) = self.eat_identifier()?;
+                self.eat_operator("::");
+                Some(CodeASTNode::Label(label))
+            }
             Token::Identifier(_, _) => {
+                let indexed = matches!(
+                    self.tokens.get(self.qualified_name_end()),
+                    Some(Token::BracketOpen(_))
+                );
                 if self.peek_assignment() {
                     self.parse_assignment(doc)
-                } else if self.peek_function_call() {
+                } else if self.peek_function_call() || self.peek_method_call() {
                     self.parse_function_call_stmt(doc)
+                } else if indexed {
+                    // `t[k] = v`; anything else starting with `t[k]` is not parsed.
+                    self.parse_assignment(doc)
                 } else {
                     None
                 }
@@ -87,27 +173,82 @@ impl CodeParser {
 
     fn peek_assignment(&self) -> bool {
         self.tokens
-            .get(self.pos + 1)
-            .map_or(false, |token| matches!(token, Token::Assignment(_)))
+            .get(self.qualified_name_end())
+            .is_some_and(|token| matches!(token, Token::Assignment(_)))
     }
 
     fn peek_function_call(&self) -> bool {
         self.tokens
-            .get(self.pos + 1)
-            .map_or(false, |token| matches!(token, Token::ParenOpen(_)))
+            .get(self.qualified_name_end())
+            .is_some_and(|token| match token {
+                Token::ParenOpen(_) => true,
+                // `require "name"` passes its one string argument without parentheses.
+                Token::StringLiteral(_, _) => {
+                    matches!(self.peek(), Some(Token::Keyword(s, _)) if s == "require")
+                }
+                _ => false,
+            })
+    }
+
+    fn peek_method_call(&self) -> bool {
+        self.tokens
+            .get(self.qualified_name_end())
+            .is_some_and(|token| matches!(token, Token::Operator(op, _) if op == ":"))
+    }
+
+    /// Position of the first token after a dotted name (`a.b.c`) starting at the current token.
+    fn qualified_name_end(&self) -> usize {
+        let mut pos = self.pos + 1;
+        while let (Some(Token::Operator(op, _)), Some(Token::Identifier(_, _))) =
+            (self.tokens.get(pos), self.tokens.get(pos + 1))
+        {
+            if op != "." {
+                break;
+            }
+            pos += 2;
+        }
+        pos
+    }
+
+    /// Consume the operator `op` if it is the next token.
+    fn eat_operator(&mut self, op: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Operator(s, _)) if s == op);
+        if found {
+            self.advance();
+        }
+        found
+    }
+
+    /// Consume `=` if it is the next token.
+    fn eat_assignment(&mut self) -> bool {
+        let found = matches!(self.peek(), Some(Token::Assignment(_)));
+        if found {
+            self.advance();
+        }
+        found
+    }
+
+    /// Consume an identifier, returning its dotted name and span.
+    fn eat_identifier(&mut self) -> Option<(String, Span)> {
+        let identifier = match self.peek() {
+            Some(Token::Identifier(name, span)) => (name.to_string(), span.clone()),
+            _ => return None,
+        };
+        self.advance();
+        Some(identifier)
     }
 
     fn match_token_variant(&self, variant: &str) -> bool {
         if let Some(token) = self.peek() {
-            match (variant, token) {
-                ("ParenOpen", Token::ParenOpen(_)) => true,
-                ("ParenClose", Token::ParenClose(_)) => true,
-                ("BraceOpen", Token::BraceOpen(_)) => true,
-                ("BraceClose", Token::BraceClose(_)) => true,
-                ("BracketOpen", Token::BracketOpen(_)) => true,
-                ("BracketClose", Token::BracketClose(_)) => true,
-                _ => false,
-            }
+            matches!(
+                (variant, token),
+                ("ParenOpen", Token::ParenOpen(_))
+                    | ("ParenClose", Token::ParenClose(_))
+                    | ("BraceOpen", Token::BraceOpen(_))
+                    | ("BraceClose", Token::BraceClose(_))
+                    | ("BracketOpen", Token::BracketOpen(_))
+                    | ("BracketClose", Token::BracketClose(_))
+            )
         } else {
             false
         }
@@ -116,13 +257,49 @@ impl CodeParser {
     // --- Parsing Functions for Code AST Nodes ---
 
     fn parse_function_def(&mut self, doc: Option<String>) -> Option<CodeASTNode> {
-        self.advance(); // consume "function"
-        let name = self.parse_qualified_name()?;
+        let start = self.advance()?.span().clone(); // consume "function"
+        let mut name = self.parse_qualified_name()?;
+        // A method (`function A:b()`) takes an implicit `self`, spanned by its colon.
+        let self_span = match self.peek() {
+            Some(Token::Operator(op, span)) if op == ":" => Some(span.clone()),
+            _ => None,
+        };
+        if self_span.is_some() {
+            self.advance();
+            let method = self.name_component()?;
+            self.advance();
+            name.push(':');
+            name.push_str(&method);
+        }
+        let mut node = self.parse_function_body(name, false, doc, &start)?;
+        if let (
+            Some(span),
+            CodeASTNode::FunctionDef {
+                params,
+                param_spans,
+                ..
+            },
+        ) = (self_span, &mut node)
+        {
+            params.insert(0, ("self".to_string(), TypeInfo::Unknown));
+            param_spans.insert(0, span);
+        }
+        Some(node)
+    }
+
+    /// Parse the parameter list and body of a function whose name has already been consumed.
+    fn parse_function_body(
+        &mut self,
+        name: String,
+        is_local: bool,
+        doc: Option<String>,
+        start: &Span,
+    ) -> Option<CodeASTNode> {
         if !self.match_token_variant("ParenOpen") {
             return None;
         }
         self.advance(); // consume '('
-        let params = self.parse_parameters();
+        let (params, param_spans) = self.parse_parameters();
         if !self.match_token_variant("ParenClose") {
             return None;
         }
@@ -130,92 +307,87 @@ impl CodeParser {
         let body = self.parse_block();
         Some(CodeASTNode::FunctionDef {
             name,
+            is_local,
             params,
+            param_spans,
             return_types: vec![],
             doc,
             annotations: vec![],
+            annotation_spans: vec![],
             body,
+            span: self.span_from(start),
         })
     }
 
     fn parse_qualified_name(&mut self) -> Option<String> {
-        let mut name = String::new();
-        if let Some(token) = self.peek().cloned() {
-            match token {
-                Token::Identifier(parts, _) => {
-                    name.push_str(&parts.join("."));
-                    self.advance();
-                }
-                Token::Keyword(s, _) => {
-                    name.push_str(&s);
+        let mut name = self.name_component()?;
+        self.advance();
+        while self.eat_operator(".") {
+            match self.name_component() {
+                Some(component) => {
+                    name.push('.');
+                    name.push_str(&component);
                     self.advance();
                 }
-                _ => return None,
-            }
-        } else {
-            return None;
-        }
-        while let Some(token) = self.peek().cloned() {
-            if let Token::Operator(ref op, _) = token {
-                if op == "." {
-                    self.advance(); // consume dot
-                    if let Some(next_token) = self.peek().cloned() {
-                        match next_token {
-                            Token::Identifier(parts, _) => {
-                                name.push('.');
-                                name.push_str(&parts.join("."));
-                                self.advance();
-                            }
-                            Token::Keyword(s, _) => {
-                                name.push('.');
-                                name.push_str(&s);
-                                self.advance();
-                            }
-                            _ => break,
-                        }
-                    } else {
-                        break;
-                    }
-                } else {
-                    break;
-                }
-            } else {
-                break;
+                None => break,
             }
         }
         Some(name)
     }
 
-    fn parse_parameters(&mut self) -> Vec<(String, TypeInfo)> {
+    /// The next token as one component of a qualified name, without consuming it.
+    fn name_component(&self) -> Option<String> {
+        match self.peek()? {
+            Token::Identifier(name, _) => Some(name.to_string()),
+            Token::Keyword(s, _) => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    /// Parse a parameter list, returning the parameters and the span of each name.
+    fn parse_parameters(&mut self) -> (Vec<(String, TypeInfo)>, Vec<Span>) {
         let mut params = Vec::new();
-        while let Some(token) = self.peek().cloned() {
-            if let Token::ParenClose(_) = token {
-                break;
-            }
-            if let Token::Identifier(parts, _) = token {
-                let param_name = parts.join(".");
-                self.advance();
-                params.push((param_name, TypeInfo::Unknown));
-            } else {
-                self.advance();
-            }
-            if let Some(Token::Operator(ref op, _)) = self.peek().cloned() {
-                if op == "," {
-                    self.advance();
+        let mut spans = Vec::new();
+        while let Some(token) = self.peek() {
+            match token {
+                Token::ParenClose(_) => break,
+                Token::Identifier(name, span) => {
+                    params.push((name.to_string(), TypeInfo::Unknown));
+                    spans.push(span.clone());
+                }
+                Token::VarArg(span) => {
+                    params.push(("...".to_string(), TypeInfo::Unknown));
+                    spans.push(span.clone());
                 }
+                _ => {}
             }
+            self.advance();
+            self.eat_operator(",");
         }
-        params
+        (params, spans)
     }
 
     fn parse_block(&mut self) -> Vec<CodeASTNode> {
+        self.parse_block_until(&["end"]).0
+    }
+
+    /// Parse statements up to one of the `terminators` keywords, consuming it and
+    /// returning which one ended the block (None at end of input).
+    fn parse_block_until(&mut self, terminators: &[&str]) -> (Vec<CodeASTNode>, Option<String>) {
         let mut nodes = Vec::new();
-        while let Some(token) = self.peek().cloned() {
-            if let Token::Keyword(ref s, _) = token {
-                if s == "end" {
-                    self.advance(); // consume "end"
-                    break;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Keyword(s, _) if terminators.contains(&s.as_str()) => {
+                    let terminator = s.clone();
+                    self.advance(); // consume the terminator
+                    return (nodes, Some(terminator));
                 }
+                // `;` is an empty statement, only ever separating others.
+                Token::Operator(op, _) if op == ";" => {
+                    self.advance();
+                    continue;
+                }
+                _ => {}
             }
             if let Some(node) = self.parse_node() {
                 nodes.push(node);
@@ -223,92 +395,115 @@ impl CodeParser {
                 self.advance();
             }
         }
-        nodes
+        (nodes, None)
     }
 
-    fn parse_variable_declaration(&mut self, doc: Option<String>) -> Option<CodeASTNode> {
-        self.advance(); // consume "local"
-        let name = if let Some(Token::Identifier(parts, _)) = self.peek().cloned() {
-            let n = parts.join(".");
+    /// Consume `keyword` if it is the next token.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Keyword(s, _)) if s == keyword);
+        if found {
             self.advance();
-            n
-        } else {
-            return None;
-        };
-        if let Some(Token::Assignment(_)) = self.peek().cloned() {
-            self.advance(); // consume '='
-                            // If initializer is a table constructor, treat as a module declaration.
-            if let Some(Token::BraceOpen(_)) = self.peek().cloned() {
-                let exports = self.parse_table_exports();
+        }
+        found
+    }
+
+    fn parse_variable_declaration(&mut self, doc: Option<String>) -> Option<CodeASTNode> {
+        let start = self.advance()?.span().clone(); // consume "local"
+
+        // `local function name(...) ... end`
+        let is_function = matches!(self.peek(), Some(Token::Keyword(s, _)) if s == "function");
+        if is_function {
+            self.advance(); // consume "function"
+        }
+        let (name, name_span) = self.eat_identifier()?;
+        if is_function {
+            return self.parse_function_body(name, true, doc, &start);
+        }
+        if self.eat_assignment() {
+            if self.eat_keyword("function") {
+                return self.parse_function_body(name, true, doc, &start);
+            }
+            // If initializer is a table constructor, treat as a module declaration.
+            if let Some(Token::BraceOpen(_)) = self.peek() {
+                let (exports, items) = self.parse_table_fields();
                 Some(CodeASTNode::ModuleDeclaration {
                     name,
                     exports,
+                    items,
                     doc,
                     annotations: vec![],
+                    span: self.span_from(&start),
                 })
             } else {
                 let expr = self.parse_expression();
                 Some(CodeASTNode::VariableDeclaration {
                     name,
+                    name_span,
                     value: expr.map(|e| Box::new(CodeASTNode::ReturnStatement(vec![e]))),
                     doc,
                     annotations: vec![],
+                    span: self.span_from(&start),
                 })
             }
         } else {
             Some(CodeASTNode::VariableDeclaration {
                 name,
+                name_span,
                 value: None,
                 doc,
                 annotations: vec![],
+                span: self.span_from(&start),
             })
         }
     }
 
     fn parse_assignment(&mut self, doc: Option<String>) -> Option<CodeASTNode> {
-        // Assume a single identifier on the LHS.
-        let lhs = if let Some(Token::Identifier(parts, _)) = self.peek().cloned() {
-            let id = parts.join(".");
-            self.advance();
-            vec![id]
-        } else {
-            return None;
-        };
-        if let Some(Token::Assignment(_)) = self.peek().cloned() {
-            self.advance(); // consume '='
-        } else {
+        // Assume a single target on the LHS: a (possibly dotted) name, optionally indexed.
+        let start = self.peek()?.span().clone();
+        let name = self.parse_qualified_name()?;
+        let target =
+            self.parse_suffixes(Expression::Identifier(name.clone(), self.span_from(&start)));
+        let is_name = matches!(target, Expression::Identifier(..));
+        if !(is_name || matches!(target, Expression::TableFieldAccess { .. }))
+            || !self.eat_assignment()
+        {
             return None;
         }
+        // `a.b.c = function(...) end` is equivalent to `function a.b.c(...) end`.
+        if is_name && self.eat_keyword("function") {
+            return self.parse_function_body(name, false, doc, &start);
+        }
+        // `a.b = {...}` declares a table just like `local a = {...}`, as in definition stubs.
+        if let (true, Some(Token::BraceOpen(_))) = (is_name, self.peek()) {
+            let (exports, items) = self.parse_table_fields();
+            return Some(CodeASTNode::ModuleDeclaration {
+                name,
+                exports,
+                items,
+                doc,
+                annotations: vec![],
+                span: self.span_from(&start),
+            });
+        }
+        let lhs = vec![target];
         let rhs_expr = self.parse_expression()?;
         Some(CodeASTNode::Assignment {
             lhs,
             rhs: vec![rhs_expr],
             doc,
             annotations: vec![],
+            span: self.span_from(&start),
         })
     }
 
     fn parse_return_statement(&mut self, _doc: Option<String>) -> Option<CodeASTNode> {
         self.advance(); // consume "return"
         let mut exprs = Vec::new();
-        while let Some(token) = self.peek().cloned() {
-            match token {
-                Token::Identifier(parts, _) => {
-                    exprs.push(Expression::Identifier(parts.join(".")));
-                    self.advance();
-                }
-                Token::NumberLiteral(s, _) => {
-                    exprs.push(Expression::Literal(s.clone()));
-                    self.advance();
-                }
-                Token::StringLiteral(s, _) => {
-                    exprs.push(Expression::Literal(s.clone()));
-                    self.advance();
-                }
-                Token::Operator(ref op, _) if op == "," => {
-                    self.advance();
-                }
-                _ => break,
+        loop {
+            if let Some(expr) = self.parse_expression() {
+                exprs.push(expr);
+            } else if !self.eat_operator(",") {
+                break;
             }
         }
         Some(CodeASTNode::ReturnStatement(exprs))
@@ -318,207 +513,929 @@ impl CodeParser {
         if !self.match_token_variant("BraceOpen") {
             return None;
         }
-        self.advance(); // consume '{'
+        let (fields, _) = self.parse_table_entries();
+        Some(CodeASTNode::TableConstructor(fields))
+    }
+
+    /// Parse a table constructor, starting at its opening brace, into its named entries and
+    /// its positional ones. Entries whose key or value is not a simple expression (such as
+    /// `[1] = x`) are skipped.
+    fn parse_table_entries(&mut self) -> (Vec<(String, Expression)>, Vec<Expression>) {
         let mut fields = Vec::new();
-        while let Some(token) = self.peek().cloned() {
-            if let Token::BraceClose(_) = token {
-                self.advance();
-                break;
-            }
-            let key = if let Some(Token::Identifier(parts, _)) = self.peek().cloned() {
-                let k = parts.join(".");
-                self.advance();
-                k
-            } else if let Some(Token::StringLiteral(s, _)) = self.peek().cloned() {
-                let k = s.clone();
-                self.advance();
-                k
-            } else {
-                self.advance();
-                continue;
-            };
-            if let Some(Token::Operator(op, _)) = self.peek().cloned() {
-                if op == "=" {
-                    self.advance(); // consume '='
+        let mut items = Vec::new();
+        self.advance(); // consume '{'
+        loop {
+            self.skip_comments();
+            let key = match (self.peek(), self.tokens.get(self.pos + 1)) {
+                (None, _) => break,
+                (Some(Token::BraceClose(_)), _) => {
+                    self.advance();
+                    break;
                 }
-            }
-            let value = self.parse_expression()?;
-            fields.push((key, value));
-            if let Some(Token::Operator(ref op, _)) = self.peek().cloned() {
-                if op == "," {
+                (Some(Token::Operator(op, _)), _) if op == "," || op == ";" => {
                     self.advance();
+                    continue;
+                }
+                (Some(Token::Identifier(name, _)), Some(Token::Assignment(_))) => {
+                    let key = name.to_string();
+                    self.pos += 2;
+                    Some(key)
+                }
+                (Some(Token::BracketOpen(_)), Some(Token::StringLiteral(key, _)))
+                    if matches!(
+                        (self.tokens.get(self.pos + 2), self.tokens.get(self.pos + 3)),
+                        (Some(Token::BracketClose(_)), Some(Token::Assignment(_)))
+                    ) =>
+                {
+                    let key = key.clone();
+                    self.pos += 4;
+                    Some(key)
+                }
+                (Some(Token::BracketOpen(_)), _) => {
+                    self.skip_field();
+                    continue;
                 }
+                _ => None,
+            };
+            let value = self.parse_expression();
+            self.skip_comments();
+            match value {
+                Some(value) if self.at_field_end() => match key {
+                    Some(key) => fields.push((key, value)),
+                    None => items.push(value),
+                },
+                _ => self.skip_field(),
             }
         }
-        Some(CodeASTNode::TableConstructor(fields))
+        (fields, items)
     }
 
-    fn parse_table_exports(&mut self) -> Vec<ExportItem> {
+    /// Parse a table constructor into its named entries, typed from their values, and the
+    /// types of its positional entries. `[key] = value` entries are skipped.
+    fn parse_table_fields(&mut self) -> (Vec<ExportItem>, Vec<TypeInfo>) {
         let mut exports = Vec::new();
+        let mut items = Vec::new();
         if !self.match_token_variant("BraceOpen") {
-            return exports;
+            return (exports, items);
         }
         self.advance(); // consume '{'
-        while let Some(token) = self.peek().cloned() {
-            if let Token::BraceClose(_) = token {
+        loop {
+            self.skip_comments();
+            match self.peek() {
+                None => break,
+                Some(Token::BraceClose(_)) => {
+                    self.advance();
+                    break;
+                }
+                Some(Token::BracketOpen(_)) => self.skip_field(),
+                Some(Token::Identifier(_, _))
+                    if matches!(self.tokens.get(self.pos + 1), Some(Token::Assignment(_))) =>
+                {
+                    let Some((name, _)) = self.eat_identifier() else {
+                        break;
+                    };
+                    self.advance(); // consume '='
+                    let type_info = self.parse_field_value();
+                    exports.push(ExportItem { name, type_info });
+                }
+                _ => {
+                    if !self.eat_operator(",") && !self.eat_operator(";") {
+                        items.push(self.parse_field_value());
+                    }
+                }
+            }
+        }
+        (exports, items)
+    }
+
+    /// Type of a table field's value, consuming the value. Anything beyond a literal, a
+    /// name, a nested table or a function is skipped and typed as unknown.
+    fn parse_field_value(&mut self) -> TypeInfo {
+        let type_info = match self.peek() {
+            Some(Token::BraceOpen(_)) => {
+                let (exports, items) = self.parse_table_fields();
+                TypeInfo::table_of(&exports, &items)
+            }
+            Some(Token::Keyword(s, span)) if s == "function" => {
+                let start = span.clone();
+                self.advance(); // consume "function"
+                self.parse_function_body(String::new(), false, None, &start);
+                TypeInfo::Function
+            }
+            Some(Token::Keyword(s, _)) if s == "true" || s == "false" => {
                 self.advance();
+                TypeInfo::Boolean
+            }
+            _ => match self.parse_expression() {
+                Some(Expression::Literal(text, _)) => parser_helpers::literal_type(&text),
+                _ => TypeInfo::Unknown,
+            },
+        };
+        self.skip_comments();
+        if self.at_field_end() {
+            type_info
+        } else {
+            self.skip_field();
+            TypeInfo::Unknown
+        }
+    }
+
+    /// Skip comments and annotations between table fields.
+    fn skip_comments(&mut self) {
+        while let Some(
+            Token::Comment(_, _)
+            | Token::DocComment(_, _)
+            | Token::Annotation(_, _)
+            | Token::BlockCommentOpen(_)
+            | Token::BlockComment(_, _)
+            | Token::BlockCommentClose(_),
+        ) = self.peek()
+        {
+            self.advance();
+        }
+    }
+
+    /// Whether the next token ends a table field: a separator, the closing brace, or the
+    /// end of input.
+    fn at_field_end(&self) -> bool {
+        match self.peek() {
+            None | Some(Token::BraceClose(_)) => true,
+            Some(Token::Operator(op, _)) => op == "," || op == ";",
+            _ => false,
+        }
+    }
+
+    /// Skip to the end of the current table field or call argument, over any nested brackets.
+    fn skip_field(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::ParenOpen(_) | Token::BraceOpen(_) | Token::BracketOpen(_) => depth += 1,
+                Token::ParenClose(_) | Token::BraceClose(_) | Token::BracketClose(_)
+                    if depth == 0 =>
+                {
+                    return
+                }
+                Token::ParenClose(_) | Token::BraceClose(_) | Token::BracketClose(_) => {
+                    depth = depth.saturating_sub(1)
+                }
+                Token::Operator(op, _) if depth == 0 && (op == "," || op == ";") => return,
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
+    fn parse_expression(&mut self) -> Option<Expression> {
+        self.parse_binary_expression(0)
+    }
+
+    /// Parse operands joined by binary operators of at least `min_precedence`.
+    fn parse_binary_expression(&mut self, min_precedence: u8) -> Option<Expression> {
+        let mut lhs = self.parse_simple_expression()?;
+        while let Some(op) = self.peek_binary_operator() {
+            let Some((precedence, right_associative)) = parser_helpers::binary_precedence(&op)
+            else {
+                break;
+            };
+            if precedence < min_precedence {
                 break;
             }
-            let name = if let Some(Token::Identifier(parts, _)) = self.peek().cloned() {
-                let n = parts.join(".");
-                self.advance();
-                n
+            let start = self.pos;
+            self.advance();
+            let next_precedence = if right_associative {
+                precedence
             } else {
-                self.advance();
-                continue;
+                precedence + 1
             };
-            exports.push(ExportItem {
-                name,
-                type_info: TypeInfo::Unknown,
-            });
-            if let Some(Token::Operator(ref op, _)) = self.peek().cloned() {
-                if op == "," {
-                    self.advance();
+            match self.parse_binary_expression(next_precedence) {
+                Some(rhs) => {
+                    lhs = Expression::BinaryOp {
+                        op,
+                        lhs: Box::new(lhs),
+                        rhs: Box::new(rhs),
+                    }
+                }
+                None => {
+                    self.pos = start;
+                    break;
                 }
             }
         }
-        exports
+        Some(lhs)
     }
 
-    fn parse_expression(&mut self) -> Option<Expression> {
-        if let Some(token) = self.peek().cloned() {
-            match token {
-                Token::Identifier(parts, _) => {
-                    let expr = Expression::Identifier(parts.join("."));
-                    self.advance();
-                    Some(expr)
+    /// The text of the operator at the current token: an operator token, or the `and` and
+    /// `or` keywords.
+    fn peek_binary_operator(&self) -> Option<String> {
+        match self.peek()? {
+            Token::Operator(op, _) => Some(op.clone()),
+            Token::Keyword(s, _) if s == "and" || s == "or" => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    /// A name, call, literal, table constructor, parenthesized expression or unary
+    /// operation, with its suffixes.
+    fn parse_simple_expression(&mut self) -> Option<Expression> {
+        let expr = match self.peek()? {
+            Token::Keyword(op, op_span) | Token::Operator(op, op_span)
+                if parser_helpers::is_unary_operator(op) =>
+            {
+                let op = op.clone();
+                let op_span = op_span.clone();
+                let start = self.pos;
+                self.advance(); // consume the operator
+                // Only `^` binds tighter than a unary operator: `not a == b` is `(not a) == b`
+                // and `-x^2` is `-(x^2)`.
+                let Some(operand) =
+                    self.parse_binary_expression(parser_helpers::UNARY_PRECEDENCE + 1)
+                else {
+                    self.pos = start;
+                    return None;
+                };
+                return Some(Expression::UnaryOp {
+                    op,
+                    operand: Box::new(operand),
+                    span: self.span_from(&op_span),
+                });
+            }
+            Token::Identifier(_, _) if self.peek_function_call() => {
+                let call = self.parse_function_call()?;
+                return Some(self.parse_suffixes(call));
+            }
+            // `require` is a keyword token, but a call to it is an ordinary call.
+            Token::Keyword(s, _) if s == "require" && self.peek_function_call() => {
+                let call = self.parse_function_call()?;
+                return Some(self.parse_suffixes(call));
+            }
+            Token::Identifier(name, span) => {
+                let base = Expression::Identifier(name.to_string(), span.clone());
+                self.advance();
+                return Some(self.parse_suffixes(base));
+            }
+            Token::NumberLiteral(s, span) => Expression::Literal(s.clone(), span.clone()),
+            Token::StringLiteral(s, span) => {
+                Expression::Literal(format!("\"{}\"", s), span.clone())
+            }
+            Token::Keyword(s, span) if matches!(s.as_str(), "true" | "false" | "nil") => {
+                Expression::Literal(s.clone(), span.clone())
+            }
+            Token::Keyword(s, start) if s == "function" => {
+                let start = start.clone();
+                self.advance(); // consume "function"
+                let Some(CodeASTNode::FunctionDef {
+                    params,
+                    param_spans,
+                    body,
+                    span,
+                    ..
+                }) = self.parse_function_body(String::new(), false, None, &start)
+                else {
+                    return None;
+                };
+                return Some(Expression::Function {
+                    params,
+                    param_spans,
+                    body,
+                    span,
+                });
+            }
+            Token::BraceOpen(start) => {
+                let start = start.clone();
+                let (fields, items) = self.parse_table_entries();
+                return Some(Expression::TableConstructor {
+                    fields,
+                    items,
+                    span: self.span_from(&start),
+                });
+            }
+            Token::ParenOpen(_) => {
+                let start = self.pos;
+                self.advance(); // consume '('
+                let inner = self.parse_expression();
+                match inner {
+                    Some(inner) if self.match_token_variant("ParenClose") => {
+                        self.advance(); // consume ')'
+                        return Some(self.parse_suffixes(inner));
+                    }
+                    _ => {
+                        self.pos = start;
+                        return None;
+                    }
                 }
-                Token::NumberLiteral(s, _) => {
-                    let expr = Expression::Literal(s.clone());
+            }
+            _ => return None,
+        };
+        self.advance();
+        Some(expr)
+    }
+
+    /// Wrap `base` in the `.name` and `[key]` accesses and `:method(args)` calls that
+    /// follow it.
+    fn parse_suffixes(&mut self, mut base: Expression) -> Expression {
+        loop {
+            if let Some(Token::Operator(op, _)) = self.peek() {
+                if op == ":" {
+                    let start = self.pos;
                     self.advance();
-                    Some(expr)
-                }
-                Token::StringLiteral(s, _) => {
-                    let expr = Expression::Literal(s.clone());
+                    let method = self.name_component();
                     self.advance();
-                    Some(expr)
+                    match method {
+                        Some(method) if self.match_token_variant("ParenOpen") => {
+                            let (args, _) = self.parse_call_arguments();
+                            base = Expression::MethodCall {
+                                span: self.span_from(&base.span()),
+                                receiver: Box::new(base),
+                                method,
+                                args,
+                            };
+                            continue;
+                        }
+                        _ => {
+                            self.pos = start;
+                            return base;
+                        }
+                    }
                 }
-                _ => None,
             }
-        } else {
-            None
+            let key = if self.eat_operator(".") {
+                match self.name_component() {
+                    Some(name) => {
+                        self.advance();
+                        FieldKey::Name(name)
+                    }
+                    None => return base,
+                }
+            } else if let Some(Token::BracketOpen(_)) = self.peek() {
+                let start = self.pos;
+                self.advance(); // consume '['
+                match self.parse_expression() {
+                    Some(index) if self.match_token_variant("BracketClose") => {
+                        self.advance(); // consume ']'
+                        FieldKey::Index(Box::new(index))
+                    }
+                    _ => {
+                        self.pos = start;
+                        return base;
+                    }
+                }
+            } else {
+                return base;
+            };
+            base = Expression::TableFieldAccess {
+                span: self.span_from(&base.span()),
+                table: Box::new(base),
+                key,
+            };
         }
     }
 
-    fn parse_if_statement(&mut self, doc: Option<String>) -> Option<CodeASTNode> {
-        self.advance(); // consume "if"
-        let condition = self.parse_expression()?;
-        let then_block = self.parse_block();
-        let mut else_block = None;
-        if let Some(token) = self.peek().cloned() {
-            if let Token::Keyword(ref s, _) = token {
-                if s == "else" {
+    /// Parse a call to a dotted name.
+    fn parse_function_call(&mut self) -> Option<Expression> {
+        let start = self.peek()?.span().clone();
+        let callee = self.parse_qualified_name()?;
+        let callee_span = self.span_from(&start);
+        let (args, skipped_args) = match self.peek() {
+            Some(Token::StringLiteral(s, span)) => {
+                let arg = Expression::Literal(format!("\"{}\"", s), span.clone());
+                self.advance();
+                (vec![arg], false)
+            }
+            _ => self.parse_call_arguments(),
+        };
+        Some(Expression::FunctionCall {
+            callee,
+            callee_span,
+            args,
+            skipped_args,
+            span: self.span_from(&start),
+        })
+    }
+
+    /// Parse a parenthesized argument list, starting at its opening parenthesis. Arguments
+    /// that are not simple expressions are skipped, and the flag returned with the list
+    /// says whether any were.
+    fn parse_call_arguments(&mut self) -> (Vec<Expression>, bool) {
+        self.advance(); // consume "("
+        let mut args = Vec::new();
+        let mut skipped = false;
+        loop {
+            self.skip_comments();
+            if let Some(Token::ParenClose(_)) = self.peek() {
+                self.advance();
+                break;
+            }
+            let start = self.pos;
+            match self.parse_expression() {
+                Some(arg) if self.at_argument_end() => args.push(arg),
+                _ => {
+                    self.pos = start;
+                    self.skip_field();
+                    skipped = true;
+                }
+            }
+            if !self.eat_operator(",") {
+                if let Some(Token::ParenClose(_)) = self.peek() {
                     self.advance();
-                    else_block = Some(self.parse_block());
                 }
+                break;
             }
         }
+        (args, skipped)
+    }
+
+    /// Whether the next token ends a call argument.
+    fn at_argument_end(&self) -> bool {
+        match self.peek() {
+            Some(Token::ParenClose(_)) => true,
+            Some(Token::Operator(op, _)) => op == ",",
+            _ => false,
+        }
+    }
+
+    fn parse_if_statement(&mut self, doc: Option<String>) -> Option<CodeASTNode> {
+        let start = self.advance()?.span().clone(); // consume "if" (or "elseif")
+        let condition = self.parse_expression()?;
+        self.eat_keyword("then");
+        let (then_block, terminator) = self.parse_block_until(&["else", "elseif", "end"]);
+        let else_block = match terminator.as_deref() {
+            Some("else") => Some(self.parse_block()),
+            // `elseif` is an if statement nested in the else branch, sharing our `end`.
+            Some("elseif") => {
+                self.pos -= 1;
+                self.parse_if_statement(None).map(|nested| vec![nested])
+            }
+            _ => None,
+        };
         Some(CodeASTNode::IfStatement {
             condition,
             then_block,
             else_block,
             doc,
             annotations: vec![],
+            span: self.span_from(&start),
         })
     }
 
     fn parse_while_loop(&mut self, doc: Option<String>) -> Option<CodeASTNode> {
-        self.advance(); // consume "while"
+        let start = self.advance()?.span().clone(); // consume "while"
         let condition = self.parse_expression()?;
+        self.eat_keyword("do");
         let body = self.parse_block();
         Some(CodeASTNode::WhileLoop {
             condition,
             body,
             doc,
             annotations: vec![],
+            span: self.span_from(&start),
         })
     }
 
-    fn parse_for_numeric(&mut self, doc: Option<String>) -> Option<CodeASTNode> {
-        self.advance(); // consume "for"
-        let var = if let Some(Token::Identifier(parts, _)) = self.peek().cloned() {
-            let v = parts.join(".");
-            self.advance();
-            v
+    /// A numeric (`for i = 1, n do`) or generic (`for k, v in pairs(t) do`) for loop.
+    fn parse_for(&mut self, doc: Option<String>) -> Option<CodeASTNode> {
+        let for_span = self.advance()?.span().clone(); // consume "for"
+        let (var, _) = self.eat_identifier()?;
+        if self.eat_assignment() {
+            self.parse_for_numeric(for_span, var, doc)
         } else {
-            return None;
-        };
-        if let Some(Token::Assignment(_)) = self.peek().cloned() {
-            self.advance();
-        } else {
-            return None;
+            self.parse_for_generic(for_span, var, doc)
         }
+    }
+
+    /// The rest of a numeric for loop, after its `=`.
+    fn parse_for_numeric(&mut self, for_span: Span, var: String, doc: Option<String>) -> Option<CodeASTNode> {
         let start = self.parse_expression()?;
-        if let Some(Token::Operator(ref op, _)) = self.peek().cloned() {
-            if op == "," {
-                self.advance();
-            }
-        }
+        self.eat_operator(",");
         let end = self.parse_expression()?;
-        let mut step = None;
-        if let Some(Token::Operator(ref op, _)) = self.peek().cloned() {
-            if op == "," {
-                self.advance();
-                step = self.parse_expression();
-            }
-        }
+        let step = if self.eat_operator(",") {
+            self.parse_expression()
+        } else {
+            None
+        };
+        self.eat_keyword("do");
         let body = self.parse_block();
         Some(CodeASTNode::ForNumeric {
             var,
-            start,
-            end,
-            step,
+            start: Box::new(start),
+            end: Box::new(end),
+            step: step.map(Box::new),
+            body,
+            doc,
+            annotations: vec![],
+            span: self.span_from(&for_span),
+        })
+    }
+
+    /// The rest of a generic for loop, after its first name.
+    fn parse_for_generic(&mut self, for_span: Span, first: String, doc: Option<String>) -> Option<CodeASTNode> {
+        let mut vars = vec![first];
+        while self.eat_operator(",") {
+            vars.push(self.eat_identifier()?.0);
+        }
+        if !self.eat_keyword("in") {
+            return None;
+        }
+        let mut exprs = vec![self.parse_expression()?];
+        while self.eat_operator(",") {
+            exprs.push(self.parse_expression()?);
+        }
+        self.eat_keyword("do");
+        let body = self.parse_block();
+        Some(CodeASTNode::ForGeneric {
+            vars,
+            exprs,
             body,
             doc,
             annotations: vec![],
+            span: self.span_from(&for_span),
         })
     }
 
     fn parse_do_block(&mut self, doc: Option<String>) -> Option<CodeASTNode> {
-        self.advance(); // consume "do"
+        let start = self.advance()?.span().clone(); // consume "do"
         let body = self.parse_block();
         Some(CodeASTNode::DoBlock {
             body,
             doc,
             annotations: vec![],
+            span: self.span_from(&start),
         })
     }
 
     fn parse_repeat_until(&mut self, doc: Option<String>) -> Option<CodeASTNode> {
-        self.advance(); // consume "repeat"
-        let body = self.parse_block();
-        if let Some(token) = self.peek().cloned() {
-            if let Token::Keyword(ref s, _) = token {
-                if s == "until" {
-                    self.advance(); // consume "until"
-                }
-            }
-        }
+        let start = self.advance()?.span().clone(); // consume "repeat"
+        let (body, _) = self.parse_block_until(&["until"]);
         let condition = self.parse_expression()?;
         Some(CodeASTNode::RepeatUntil {
             body,
             condition,
             doc,
             annotations: vec![],
+            span: self.span_from(&start),
         })
     }
 
     fn parse_function_call_stmt(&mut self, doc: Option<String>) -> Option<CodeASTNode> {
+        let start = self.peek()?.span().clone();
         let call = self.parse_expression()?;
         Some(CodeASTNode::FunctionCallStmt {
             call,
             doc,
             annotations: vec![],
+            span: self.span_from(&start),
         })
     }
 }
+
+/// The comments and annotations before a node.
+struct LeadingDoc {
+    /// The text after `--` of the `---` lines, one per line, or else of the last plain
+    /// comment.
+    doc: Option<String>,
+    /// The doc of a block comment ending the run.
+    block_doc: Option<String>,
+    annotations: Vec<(AnnotationASTNode, Span)>,
+}
+
+/// A block comment's text as a doc: its lines without surrounding blank lines, each written
+/// as if after `-- `.
+fn block_comment_doc(text: &str) -> String {
+    text.trim_matches(|c: char| c == '\n' || c == '\r')
+        .lines()
+        .map(|line| format!(" {}", line.trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The `name = value` entries of the table constructor in `tokens` whose value is a single
+/// literal, as source text: `{ Red = 1, Green = "g" }` gives `Red = 1` and `Green = "g"`.
+fn table_entry_values(tokens: &[Token]) -> Vec<(String, String)> {
+    let mut values = Vec::new();
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::BraceOpen(_) => depth += 1,
+            Token::BraceClose(_) => depth -= 1,
+            Token::Identifier(name, _) if depth == 1 => {
+                let rest = &tokens[index + 1..];
+                let (value, end) = match rest {
+                    [Token::Assignment(_), Token::NumberLiteral(n, _), ..] => (n.clone(), 2),
+                    [Token::Assignment(_), Token::StringLiteral(s, _), ..] => {
+                        (format!("\"{}\"", s), 2)
+                    }
+                    [Token::Assignment(_), Token::Operator(op, _), Token::NumberLiteral(n, _), ..]
+                        if op == "-" =>
+                    {
+                        (format!("-{}", n), 3)
+                    }
+                    _ => continue,
+                };
+                // The literal must be the whole value.
+                let at_end = match rest.get(end) {
+                    None | Some(Token::BraceClose(_)) => true,
+                    Some(Token::Operator(op, _)) => op == "," || op == ";",
+                    _ => false,
+                };
+                if at_end {
+                    values.push((name.to_string(), value));
+                }
+            }
+            _ => {}
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::CodeTokenizer;
+
+    fn parse(source: &str) -> Vec<CodeASTNode> {
+        CodeParser::new(CodeTokenizer::new(source).tokenize()).parse()
+    }
+
+    /// The expressions of the single return statement in `source`.
+    fn returned(source: &str) -> Vec<Expression> {
+        match parse(source).as_slice() {
+            [CodeASTNode::ReturnStatement(exprs)] => exprs.clone(),
+            other => panic!("expected a return statement, got {:?}", other),
+        }
+    }
+
+    /// The name `expr` refers to, if it is a plain identifier.
+    fn identifier(expr: &Expression) -> Option<&str> {
+        match expr {
+            Expression::Identifier(name, _) => Some(name),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn return_keeps_calls_and_tables() {
+        match returned("return compute(x)").as_slice() {
+            [Expression::FunctionCall { callee, args, .. }] => {
+                assert_eq!(callee, "compute");
+                assert!(matches!(args.as_slice(), [Expression::Identifier(name, _)] if name == "x"));
+            }
+            other => panic!("expected a call, got {:?}", other),
+        }
+        match returned("return { ok = true }").as_slice() {
+            [Expression::TableConstructor { fields, items, .. }] => {
+                assert!(items.is_empty());
+                assert!(matches!(
+                    fields.as_slice(),
+                    [(key, Expression::Literal(value, _))] if key == "ok" && value == "true"
+                ));
+            }
+            other => panic!("expected a table, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn table_entries_keep_function_values() {
+        match returned("return { run = function() end, ok = true }").as_slice() {
+            [Expression::TableConstructor { fields, .. }] => {
+                let keys: Vec<&str> = fields.iter().map(|(key, _)| key.as_str()).collect();
+                assert_eq!(keys, ["run", "ok"]);
+                assert!(matches!(fields[0].1, Expression::Function { .. }));
+            }
+            other => panic!("expected a table, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn function_span_covers_function_through_end() {
+        let source = "local x = 1\n\nfunction greet(name)\n    print(name)\nend\n";
+        match parse(source).as_slice() {
+            [_, CodeASTNode::FunctionDef { span, .. }] => {
+                let text = &source[span.start..span.end];
+                assert_eq!(text, "function greet(name)\n    print(name)\nend");
+                assert_eq!((span.line, span.column), (3, 1));
+            }
+            other => panic!("expected a declaration and a function, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn large_token_vectors() {
+        let source: String = (0..2000)
+            .map(|i| format!("local function f{i}(a, b)\n    return a + b * {i}\nend\n"))
+            .collect();
+        let ast = parse(&source);
+        assert_eq!(ast.len(), 2000);
+        for (i, node) in ast.iter().enumerate() {
+            match node {
+                CodeASTNode::FunctionDef { name, params, .. } => {
+                    assert_eq!(name, &format!("f{}", i));
+                    assert_eq!(params.len(), 2);
+                }
+                other => panic!("expected a function, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn field_access_chains() {
+        match returned("return a.b.c").as_slice() {
+            [Expression::TableFieldAccess { table, key: FieldKey::Name(c), .. }] => {
+                assert_eq!(c, "c");
+                match table.as_ref() {
+                    Expression::TableFieldAccess { table, key: FieldKey::Name(b), .. } => {
+                        assert_eq!(b, "b");
+                        assert!(matches!(table.as_ref(), Expression::Identifier(a, _) if a == "a"));
+                    }
+                    other => panic!("expected a.b, got {:?}", other),
+                }
+            }
+            other => panic!("expected a field access, got {:?}", other),
+        }
+        match returned("return t[i]").as_slice() {
+            [Expression::TableFieldAccess { table, key: FieldKey::Index(index), .. }] => {
+                assert!(matches!(table.as_ref(), Expression::Identifier(t, _) if t == "t"));
+                assert!(matches!(index.as_ref(), Expression::Identifier(i, _) if i == "i"));
+            }
+            other => panic!("expected an index, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn method_calls() {
+        match returned("return obj:get(1)").as_slice() {
+            [Expression::MethodCall { receiver, method, args, .. }] => {
+                assert_eq!(identifier(receiver), Some("obj"));
+                assert_eq!(method, "get");
+                assert_eq!(args.len(), 1);
+            }
+            other => panic!("expected a method call, got {:?}", other),
+        }
+        match returned("return win:active_tab():set_title(t)").as_slice() {
+            [Expression::MethodCall { receiver, method, args, .. }] => {
+                assert_eq!(method, "set_title");
+                assert_eq!(args.len(), 1);
+                match receiver.as_ref() {
+                    Expression::MethodCall { receiver, method, args, .. } => {
+                        assert_eq!(method, "active_tab");
+                        assert!(args.is_empty());
+                        assert_eq!(identifier(receiver), Some("win"));
+                    }
+                    other => panic!("expected win:active_tab(), got {:?}", other),
+                }
+            }
+            other => panic!("expected a method call, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn semicolons_separate_statements() {
+        let names: Vec<String> = parse("local a = 1; local b = 2")
+            .into_iter()
+            .map(|node| match node {
+                CodeASTNode::VariableDeclaration { name, .. } => name,
+                other => panic!("expected a declaration, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(names, ["a", "b"]);
+    }
+
+    #[test]
+    fn break_and_goto() {
+        match parse("while true do\n    break\nend").as_slice() {
+            [CodeASTNode::WhileLoop { body, .. }] => assert_eq!(body, &[CodeASTNode::Break]),
+            other => panic!("expected a loop, got {:?}", other),
+        }
+        let jump = parse("goto done\nprint(1)\n::done::");
+        assert_eq!(jump.len(), 3);
+        assert_eq!(jump[0], CodeASTNode::Goto("done".to_string()));
+        assert!(matches!(jump[1], CodeASTNode::FunctionCallStmt { .. }));
+        assert_eq!(jump[2], CodeASTNode::Label("done".to_string()));
+    }
+
+    #[test]
+    fn table_field_types() {
+        match parse("local t = { name = \"x\", count = 3 }").as_slice() {
+            [CodeASTNode::ModuleDeclaration { exports, items, .. }] => {
+                let fields: Vec<(&str, &TypeInfo)> = exports
+                    .iter()
+                    .map(|export| (export.name.as_str(), &export.type_info))
+                    .collect();
+                assert_eq!(fields, [("name", &TypeInfo::String), ("count", &TypeInfo::Number)]);
+                assert!(items.is_empty());
+            }
+            other => panic!("expected a table declaration, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn logical_operators_in_conditions() {
+        match parse("if a and not b then\n    print(1)\nend").as_slice() {
+            [CodeASTNode::IfStatement { condition: Expression::BinaryOp { op, lhs, rhs }, .. }] => {
+                assert_eq!(op, "and");
+                assert_eq!(identifier(lhs), Some("a"));
+                match rhs.as_ref() {
+                    Expression::UnaryOp { op, operand, .. } => {
+                        assert_eq!(op, "not");
+                        assert_eq!(identifier(operand), Some("b"));
+                    }
+                    other => panic!("expected not b, got {:?}", other),
+                }
+            }
+            other => panic!("expected an if with a binary condition, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn field_assignment_targets() {
+        let targets: Vec<Expression> = parse("M.version = \"1.0\"\nt[k] = true")
+            .into_iter()
+            .map(|node| match node {
+                CodeASTNode::Assignment { mut lhs, .. } if lhs.len() == 1 => lhs.remove(0),
+                other => panic!("expected a single assignment, got {:?}", other),
+            })
+            .collect();
+        // Dotted targets keep their full name, like dotted function names
+        assert_eq!(identifier(&targets[0]), Some("M.version"));
+        match &targets[1] {
+            Expression::TableFieldAccess { table, key: FieldKey::Index(key), .. } => {
+                assert_eq!(identifier(table), Some("t"));
+                assert_eq!(identifier(key), Some("k"));
+            }
+            other => panic!("expected t[k], got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn require_without_parentheses() {
+        for source in ["local util = require \"app.util\"", "local util = require [[app.util]]"] {
+            match parse(source).as_slice() {
+                [CodeASTNode::VariableDeclaration { value: Some(value), .. }] => {
+                    match value.as_ref() {
+                        CodeASTNode::ReturnStatement(exprs) => {
+                            assert_eq!(parser_helpers::require_path(&exprs[0]), Some("app.util"));
+                        }
+                        other => panic!("expected an initializer, got {:?}", other),
+                    }
+                }
+                other => panic!("expected a declaration, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn triple_dash_comments_document_the_next_function() {
+        match parse("--- A helper\nfunction f() end").as_slice() {
+            [CodeASTNode::FunctionDef { name, doc, .. }] => {
+                assert_eq!(name, "f");
+                // Docs keep the comment text after the leading `--`
+                assert_eq!(doc.as_deref(), Some("- A helper"));
+            }
+            other => panic!("expected a documented function, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn leading_block_comment_documents_the_module() {
+        match parse("--[[ Helpers for strings ]]\nlocal M = {}\nreturn M").as_slice() {
+            [CodeASTNode::ModuleDeclaration { name, doc, .. }, CodeASTNode::ReturnStatement(_)] => {
+                assert_eq!(name, "M");
+                assert_eq!(doc.as_deref().map(str::trim), Some("Helpers for strings"));
+            }
+            other => panic!("expected a documented module, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn callee_span_points_at_the_callee() {
+        let source = "local n = 1\nfoo(n)";
+        let call = match &parse(source)[1] {
+            CodeASTNode::FunctionCallStmt { call, .. } => call.clone(),
+            other => panic!("expected a call statement, got {:?}", other),
+        };
+        match call {
+            Expression::FunctionCall { callee_span, .. } => {
+                assert_eq!(&source[callee_span.start..callee_span.end], "foo");
+                assert_eq!((callee_span.line, callee_span.column), (2, 1));
+            }
+            other => panic!("expected a call, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn enum_members_keep_their_numeric_values() {
+        let source = "---@enum Color\nlocal Color = { Red = 1, Green = 2, Blue = 3 }";
+        let annotations = match parse(source).as_slice() {
+            [node] => node.annotations().to_vec(),
+            other => panic!("expected one declaration, got {:?}", other),
+        };
+        match annotations.as_slice() {
+            [AnnotationASTNode::Enum { name, values, .. }] => {
+                assert_eq!(name, "Color");
+                let values: Vec<(&str, &str)> =
+                    values.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+                assert_eq!(values, [("Red", "1"), ("Green", "2"), ("Blue", "3")]);
+            }
+            other => panic!("expected an enum, got {:?}", other),
+        }
+    }
+}
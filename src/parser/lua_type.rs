@@ -0,0 +1,290 @@
+// src/parser/lua_type.rs
+//
+// Structured form of the type expressions used in annotations
+// (`---@param`, `---@return`, `---@field`).
+
+use crate::parser::ast::TypeInfo;
+use crate::parser::parser_helpers;
+
+/// A parsed annotation type expression.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LuaType {
+    /// A plain or dotted type name (e.g. `string`, `vim.api.keyset`).
+    Named(String),
+    /// `T[]`
+    Array(Box<LuaType>),
+    /// `table<K, V>`
+    Dict(Box<LuaType>, Box<LuaType>),
+    /// `fun(a: A, b: B): R`
+    Function {
+        params: Vec<(String, LuaType)>,
+        returns: Vec<LuaType>,
+    },
+    /// An inline table type, `{ name: T, other: U }`
+    Struct(Vec<(String, LuaType)>),
+    /// `A|B`
+    Union(Vec<LuaType>),
+    /// `T?`
+    Optional(Box<LuaType>),
+}
+
+impl LuaType {
+    /// Parse annotation type text, returning None if it is not a well-formed type.
+    pub fn parse(text: &str) -> Option<LuaType> {
+        let mut parser = TypeParser {
+            chars: text.chars().collect(),
+            pos: 0,
+        };
+        let lua_type = parser.parse_union()?;
+        parser.skip_whitespace();
+        if parser.pos == parser.chars.len() {
+            Some(lua_type)
+        } else {
+            None
+        }
+    }
+
+    /// The closest inference type; unions keep each distinct member.
+    pub fn to_type_info(&self) -> TypeInfo {
+        self.to_type_info_with(&parser_helpers::type_info_from_name)
+    }
+
+    /// Like `to_type_info`, with `named` deciding what each type name maps to
+    /// (e.g. to recognize the classes in a type registry).
+    pub fn to_type_info_with(&self, named: &dyn Fn(&str) -> TypeInfo) -> TypeInfo {
+        match self {
+            LuaType::Named(name) => named(name),
+            LuaType::Array(element) => TypeInfo::Array(Box::new(element.to_type_info_with(named))),
+            LuaType::Dict(key, value) => TypeInfo::Dict(
+                Box::new(key.to_type_info_with(named)),
+                Box::new(value.to_type_info_with(named)),
+            ),
+            LuaType::Function { .. } => TypeInfo::Function,
+            LuaType::Struct(_) => TypeInfo::Table,
+            LuaType::Optional(inner) => TypeInfo::Optional(Box::new(inner.to_type_info_with(named))),
+            LuaType::Union(members) => {
+                let mut types: Vec<TypeInfo> = Vec::new();
+                for member in members {
+                    let member = member.to_type_info_with(named);
+                    if !types.contains(&member) {
+                        types.push(member);
+                    }
+                }
+                match types.as_slice() {
+                    [single] => single.clone(),
+                    _ => TypeInfo::Union(types),
+                }
+            }
+        }
+    }
+}
+
+/// Recursive descent parser over the characters of a type expression.
+struct TypeParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl TypeParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consume `expected` (after any whitespace), returning whether it was present.
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_name(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            None
+        } else {
+            Some(self.chars[start..self.pos].iter().collect())
+        }
+    }
+
+    fn parse_union(&mut self) -> Option<LuaType> {
+        let mut members = vec![self.parse_postfix()?];
+        while self.eat('|') {
+            members.push(self.parse_postfix()?);
+        }
+        if members.len() == 1 {
+            members.pop()
+        } else {
+            Some(LuaType::Union(members))
+        }
+    }
+
+    /// A primary type followed by any number of `[]` and `?` suffixes.
+    fn parse_postfix(&mut self) -> Option<LuaType> {
+        let mut lua_type = self.parse_primary()?;
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some('[') && self.chars.get(self.pos + 1) == Some(&']') {
+                self.pos += 2;
+                lua_type = LuaType::Array(Box::new(lua_type));
+            } else if self.eat('?') {
+                lua_type = LuaType::Optional(Box::new(lua_type));
+            } else {
+                return Some(lua_type);
+            }
+        }
+    }
+
+    fn parse_primary(&mut self) -> Option<LuaType> {
+        if self.eat('(') {
+            let inner = self.parse_union()?;
+            return if self.eat(')') { Some(inner) } else { None };
+        }
+        if self.eat('{') {
+            return self.parse_struct();
+        }
+        let name = self.parse_name()?;
+        match name.as_str() {
+            "fun" if self.eat('(') => self.parse_function(),
+            "table" if self.eat('<') => {
+                let key = self.parse_union()?;
+                if !self.eat(',') {
+                    return None;
+                }
+                let value = self.parse_union()?;
+                if !self.eat('>') {
+                    return None;
+                }
+                Some(LuaType::Dict(Box::new(key), Box::new(value)))
+            }
+            _ => Some(LuaType::Named(name)),
+        }
+    }
+
+    /// The rest of a `fun(...)` type, after its opening parenthesis.
+    fn parse_function(&mut self) -> Option<LuaType> {
+        let mut params = Vec::new();
+        if !self.eat(')') {
+            loop {
+                let name = if self.eat_vararg() {
+                    "...".to_string()
+                } else {
+                    self.parse_name()?
+                };
+                // `name?: T` is an optional parameter
+                let optional = self.eat('?');
+                let mut param_type = if self.eat(':') {
+                    self.parse_union()?
+                } else {
+                    LuaType::Named("any".to_string())
+                };
+                if optional {
+                    param_type = LuaType::Optional(Box::new(param_type));
+                }
+                params.push((name, param_type));
+                if self.eat(')') {
+                    break;
+                }
+                if !self.eat(',') {
+                    return None;
+                }
+            }
+        }
+        let mut returns = Vec::new();
+        if self.eat(':') {
+            returns.push(self.parse_union()?);
+            while self.eat(',') {
+                returns.push(self.parse_union()?);
+            }
+        }
+        Some(LuaType::Function { params, returns })
+    }
+
+    /// The rest of an inline table type, after its opening brace.
+    fn parse_struct(&mut self) -> Option<LuaType> {
+        let mut fields = Vec::new();
+        if self.eat('}') {
+            return Some(LuaType::Struct(fields));
+        }
+        loop {
+            let name = self.parse_name()?;
+            let optional = self.eat('?');
+            if !self.eat(':') {
+                return None;
+            }
+            let mut field_type = self.parse_union()?;
+            if optional {
+                field_type = LuaType::Optional(Box::new(field_type));
+            }
+            fields.push((name, field_type));
+            if self.eat('}') {
+                return Some(LuaType::Struct(fields));
+            }
+            if !self.eat(',') {
+                return None;
+            }
+            // A trailing comma is allowed
+            if self.eat('}') {
+                return Some(LuaType::Struct(fields));
+            }
+        }
+    }
+
+    fn eat_vararg(&mut self) -> bool {
+        self.skip_whitespace();
+        let is_vararg = self.chars[self.pos..].starts_with(&['.', '.', '.']);
+        if is_vararg {
+            self.pos += 3;
+        }
+        is_vararg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(name: &str) -> LuaType {
+        LuaType::Named(name.to_string())
+    }
+
+    #[test]
+    fn complex_forms() {
+        assert_eq!(
+            LuaType::parse("table<string, number>"),
+            Some(LuaType::Dict(Box::new(named("string")), Box::new(named("number"))))
+        );
+        assert_eq!(LuaType::parse("string[]"), Some(LuaType::Array(Box::new(named("string")))));
+        assert_eq!(
+            LuaType::parse("fun(x: number): boolean"),
+            Some(LuaType::Function {
+                params: vec![("x".to_string(), named("number"))],
+                returns: vec![named("boolean")],
+            })
+        );
+        assert_eq!(LuaType::parse("A|B"), Some(LuaType::Union(vec![named("A"), named("B")])));
+        assert_eq!(
+            LuaType::parse("vim.api.keyset?"),
+            Some(LuaType::Optional(Box::new(named("vim.api.keyset"))))
+        );
+        assert_eq!(LuaType::parse("table<string>"), None);
+        assert_eq!(LuaType::parse("fun(x: number"), None);
+    }
+}
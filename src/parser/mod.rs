@@ -3,8 +3,10 @@ pub mod ast;
 pub mod ast_annotations_printer;
 pub mod ast_code_printer;
 pub mod code_parser;
+pub mod lua_type;
 pub mod parser_helpers;
 pub mod pretty_print;
+pub mod visitor;
 
 // Optionally, provide a unified interface here.
 pub use ast_annotations_printer::pretty_print_annotation_ast;
@@ -1,5 +1,6 @@
 // src/parser/parser_helpers.rs
 
+use crate::parser::ast::{Expression, TypeInfo};
 use crate::tokenizer::token::Token;
 
 /// Given a token, returns Some(token) if it is an annotation token,
@@ -19,3 +20,75 @@ pub fn extract_code_token(token: &Token) -> Option<&Token> {
         other => Some(other),
     }
 }
+
+/// Type of a literal kept as source text.
+pub fn literal_type(text: &str) -> TypeInfo {
+    if text.starts_with(['"', '\'']) {
+        TypeInfo::String
+    } else if text == "true" || text == "false" {
+        TypeInfo::Boolean
+    } else if text.parse::<f64>().is_ok() {
+        TypeInfo::Number
+    } else {
+        TypeInfo::Unknown
+    }
+}
+
+/// The module path of a `require("path")` call, or None for any other expression.
+pub fn require_path(expr: &Expression) -> Option<&str> {
+    let Expression::FunctionCall { callee, args, .. } = expr else {
+        return None;
+    };
+    match args.as_slice() {
+        [Expression::Literal(path, _)] if callee == "require" => path
+            .strip_prefix('"')
+            .and_then(|path| path.strip_suffix('"')),
+        _ => None,
+    }
+}
+
+/// Whether an operator or keyword token is a unary operator: `not`, `#` (length), `-`
+/// (negation) or `~` (bitwise not).
+pub fn is_unary_operator(op: &str) -> bool {
+    matches!(op, "not" | "#" | "-" | "~")
+}
+
+/// Precedence of the unary operators, between the binary operators and `^`.
+pub const UNARY_PRECEDENCE: u8 = 11;
+
+/// Precedence of a binary operator (higher binds tighter) and whether it is right
+/// associative, or None if `op` is not one. `and` and `or` are included although they
+/// are keywords rather than operator tokens.
+pub fn binary_precedence(op: &str) -> Option<(u8, bool)> {
+    let precedence = match op {
+        "or" => (1, false),
+        "and" => (2, false),
+        "<" | ">" | "<=" | ">=" | "~=" | "==" => (3, false),
+        "|" => (4, false),
+        "~" => (5, false),
+        "&" => (6, false),
+        "<<" | ">>" => (7, false),
+        ".." => (8, true),
+        "+" | "-" => (9, false),
+        "*" | "/" | "//" | "%" => (10, false),
+        // Unary operators (UNARY_PRECEDENCE) sit in between.
+        "^" => (12, true),
+        _ => return None,
+    };
+    Some(precedence)
+}
+
+/// Maps a builtin annotation type name (e.g. `string`) to its TypeInfo,
+/// returning TypeInfo::Unknown for anything else.
+pub fn type_info_from_name(name: &str) -> TypeInfo {
+    match name {
+        "nil" => TypeInfo::Nil,
+        "string" => TypeInfo::String,
+        "number" => TypeInfo::Number,
+        "integer" => TypeInfo::Integer,
+        "boolean" => TypeInfo::Boolean,
+        "table" => TypeInfo::Table,
+        "function" => TypeInfo::Function,
+        _ => TypeInfo::Unknown,
+    }
+}
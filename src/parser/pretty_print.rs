@@ -1,7 +1,9 @@
 // src/pretty_print.rs
 
+use crate::parser::ast::{AnnotationASTNode, CodeASTNode, Expression, FieldKey, TypeInfo};
 use crate::parser::ast_annotations_printer;
 use crate::parser::ast_code_printer;
+use crate::parser::parser_helpers;
 
 pub fn pretty_print_code_ast(ast: &[crate::parser::ast::CodeASTNode]) -> String {
     ast_code_printer::pretty_print_code_ast(ast, 0)
@@ -13,13 +15,677 @@ pub fn pretty_print_annotation_ast(ast: &[crate::parser::ast::AnnotationASTNode]
 
 pub fn pretty_print_merged(
     code_ast: &[crate::parser::ast::CodeASTNode],
-    annotation_ast: &[crate::parser::ast::AnnotationASTNode]
+    annotation_ast: &[crate::parser::ast::AnnotationASTNode],
 ) -> String {
     let mut output = String::new();
     output.push_str("=== Merged AST ===\n\n");
     output.push_str("---- Code AST ----\n");
     output.push_str(&ast_code_printer::pretty_print_code_ast(code_ast, 0));
     output.push_str("\n---- Annotation AST ----\n");
-    output.push_str(&ast_annotations_printer::pretty_print_annotation_ast(annotation_ast, 0));
+    output.push_str(&ast_annotations_printer::pretty_print_annotation_ast(
+        annotation_ast,
+        0,
+    ));
     output
 }
+
+/// Regenerate Lua source from a code AST.
+///
+/// Unlike `pretty_print_code_ast`, the output is valid Lua: tokenizing and parsing it again
+/// yields an equivalent AST, annotations included. The AST only keeps the types of a module
+/// table's entries, so their values come back as placeholders of those types (`0`, `""`, ...).
+pub fn emit_code(ast: &[CodeASTNode]) -> String {
+    let mut output = String::new();
+    for node in ast {
+        emit_node(node, 0, &mut output);
+    }
+    output
+}
+
+/// Render an annotation as LuaLS annotation text (e.g. `---@param name string The name`).
+///
+/// Parsing the result yields the same annotation again. A class's fields come out as
+/// `---@field` lines after the `---@class` line, so the result may span several lines.
+pub fn emit_annotation(node: &AnnotationASTNode) -> String {
+    match node {
+        AnnotationASTNode::Alias { name, variants } => {
+            let variants: Vec<String> = variants
+                .iter()
+                .map(|(value, desc)| match desc {
+                    Some(desc) => format!("{} # {}", value, desc),
+                    None => value.clone(),
+                })
+                .collect();
+            format!("---@alias {} {}", name, variants.join(" | "))
+        }
+        AnnotationASTNode::As { target } => format!("---@as {}", target),
+        AnnotationASTNode::Async => "---@async".to_string(),
+        AnnotationASTNode::Cast { variable, casts } => {
+            let casts: Vec<String> = casts
+                .iter()
+                .map(|(type_field, add)| format!("{}{}", if *add { "+" } else { "-" }, type_field))
+                .collect();
+            format!("---@cast {} {}", variable, casts.join(", "))
+                .trim_end()
+                .to_string()
+        }
+        AnnotationASTNode::Class {
+            name,
+            parents,
+            exact,
+            fields,
+        } => {
+            let mut line = "---@class ".to_string();
+            if *exact {
+                line.push_str("(exact) ");
+            }
+            line.push_str(name);
+            if !parents.is_empty() {
+                line.push_str(&format!(": {}", parents.join(", ")));
+            }
+            for (field, type_info) in fields {
+                line.push_str(&format!("\n---@field {} {}", field, type_info));
+            }
+            line
+        }
+        AnnotationASTNode::Deprecated => "---@deprecated".to_string(),
+        AnnotationASTNode::Diagnostic { action, diagnostic } => match diagnostic {
+            Some(names) => format!(
+                "---@diagnostic {}: {}",
+                action,
+                names.split(',').collect::<Vec<_>>().join(", ")
+            ),
+            None => format!("---@diagnostic {}", action),
+        },
+        AnnotationASTNode::Enum {
+            name, key, members, ..
+        } => {
+            let mut line = "---@enum ".to_string();
+            if *key {
+                line.push_str("(key) ");
+            }
+            line.push_str(name);
+            for (member, desc) in members {
+                line.push_str(&format!(" | {}", member));
+                if let Some(desc) = desc {
+                    line.push_str(&format!(" # {}", desc));
+                }
+            }
+            line
+        }
+        AnnotationASTNode::Field {
+            scope,
+            name,
+            type_field,
+            description,
+            ..
+        } => {
+            let mut line = "---@field ".to_string();
+            if let Some(scope) = scope {
+                line.push_str(&format!("{} ", scope));
+            }
+            line.push_str(&format!("{} {}", name, type_field));
+            push_description(&mut line, description);
+            line
+        }
+        AnnotationASTNode::Generic { params } => {
+            let params: Vec<String> = params
+                .iter()
+                .map(|(name, constraint)| match constraint {
+                    Some(constraint) => format!("{} : {}", name, constraint),
+                    None => name.clone(),
+                })
+                .collect();
+            format!("---@generic {}", params.join(", "))
+        }
+        AnnotationASTNode::Meta { name } => match name {
+            Some(name) => format!("---@meta {}", name),
+            None => "---@meta".to_string(),
+        },
+        AnnotationASTNode::Module { module_name } => format!("---@module {}", module_name),
+        AnnotationASTNode::Nondiscard => "---@nodiscard".to_string(),
+        AnnotationASTNode::Operator {
+            operator,
+            signature,
+        } => match signature {
+            Some(signature) => format!("---@operator {}: {}", operator, signature),
+            None => format!("---@operator {}", operator),
+        },
+        AnnotationASTNode::Overload { signature, .. } => format!("---@overload {}", signature),
+        AnnotationASTNode::Package => "---@package".to_string(),
+        AnnotationASTNode::Param {
+            name,
+            type_field,
+            description,
+            ..
+        } => {
+            let mut line = format!("---@param {} {}", name, type_field);
+            push_description(&mut line, description);
+            line
+        }
+        AnnotationASTNode::Private => "---@private".to_string(),
+        AnnotationASTNode::Protected => "---@protected".to_string(),
+        AnnotationASTNode::Return {
+            type_field,
+            name,
+            description,
+            ..
+        } => {
+            let mut line = format!("---@return {}", type_field);
+            if let Some(name) = name {
+                line.push_str(&format!(" {}", name));
+            }
+            push_description(&mut line, description);
+            line
+        }
+        AnnotationASTNode::See { reference } => format!("---@see {}", reference),
+        AnnotationASTNode::Source { path } => format!("---@source {}", path),
+        AnnotationASTNode::Type { type_field } => format!("---@type {}", type_field),
+        AnnotationASTNode::Vararg { type_field } => match type_field {
+            Some(type_field) => format!("---@vararg {}", type_field),
+            None => "---@vararg".to_string(),
+        },
+        AnnotationASTNode::Version {
+            version,
+            comparison,
+        } => format!(
+            "---@version {}{}",
+            comparison.as_deref().unwrap_or(""),
+            version
+        ),
+        AnnotationASTNode::Other { keyword, content } => format!("---@{} {}", keyword, content)
+            .trim_end()
+            .to_string(),
+    }
+}
+
+/// Append a description after a `#`, which keeps it apart from the preceding name or type.
+fn push_description(line: &mut String, description: &Option<String>) {
+    if let Some(description) = description {
+        line.push_str(&format!(" # {}", description));
+    }
+}
+
+/// Append one statement at the given indentation level.
+fn emit_node(node: &CodeASTNode, indent: usize, out: &mut String) {
+    let pad = "    ".repeat(indent);
+    match node {
+        CodeASTNode::ModuleDeclaration {
+            name,
+            exports,
+            items,
+            doc,
+            ..
+        } => {
+            emit_doc(doc, node, &pad, out);
+            if exports.is_empty() && items.is_empty() {
+                out.push_str(&format!("{}local {} = {{}}\n", pad, name));
+            } else {
+                out.push_str(&format!("{}local {} = {{\n", pad, name));
+                for item in items {
+                    out.push_str(&format!("{}    {},\n", pad, placeholder(item)));
+                }
+                for export in exports {
+                    out.push_str(&format!(
+                        "{}    {} = {},\n",
+                        pad,
+                        export.name,
+                        placeholder(&export.type_info)
+                    ));
+                }
+                out.push_str(&format!("{}}}\n", pad));
+            }
+        }
+        CodeASTNode::FunctionDef {
+            name,
+            is_local,
+            params,
+            doc,
+            body,
+            ..
+        } => {
+            emit_doc(doc, node, &pad, out);
+            let mut params: Vec<&str> = params.iter().map(|(param, _)| param.as_str()).collect();
+            // A `:` method's `self` is implicit; the parser adds it as the first parameter.
+            if name.contains(':') && params.first() == Some(&"self") {
+                params.remove(0);
+            }
+            let local = if *is_local { "local " } else { "" };
+            out.push_str(&format!(
+                "{}{}function {}({})\n",
+                pad,
+                local,
+                name,
+                params.join(", ")
+            ));
+            emit_block(body, indent + 1, out);
+            out.push_str(&format!("{}end\n", pad));
+        }
+        CodeASTNode::VariableDeclaration {
+            name, value, doc, ..
+        } => {
+            emit_doc(doc, node, &pad, out);
+            match value.as_deref() {
+                // The parser wraps a local's initializer in a single-expression return.
+                Some(CodeASTNode::ReturnStatement(exprs)) if !exprs.is_empty() => {
+                    out.push_str(&format!(
+                        "{}local {} = {}\n",
+                        pad,
+                        name,
+                        emit_expressions(exprs)
+                    ));
+                }
+                _ => out.push_str(&format!("{}local {}\n", pad, name)),
+            }
+        }
+        CodeASTNode::ReturnStatement(exprs) => {
+            if exprs.is_empty() {
+                out.push_str(&format!("{}return\n", pad));
+            } else {
+                out.push_str(&format!("{}return {}\n", pad, emit_expressions(exprs)));
+            }
+        }
+        CodeASTNode::Comment(text) => {
+            if text.contains('\n') {
+                out.push_str(&format!("{}--[[{}]]\n", pad, text));
+            } else {
+                out.push_str(&format!("{}--{}\n", pad, text));
+            }
+        }
+        CodeASTNode::TableConstructor(fields) => {
+            out.push_str(&format!("{}{}\n", pad, emit_table(fields, &[])));
+        }
+        CodeASTNode::Assignment { lhs, rhs, doc, .. } => {
+            emit_doc(doc, node, &pad, out);
+            out.push_str(&format!(
+                "{}{} = {}\n",
+                pad,
+                emit_expressions(lhs),
+                emit_expressions(rhs)
+            ));
+        }
+        CodeASTNode::IfStatement { doc, .. } => {
+            emit_doc(doc, node, &pad, out);
+            emit_if(node, "if", indent, out);
+            out.push_str(&format!("{}end\n", pad));
+        }
+        CodeASTNode::WhileLoop {
+            condition,
+            body,
+            doc,
+            ..
+        } => {
+            emit_doc(doc, node, &pad, out);
+            out.push_str(&format!("{}while {} do\n", pad, emit_expression(condition)));
+            emit_block(body, indent + 1, out);
+            out.push_str(&format!("{}end\n", pad));
+        }
+        CodeASTNode::ForNumeric {
+            var,
+            start,
+            end,
+            step,
+            body,
+            doc,
+            ..
+        } => {
+            emit_doc(doc, node, &pad, out);
+            let mut range = format!("{}, {}", emit_expression(start), emit_expression(end));
+            if let Some(step) = step {
+                range.push_str(&format!(", {}", emit_expression(step)));
+            }
+            out.push_str(&format!("{}for {} = {} do\n", pad, var, range));
+            emit_block(body, indent + 1, out);
+            out.push_str(&format!("{}end\n", pad));
+        }
+        CodeASTNode::ForGeneric {
+            vars,
+            exprs,
+            body,
+            doc,
+            ..
+        } => {
+            emit_doc(doc, node, &pad, out);
+            let exprs: Vec<String> = exprs.iter().map(emit_expression).collect();
+            out.push_str(&format!("{}for {} in {} do\n", pad, vars.join(", "), exprs.join(", ")));
+            emit_block(body, indent + 1, out);
+            out.push_str(&format!("{}end\n", pad));
+        }
+        CodeASTNode::DoBlock { body, doc, .. } => {
+            emit_doc(doc, node, &pad, out);
+            out.push_str(&format!("{}do\n", pad));
+            emit_block(body, indent + 1, out);
+            out.push_str(&format!("{}end\n", pad));
+        }
+        CodeASTNode::RepeatUntil {
+            body,
+            condition,
+            doc,
+            ..
+        } => {
+            emit_doc(doc, node, &pad, out);
+            out.push_str(&format!("{}repeat\n", pad));
+            emit_block(body, indent + 1, out);
+            out.push_str(&format!("{}until {}\n", pad, emit_expression(condition)));
+        }
+        CodeASTNode::FunctionCallStmt { call, doc, .. } => {
+            emit_doc(doc, node, &pad, out);
+            let call = match call {
+                // Call statements whose arguments weren't kept are emitted with none.
+                Expression::Identifier(name, _) => format!("{}()", name),
+                other => emit_expression(other),
+            };
+            out.push_str(&format!("{}{}\n", pad, call));
+        }
+        CodeASTNode::Break => out.push_str(&format!("{}break\n", pad)),
+        CodeASTNode::Goto(label) => out.push_str(&format!("{}goto {}\n", pad, label)),
+        CodeASTNode::Label(label) => out.push_str(&format!("{}::{}::\n", pad, label)),
+    }
+}
+
+/// Append an if statement without its `end`, folding a lone nested if in the else
+/// branch into an `elseif`.
+fn emit_if(node: &CodeASTNode, keyword: &str, indent: usize, out: &mut String) {
+    let CodeASTNode::IfStatement {
+        condition,
+        then_block,
+        else_block,
+        ..
+    } = node
+    else {
+        return;
+    };
+    let pad = "    ".repeat(indent);
+    out.push_str(&format!(
+        "{}{} {} then\n",
+        pad,
+        keyword,
+        emit_expression(condition)
+    ));
+    emit_block(then_block, indent + 1, out);
+    match else_block.as_deref() {
+        Some([nested @ CodeASTNode::IfStatement { doc: None, .. }]) => {
+            emit_if(nested, "elseif", indent, out);
+        }
+        Some(else_block) => {
+            out.push_str(&format!("{}else\n", pad));
+            emit_block(else_block, indent + 1, out);
+        }
+        None => {}
+    }
+}
+
+fn emit_block(body: &[CodeASTNode], indent: usize, out: &mut String) {
+    for node in body {
+        emit_node(node, indent, out);
+    }
+}
+
+/// Append a node's doc comment followed by its annotations.
+fn emit_doc(doc: &Option<String>, node: &CodeASTNode, pad: &str, out: &mut String) {
+    for line in doc.iter().flat_map(|doc| doc.lines()) {
+        out.push_str(&format!("{}--{}\n", pad, line));
+    }
+    for annotation in node.annotations() {
+        for line in emit_annotation(annotation).lines() {
+            out.push_str(&format!("{}{}\n", pad, line));
+        }
+    }
+}
+
+/// A value the parser infers `type_info` for, standing in for a module entry's value.
+fn placeholder(type_info: &TypeInfo) -> String {
+    match type_info {
+        TypeInfo::String => "\"\"".to_string(),
+        TypeInfo::Number | TypeInfo::Integer => "0".to_string(),
+        TypeInfo::Boolean => "false".to_string(),
+        TypeInfo::Function => "function() end".to_string(),
+        TypeInfo::Table => "{}".to_string(),
+        TypeInfo::Array(element) => format!("{{ {} }}", placeholder(element)),
+        TypeInfo::Dict(_, value) => format!("{{ value = {} }}", placeholder(value)),
+        _ => "nil".to_string(),
+    }
+}
+
+fn emit_expressions(exprs: &[Expression]) -> String {
+    exprs
+        .iter()
+        .map(emit_expression)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn emit_expression(expr: &Expression) -> String {
+    match expr {
+        Expression::Identifier(name, _) => name.clone(),
+        // Literals are kept as source text, strings with their quotes.
+        Expression::Literal(text, _) => text.clone(),
+        Expression::FunctionCall { callee, args, .. } => {
+            format!("{}({})", callee, emit_expressions(args))
+        }
+        Expression::MethodCall {
+            receiver,
+            method,
+            args,
+            ..
+        } => format!(
+            "{}:{}({})",
+            emit_expression(receiver),
+            method,
+            emit_expressions(args)
+        ),
+        Expression::BinaryOp { op, lhs, rhs } => format!(
+            "{} {} {}",
+            emit_operand(lhs, op, false),
+            op,
+            emit_operand(rhs, op, true)
+        ),
+        Expression::UnaryOp { op, operand, .. } => {
+            // Word operators need a space before their operand, and `- -x` must not become
+            // a comment.
+            let text = emit_expression(operand);
+            let separator =
+                if op.chars().all(char::is_alphabetic) || (op == "-" && text.starts_with('-')) {
+                    " "
+                } else {
+                    ""
+                };
+            if matches!(**operand, Expression::BinaryOp { ref op, .. } if op != "^") {
+                format!("{}{}({})", op, separator, text)
+            } else {
+                format!("{}{}{}", op, separator, text)
+            }
+        }
+        Expression::TableConstructor { fields, items, .. } => emit_table(fields, items),
+        Expression::TableFieldAccess { table, key, .. } => match key {
+            FieldKey::Name(name) => format!("{}.{}", emit_expression(table), name),
+            FieldKey::Index(index) => {
+                format!("{}[{}]", emit_expression(table), emit_expression(index))
+            }
+        },
+        Expression::Function { params, body, .. } => {
+            let params: Vec<&str> = params.iter().map(|(param, _)| param.as_str()).collect();
+            let mut out = format!("function({})\n", params.join(", "));
+            emit_block(body, 1, &mut out);
+            out.push_str("end");
+            out
+        }
+    }
+}
+
+/// Emit an operand of the binary operator `op`, parenthesized when it is an operation
+/// that would otherwise bind differently.
+fn emit_operand(operand: &Expression, op: &str, is_rhs: bool) -> String {
+    let text = emit_expression(operand);
+    let inner = match operand {
+        Expression::BinaryOp { op: inner, .. } => inner,
+        // Only `^` binds tighter than a unary operator, so `(-x) ^ 2` keeps its parentheses.
+        Expression::UnaryOp { .. } if op == "^" && !is_rhs => return format!("({})", text),
+        _ => return text,
+    };
+    let (Some((precedence, right_associative)), Some((inner_precedence, _))) = (
+        parser_helpers::binary_precedence(op),
+        parser_helpers::binary_precedence(inner),
+    ) else {
+        return text;
+    };
+    if inner_precedence < precedence
+        || (inner_precedence == precedence && is_rhs != right_associative)
+    {
+        format!("({})", text)
+    } else {
+        text
+    }
+}
+
+/// Emit a table constructor, positional entries first.
+fn emit_table(fields: &[(String, Expression)], items: &[Expression]) -> String {
+    if fields.is_empty() && items.is_empty() {
+        return "{}".to_string();
+    }
+    let entries: Vec<String> = items
+        .iter()
+        .map(emit_expression)
+        .chain(fields.iter().map(|(key, value)| {
+            let is_name = key
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphabetic() || c == '_')
+                && key.chars().all(|c| c.is_alphanumeric() || c == '_');
+            if is_name {
+                format!("{} = {}", key, emit_expression(value))
+            } else {
+                format!("[\"{}\"] = {}", key, emit_expression(value))
+            }
+        }))
+        .collect();
+    format!("{{ {} }}", entries.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::annotation_parser::AnnotationParser;
+    use crate::parser::code_parser::CodeParser;
+    use crate::tokenizer::CodeTokenizer;
+
+    fn parse(source: &str) -> Vec<CodeASTNode> {
+        CodeParser::new(CodeTokenizer::new(source).tokenize()).parse()
+    }
+
+    #[test]
+    fn emitted_code_parses_back() {
+        let source = r#"
+---@class Point
+---@field x number
+local M = { 1, 2, name = "point", scale = 2.5, enabled = true, list = { "a" }, opts = { k = 1 } }
+
+--- Move a point
+---@param dx number
+---@return number
+function M.move(p, dx)
+    local x = p.x + dx * 2
+    if x > 10 then
+        x = 10
+    elseif x < -10 then
+        x = -(10)
+    else
+        x = x
+    end
+    for i = 1, #p, 2 do
+        print(i)
+    end
+    for k, v in pairs(p) do
+        print(k, v)
+    end
+    while not p.done do
+        p.done = true
+    end
+    repeat
+        x = x - 1
+    until x <= 0
+    do
+        local t = { a = 1, [2] = "b" }
+    end
+    return x .. "!"
+end
+
+function M:get(key)
+    return self[key]
+end
+
+return M
+"#;
+        let ast = parse(source);
+        let emitted = emit_code(&ast);
+        let reparsed = parse(&emitted);
+        assert_eq!(reparsed.len(), ast.len());
+        assert_eq!(emit_code(&reparsed), emitted);
+        for (before, after) in ast.iter().zip(&reparsed) {
+            assert_eq!(before.annotations(), after.annotations());
+            if let (
+                CodeASTNode::ModuleDeclaration { exports, items, .. },
+                CodeASTNode::ModuleDeclaration {
+                    exports: reparsed_exports,
+                    items: reparsed_items,
+                    ..
+                },
+            ) = (before, after)
+            {
+                assert_eq!(exports, reparsed_exports);
+                assert_eq!(items, reparsed_items);
+            }
+        }
+    }
+
+    #[test]
+    fn emitted_annotations_parse_back() {
+        let lines = [
+            "---@alias Mode \"fast\" # Quick | \"slow\"",
+            "---@as string",
+            "---@async",
+            "---@cast value +string, -nil",
+            "---@class (exact) Dog : Animal, Named",
+            "---@deprecated",
+            "---@diagnostic disable-next-line: unused-local, undefined-global",
+            "---@enum (key) Color",
+            "---@field private age integer # Years",
+            "---@generic T : table, K",
+            "---@meta socket",
+            "---@module util",
+            "---@nodiscard",
+            "---@operator add(Vector): Vector",
+            "---@overload fun(x: number): string",
+            "---@package",
+            "---@param name string|nil # The name",
+            "---@private",
+            "---@protected",
+            "---@return boolean ok # Whether it worked",
+            "---@see Dog.bark",
+            "---@source file.lua:10",
+            "---@type table<string, number>",
+            "---@vararg any",
+            "---@version >5.2",
+            "---@custom whatever text",
+        ];
+        let parse_annotations = |text: &str| {
+            let tokens = CodeTokenizer::new(text).tokenize();
+            AnnotationParser::new(&tokens).parse()
+        };
+        let mut variants = std::collections::HashSet::new();
+        for line in lines {
+            let parsed = parse_annotations(line);
+            assert_eq!(parsed.len(), 1, "{}", line);
+            variants.insert(std::mem::discriminant(&parsed[0]));
+            let emitted = emit_annotation(&parsed[0]);
+            assert_eq!(parse_annotations(&emitted), parsed, "{} emitted as {}", line, emitted);
+        }
+        assert_eq!(variants.len(), lines.len(), "each line covers another variant");
+    }
+
+    #[test]
+    fn methods_keep_self_implicit() {
+        let emitted = emit_code(&parse("function M:get(x)\n    return x\nend\n"));
+        assert!(emitted.contains("function M:get(x)"), "{}", emitted);
+    }
+}
@@ -0,0 +1,208 @@
+// src/parser/visitor.rs
+//
+// Traversal of code ASTs, for analyses that only care about some of the nodes.
+
+use crate::parser::ast::{CodeASTNode, Expression, FieldKey};
+
+/// Callbacks for `walk`. Every method does nothing by default, so an implementation only
+/// overrides the ones it needs.
+pub trait Visitor {
+    /// Called for every node, before the node-specific method.
+    fn visit_node(&mut self, _node: &CodeASTNode) {}
+
+    /// Called for every node once its nested blocks have been walked.
+    fn leave_node(&mut self, _node: &CodeASTNode) {}
+
+    /// Called for each `CodeASTNode::FunctionDef`, before its body is walked.
+    fn visit_function_def(&mut self, _node: &CodeASTNode) {}
+
+    /// Called for each `CodeASTNode::VariableDeclaration`.
+    fn visit_variable_declaration(&mut self, _node: &CodeASTNode) {}
+
+    /// Called for each `CodeASTNode::Assignment`.
+    fn visit_assignment(&mut self, _node: &CodeASTNode) {}
+
+    /// Called for each `CodeASTNode::FunctionCallStmt`.
+    fn visit_call_statement(&mut self, _node: &CodeASTNode) {}
+
+    /// Called for each `return` statement with its values.
+    fn visit_return(&mut self, _values: &[Expression]) {}
+
+    /// Called for every expression, outermost first.
+    fn visit_expression(&mut self, _expr: &Expression) {}
+
+    /// Called for every expression once the expressions and bodies in it have been walked.
+    fn leave_expression(&mut self, _expr: &Expression) {}
+}
+
+/// Walk the nodes in order, recursing into function bodies, `if`/`while`/`for`/`repeat`
+/// blocks and `do` blocks, and into the expressions they contain.
+pub fn walk<V: Visitor + ?Sized>(ast: &[CodeASTNode], visitor: &mut V) {
+    for node in ast {
+        walk_node(node, visitor);
+    }
+}
+
+fn walk_node<V: Visitor + ?Sized>(node: &CodeASTNode, visitor: &mut V) {
+    visitor.visit_node(node);
+    match node {
+        CodeASTNode::FunctionDef { body, .. } => {
+            visitor.visit_function_def(node);
+            walk(body, visitor);
+        }
+        CodeASTNode::VariableDeclaration { value, .. } => {
+            visitor.visit_variable_declaration(node);
+            if let Some(value) = value {
+                walk_node(value, visitor);
+            }
+        }
+        CodeASTNode::Assignment { rhs, .. } => {
+            visitor.visit_assignment(node);
+            walk_expressions(rhs, visitor);
+        }
+        CodeASTNode::ReturnStatement(values) => {
+            visitor.visit_return(values);
+            walk_expressions(values, visitor);
+        }
+        CodeASTNode::TableConstructor(fields) => {
+            for (_, value) in fields {
+                walk_expression(value, visitor);
+            }
+        }
+        CodeASTNode::IfStatement {
+            condition,
+            then_block,
+            else_block,
+            ..
+        } => {
+            walk_expression(condition, visitor);
+            walk(then_block, visitor);
+            if let Some(else_block) = else_block {
+                walk(else_block, visitor);
+            }
+        }
+        CodeASTNode::WhileLoop {
+            condition, body, ..
+        } => {
+            walk_expression(condition, visitor);
+            walk(body, visitor);
+        }
+        CodeASTNode::ForNumeric {
+            start,
+            end,
+            step,
+            body,
+            ..
+        } => {
+            walk_expression(start, visitor);
+            walk_expression(end, visitor);
+            if let Some(step) = step {
+                walk_expression(step, visitor);
+            }
+            walk(body, visitor);
+        }
+        CodeASTNode::ForGeneric { exprs, body, .. } => {
+            for expr in exprs {
+                walk_expression(expr, visitor);
+            }
+            walk(body, visitor);
+        }
+        CodeASTNode::DoBlock { body, .. } => walk(body, visitor),
+        CodeASTNode::RepeatUntil {
+            body, condition, ..
+        } => {
+            walk(body, visitor);
+            walk_expression(condition, visitor);
+        }
+        CodeASTNode::FunctionCallStmt { call, .. } => {
+            visitor.visit_call_statement(node);
+            walk_expression(call, visitor);
+        }
+        CodeASTNode::ModuleDeclaration { .. }
+        | CodeASTNode::Comment(_)
+        | CodeASTNode::Break
+        | CodeASTNode::Goto(_)
+        | CodeASTNode::Label(_) => {}
+    }
+    visitor.leave_node(node);
+}
+
+fn walk_expressions<V: Visitor + ?Sized>(exprs: &[Expression], visitor: &mut V) {
+    for expr in exprs {
+        walk_expression(expr, visitor);
+    }
+}
+
+fn walk_expression<V: Visitor + ?Sized>(expr: &Expression, visitor: &mut V) {
+    visitor.visit_expression(expr);
+    match expr {
+        Expression::Identifier(..) | Expression::Literal(..) => {}
+        Expression::FunctionCall { args, .. } => walk_expressions(args, visitor),
+        Expression::MethodCall { receiver, args, .. } => {
+            walk_expression(receiver, visitor);
+            walk_expressions(args, visitor);
+        }
+        Expression::BinaryOp { lhs, rhs, .. } => {
+            walk_expression(lhs, visitor);
+            walk_expression(rhs, visitor);
+        }
+        Expression::UnaryOp { operand, .. } => walk_expression(operand, visitor),
+        Expression::TableConstructor { fields, items, .. } => {
+            for (_, value) in fields {
+                walk_expression(value, visitor);
+            }
+            walk_expressions(items, visitor);
+        }
+        Expression::TableFieldAccess { table, key, .. } => {
+            walk_expression(table, visitor);
+            if let FieldKey::Index(index) = key {
+                walk_expression(index, visitor);
+            }
+        }
+        Expression::Function { body, .. } => walk(body, visitor),
+    }
+    visitor.leave_expression(expr);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::code_parser::CodeParser;
+    use crate::tokenizer::CodeTokenizer;
+
+    #[derive(Default)]
+    struct FunctionCounter {
+        names: Vec<String>,
+    }
+
+    impl Visitor for FunctionCounter {
+        fn visit_function_def(&mut self, node: &CodeASTNode) {
+            if let CodeASTNode::FunctionDef { name, .. } = node {
+                self.names.push(name.clone());
+            }
+        }
+    }
+
+    #[test]
+    fn counts_nested_function_definitions() {
+        let source = [
+            "local M = {}",
+            "function M.outer()",
+            "    local function inner()",
+            "        if true then",
+            "            local function deepest() end",
+            "        end",
+            "    end",
+            "    for i = 1, 2 do",
+            "        local function in_loop() end",
+            "    end",
+            "end",
+            "return M",
+        ]
+        .join("\n");
+        let ast = CodeParser::new(CodeTokenizer::new(&source).tokenize()).parse();
+        let mut counter = FunctionCounter::default();
+        walk(&ast, &mut counter);
+        assert_eq!(counter.names, ["M.outer", "inner", "deepest", "in_loop"]);
+    }
+}
@@ -1,7 +1,11 @@
 // src/project_context.rs
 
-use crate::frameworks::FrameworkRegistry;
-use crate::parser::ast::{ExportItem, TypeInfo};
+use crate::error::LuaToolsError;
+use crate::frameworks::{FrameworkRegistry, FrameworkVersion};
+use crate::{log_message, log_verbose};
+use crate::parser::ast::{AnnotationASTNode, CodeASTNode, ExportItem, Expression, TypeInfo};
+use crate::parser::lua_type::LuaType;
+use crate::parser::parser_helpers;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -46,6 +50,30 @@ pub struct CustomType {
     pub description: Option<String>,
     pub is_alias: bool,
     pub variants: Vec<String>,  // For alias/enum types
+    pub parents: Vec<String>,   // Classes this one inherits from (`---@class Dog : Animal`)
+}
+
+impl CustomType {
+    /// The type an alias stands for: the union of its variants, each a string literal
+    pub fn alias_type(&self) -> Option<TypeInfo> {
+        if !self.is_alias || self.variants.is_empty() {
+            return None;
+        }
+        Some(TypeInfo::Union(
+            self.variants.iter()
+                .map(|variant| TypeInfo::Literal(variant_literal(variant)))
+                .collect(),
+        ))
+    }
+}
+
+/// An alias or enum variant as a literal: numbers as written, anything else as a string
+fn variant_literal(variant: &str) -> String {
+    if variant.parse::<f64>().is_ok() {
+        variant.to_string()
+    } else {
+        format!("\"{}\"", variant)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -72,6 +100,8 @@ pub enum LuaVersion {
     Lua52,
     Lua53,
     Lua54,
+    /// LuaJIT 2.x: Lua 5.1 semantics plus goto, the `bit` module and the FFI
+    LuaJIT,
 }
 
 impl LuaVersion {
@@ -82,11 +112,12 @@ impl LuaVersion {
             LuaVersion::Lua52 => "5.2", 
             LuaVersion::Lua53 => "5.3",
             LuaVersion::Lua54 => "5.4",
+            LuaVersion::LuaJIT => "LuaJIT",
         }
     }
     
     /// Parse version string
-    pub fn from_str(version: &str) -> Option<Self> {
+    pub fn parse(version: &str) -> Option<Self> {
         match version {
             "5.1" => Some(LuaVersion::Lua51),
             "5.2" => Some(LuaVersion::Lua52),
@@ -97,10 +128,34 @@ impl LuaVersion {
             "52" => Some(LuaVersion::Lua52),
             "53" => Some(LuaVersion::Lua53),
             "54" => Some(LuaVersion::Lua54),
+            "LuaJIT" | "luajit" | "jit" => Some(LuaVersion::LuaJIT),
             _ => None,
         }
     }
     
+    /// Parse version string
+    #[deprecated(note = "use `LuaVersion::parse` instead")]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(version: &str) -> Option<Self> {
+        Self::parse(version)
+    }
+    
+    /// What to use instead of a standard library function (e.g. `unpack`, `math.pow`) that this
+    /// version no longer provides, or None if the name is not a removed function
+    pub fn removed_function_replacement(&self, name: &str) -> Option<&'static str> {
+        let (replacement, removed) = match name {
+            "setfenv" | "getfenv" => ("`_ENV`", !self.has_feature(name)),
+            "loadstring" => ("`load`", !self.has_feature(name)),
+            "unpack" => ("`table.unpack`", !self.has_feature(name)),
+            // Lua 5.2 still provides these through its default compatibility options
+            "table.getn" => ("the `#` operator", matches!(self, LuaVersion::Lua53 | LuaVersion::Lua54)),
+            "math.pow" => ("the `^` operator", matches!(self, LuaVersion::Lua53 | LuaVersion::Lua54)),
+            "math.log10" => ("`math.log(x, 10)`", matches!(self, LuaVersion::Lua53 | LuaVersion::Lua54)),
+            _ => return None,
+        };
+        removed.then_some(replacement)
+    }
+    
     /// Check if feature is available in this version
     pub fn has_feature(&self, feature: &str) -> bool {
         match (self, feature) {
@@ -111,6 +166,11 @@ impl LuaVersion {
             (LuaVersion::Lua51, "unpack") => true,
             (LuaVersion::Lua51, "loadstring") => true,
             
+            // LuaJIT keeps the 5.1 environment functions and adds its own libraries
+            (LuaVersion::LuaJIT, "module" | "setfenv" | "getfenv" | "unpack" | "loadstring") => true,
+            (LuaVersion::LuaJIT, "bit" | "ffi" | "jit") => true,
+            (LuaVersion::LuaJIT, "bit32") => false,
+            
             // Lua 5.2+ features
             (LuaVersion::Lua51, "goto") => false,
             (LuaVersion::Lua51, "bit32") => false,
@@ -118,12 +178,12 @@ impl LuaVersion {
             (_, "bit32") => true,
             
             // Lua 5.3+ features
-            (LuaVersion::Lua51, "integer_division") => false,
+            (LuaVersion::Lua51 | LuaVersion::LuaJIT, "integer_division") => false,
             (LuaVersion::Lua52, "integer_division") => false,
             (_, "integer_division") => true,
             
             // Lua 5.3+ utf8 library
-            (LuaVersion::Lua51, "utf8") => false,
+            (LuaVersion::Lua51 | LuaVersion::LuaJIT, "utf8") => false,
             (LuaVersion::Lua52, "utf8") => false,
             (_, "utf8") => true,
             
@@ -137,13 +197,63 @@ impl LuaVersion {
     }
 }
 
-#[derive(Debug)]
+/// A type file regenerated from an existing one merged with the observed types
+#[derive(Debug, Clone)]
+pub struct TypeFileMerge {
+    /// The merged type file
+    pub content: String,
+    /// Definitions whose shape differs between the existing file and the project;
+    /// the existing definition is kept for each
+    pub conflicts: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
 pub struct TypeRegistry {
     pub standard_types: HashMap<&'static str, TypeInfo>,
     pub custom_types: HashMap<String, CustomType>,
     pub function_signatures: HashMap<String, FunctionSignature>,
 }
 
+impl TypeRegistry {
+    /// A field of a class, looked up on the class first and then on its parents in order
+    pub fn find_field(&self, class: &str, field: &str) -> Option<&TypeField> {
+        self.find_in_hierarchy(class, &mut HashSet::new(), &|custom_type| {
+            custom_type.fields.iter().find(|f| f.name == field)
+        })
+    }
+    
+    /// A method of a class, looked up on the class first and then on its parents in order
+    pub fn find_method(&self, class: &str, method: &str) -> Option<&FunctionSignature> {
+        self.find_in_hierarchy(class, &mut HashSet::new(), &|custom_type| custom_type.methods.get(method))
+    }
+    
+    /// Whether `class` is `ancestor` or inherits from it
+    pub fn is_subclass(&self, class: &str, ancestor: &str) -> bool {
+        self.find_in_hierarchy(class, &mut HashSet::new(), &|custom_type| {
+            (custom_type.name == ancestor).then_some(custom_type)
+        })
+        .is_some()
+    }
+    
+    /// Depth-first search of a class and its ancestors; `visited` guards against cyclic inheritance
+    fn find_in_hierarchy<'a, T>(
+        &'a self,
+        class: &str,
+        visited: &mut HashSet<String>,
+        find: &dyn Fn(&'a CustomType) -> Option<&'a T>,
+    ) -> Option<&'a T> {
+        if !visited.insert(class.to_string()) {
+            return None;
+        }
+        let custom_type = self.custom_types.get(class)?;
+        if let Some(found) = find(custom_type) {
+            return Some(found);
+        }
+        custom_type.parents.iter()
+            .find_map(|parent| self.find_in_hierarchy(parent, visited, find))
+    }
+}
+
 pub struct ProjectContext {
     /// All modules in the project, keyed by their module name
     pub modules: HashMap<String, ModuleInfo>,
@@ -159,10 +269,22 @@ pub struct ProjectContext {
     pub type_file_processed: bool,
     /// Target Lua version for type checking and features
     pub lua_version: LuaVersion,
-    /// Framework registry for framework-specific type definitions
+    /// Framework registry for framework-specific type definitions.
+    /// Created on first use so that constructing a context does no filesystem scans.
     pub framework_registry: Option<FrameworkRegistry>,
     /// Detected frameworks in the project
     pub detected_frameworks: Vec<(String, String)>, // (name, version)
+    /// Global names provided by the applied frameworks (e.g. `vim`, `love`)
+    pub framework_globals: HashSet<String>,
+    /// Search templates used to resolve require() paths, following package.path
+    /// semantics (`?` is replaced by the module path with dots turned into separators)
+    pub package_path: Vec<String>,
+}
+
+impl Default for ProjectContext {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ProjectContext {
@@ -184,12 +306,12 @@ impl ProjectContext {
         registry.standard_types.insert("boolean", TypeInfo::Boolean);
         registry.standard_types.insert("table", TypeInfo::Table);
         registry.standard_types.insert("function", TypeInfo::Function);
-        registry.standard_types.insert("nil", TypeInfo::Unknown);
+        registry.standard_types.insert("nil", TypeInfo::Nil);
         registry.standard_types.insert("any", TypeInfo::Unknown);
         
         // Add Lua 5.3+ integer type
         if matches!(lua_version, LuaVersion::Lua53 | LuaVersion::Lua54) {
-            registry.standard_types.insert("integer", TypeInfo::Number);
+            registry.standard_types.insert("integer", TypeInfo::Integer);
         }
         
         let mut ctx = Self {
@@ -200,8 +322,10 @@ impl ProjectContext {
             dependency_graph: HashMap::new(),
             type_file_processed: false,
             lua_version,
-            framework_registry: Some(FrameworkRegistry::new()),
+            framework_registry: None,
             detected_frameworks: Vec::new(),
+            framework_globals: HashSet::new(),
+            package_path: vec!["?.lua".to_string(), "?/init.lua".to_string()],
         };
         
         // Load standard library definitions
@@ -233,6 +357,12 @@ impl ProjectContext {
             LuaVersion::Lua51 => {
                 // No bit32 or utf8 in 5.1
             },
+            LuaVersion::LuaJIT => {
+                // LuaJIT ships its own bit operations, FFI and JIT control modules
+                std_libs.push("bit");
+                std_libs.push("ffi");
+                std_libs.push("jit");
+            },
             LuaVersion::Lua52 => {
                 // Lua 5.2 has bit32 but no utf8
                 std_libs.push("bit32");
@@ -262,6 +392,8 @@ impl ProjectContext {
                 "table" => self.load_table_library(),
                 "math" => self.load_math_library(),
                 "bit32" => self.load_bit32_library(),
+                "bit" => self.load_bit_library(),
+                "ffi" => self.load_ffi_library(),
                 "utf8" => self.load_utf8_library(),
                 // Add more as needed
                 _ => {}
@@ -302,8 +434,8 @@ impl ProjectContext {
         
         // Version-specific global functions
         match self.lua_version {
-            LuaVersion::Lua51 => {
-                // Lua 5.1 specific globals
+            LuaVersion::Lua51 | LuaVersion::LuaJIT => {
+                // Lua 5.1 specific globals (kept by LuaJIT)
                 global_functions.push(("getfenv", TypeInfo::Table));
                 global_functions.push(("loadstring", TypeInfo::Function));
                 global_functions.push(("module", TypeInfo::Unknown));
@@ -433,6 +565,111 @@ impl ProjectContext {
         }
     }
     
+    /// Load bit library (LuaJIT's BitOp module)
+    fn load_bit_library(&mut self) {
+        if !matches!(self.lua_version, LuaVersion::LuaJIT) {
+            return; // Skip if not available
+        }
+        
+        let module_name = "bit";
+        
+        // BitOp functions
+        let bit_functions = vec![
+            ("arshift", TypeInfo::Number),
+            ("band", TypeInfo::Number),
+            ("bnot", TypeInfo::Number),
+            ("bor", TypeInfo::Number),
+            ("bswap", TypeInfo::Number),
+            ("bxor", TypeInfo::Number),
+            ("lshift", TypeInfo::Number),
+            ("rol", TypeInfo::Number),
+            ("ror", TypeInfo::Number),
+            ("rshift", TypeInfo::Number),
+            ("tobit", TypeInfo::Number),
+            ("tohex", TypeInfo::String),
+        ];
+        
+        for (fn_name, ret_type) in bit_functions {
+            let export = ExportItem {
+                name: fn_name.to_string(),
+                type_info: ret_type.clone(),
+            };
+            
+            // Add to module exports
+            if let Some(module) = self.modules.get_mut(module_name) {
+                module.exports.insert(fn_name.to_string(), export);
+            }
+            
+            // Add to function signatures
+            let full_name = format!("{}.{}", module_name, fn_name);
+            let sig = FunctionSignature {
+                name: full_name.clone(),
+                parameters: Vec::new(),
+                return_types: vec![ret_type],
+                description: None,
+                is_method: false,
+            };
+            
+            self.type_registry.function_signatures.insert(full_name, sig);
+        }
+    }
+    
+    /// Load ffi library (LuaJIT)
+    fn load_ffi_library(&mut self) {
+        if !matches!(self.lua_version, LuaVersion::LuaJIT) {
+            return; // Skip if not available
+        }
+        
+        let module_name = "ffi";
+        
+        // FFI functions
+        let ffi_functions = vec![
+            ("cast", TypeInfo::Unknown),
+            ("cdef", TypeInfo::Unknown),
+            ("gc", TypeInfo::Unknown),
+            ("istype", TypeInfo::Boolean),
+            ("load", TypeInfo::Table),
+            ("metatype", TypeInfo::Unknown),
+            ("new", TypeInfo::Unknown),
+            ("sizeof", TypeInfo::Number),
+            ("string", TypeInfo::String),
+            ("typeof", TypeInfo::Unknown),
+        ];
+        
+        for (fn_name, ret_type) in ffi_functions {
+            let export = ExportItem {
+                name: fn_name.to_string(),
+                type_info: ret_type.clone(),
+            };
+            
+            // Add to module exports
+            if let Some(module) = self.modules.get_mut(module_name) {
+                module.exports.insert(fn_name.to_string(), export);
+            }
+            
+            // Add to function signatures
+            let full_name = format!("{}.{}", module_name, fn_name);
+            let sig = FunctionSignature {
+                name: full_name.clone(),
+                parameters: Vec::new(),
+                return_types: vec![ret_type],
+                description: None,
+                is_method: false,
+            };
+            
+            self.type_registry.function_signatures.insert(full_name, sig);
+        }
+        
+        // The C library namespace
+        if let Some(module) = self.modules.get_mut(module_name) {
+            let c_export = ExportItem {
+                name: "C".to_string(),
+                type_info: TypeInfo::Table,
+            };
+            module.exports.insert("C".to_string(), c_export);
+        }
+    }
+    
     /// Load utf8 library (Lua 5.3+)
     fn load_utf8_library(&mut self) {
         if !matches!(self.lua_version, LuaVersion::Lua53 | LuaVersion::Lua54) {
@@ -545,7 +782,7 @@ impl ProjectContext {
         
         // Version-specific table functions
         match self.lua_version {
-            LuaVersion::Lua51 => {
+            LuaVersion::Lua51 | LuaVersion::LuaJIT => {
                 // maxn is only in 5.1
                 table_functions.push(("maxn", TypeInfo::Number));
                 
@@ -624,7 +861,7 @@ impl ProjectContext {
         
         // Version-specific math functions
         match self.lua_version {
-            LuaVersion::Lua51 => {
+            LuaVersion::Lua51 | LuaVersion::LuaJIT => {
                 // Lua 5.1 functions
                 math_functions.push(("pow", TypeInfo::Number));
                 math_functions.push(("log10", TypeInfo::Number));
@@ -717,6 +954,9 @@ impl ProjectContext {
                 // Attempt to detect Lua version from project files
                 self.detect_lua_version(&current_dir);
                 
+                // Detect frameworks
+                self.detect_frameworks(&current_dir);
+                
                 return Some(current_dir);
             }
             
@@ -728,10 +968,10 @@ impl ProjectContext {
         self.project_root = Some(starting_path.to_path_buf());
         
         // Try to detect Lua version anyway
-        self.detect_lua_version(&starting_path.to_path_buf());
+        self.detect_lua_version(starting_path);
         
         // Detect frameworks
-        self.detect_frameworks(&starting_path.to_path_buf());
+        self.detect_frameworks(starting_path);
         
         Some(starting_path.to_path_buf())
     }
@@ -748,24 +988,23 @@ impl ProjectContext {
                 if content.contains("\"runtime.version\":") || content.contains("\"runtime\": {") {
                     if content.contains("\"5.1\"") || content.contains("\"51\"") || content.contains("\"Lua 5.1\"") {
                         self.lua_version = LuaVersion::Lua51;
-                        println!("Detected Lua 5.1 from .luarc.json");
+                        log_verbose!("Detected Lua 5.1 from .luarc.json");
                         return;
                     } else if content.contains("\"5.2\"") || content.contains("\"52\"") || content.contains("\"Lua 5.2\"") {
                         self.lua_version = LuaVersion::Lua52;
-                        println!("Detected Lua 5.2 from .luarc.json");
+                        log_verbose!("Detected Lua 5.2 from .luarc.json");
                         return;
                     } else if content.contains("\"5.3\"") || content.contains("\"53\"") || content.contains("\"Lua 5.3\"") {
                         self.lua_version = LuaVersion::Lua53;
-                        println!("Detected Lua 5.3 from .luarc.json");
+                        log_verbose!("Detected Lua 5.3 from .luarc.json");
                         return;
                     } else if content.contains("\"5.4\"") || content.contains("\"54\"") || content.contains("\"Lua 5.4\"") {
                         self.lua_version = LuaVersion::Lua54;
-                        println!("Detected Lua 5.4 from .luarc.json");
+                        log_verbose!("Detected Lua 5.4 from .luarc.json");
                         return;
                     } else if content.contains("\"LuaJIT\"") || content.contains("\"luajit\"") {
-                        // LuaJIT is closest to Lua 5.1 with some 5.2 features
-                        self.lua_version = LuaVersion::Lua51; 
-                        println!("Detected LuaJIT from .luarc.json (using Lua 5.1 compatibility)");
+                        self.lua_version = LuaVersion::LuaJIT;
+                        log_verbose!("Detected LuaJIT from .luarc.json");
                         return;
                     }
                 }
@@ -779,9 +1018,9 @@ impl ProjectContext {
         if lua_version_file.exists() {
             if let Ok(content) = std::fs::read_to_string(&lua_version_file) {
                 let content = content.trim();
-                if let Some(version) = LuaVersion::from_str(content) {
+                if let Some(version) = LuaVersion::parse(content) {
                     self.lua_version = version;
-                    println!("Detected Lua {} from .lua-version file", version.as_str());
+                    log_verbose!("Detected Lua {} from .lua-version file", version.as_str());
                     return;
                 }
             }
@@ -795,22 +1034,22 @@ impl ProjectContext {
                     if content.contains("= \"5.1\"") || content.contains("= '5.1'") || 
                        content.contains("=\"5.1\"") || content.contains("='5.1'") {
                         self.lua_version = LuaVersion::Lua51;
-                        println!("Detected Lua 5.1 from config.lua");
+                        log_verbose!("Detected Lua 5.1 from config.lua");
                         return;
                     } else if content.contains("= \"5.2\"") || content.contains("= '5.2'") || 
                               content.contains("=\"5.2\"") || content.contains("='5.2'") {
                         self.lua_version = LuaVersion::Lua52;
-                        println!("Detected Lua 5.2 from config.lua");
+                        log_verbose!("Detected Lua 5.2 from config.lua");
                         return;
                     } else if content.contains("= \"5.3\"") || content.contains("= '5.3'") || 
                               content.contains("=\"5.3\"") || content.contains("='5.3'") {
                         self.lua_version = LuaVersion::Lua53;
-                        println!("Detected Lua 5.3 from config.lua");
+                        log_verbose!("Detected Lua 5.3 from config.lua");
                         return;
                     } else if content.contains("= \"5.4\"") || content.contains("= '5.4'") || 
                               content.contains("=\"5.4\"") || content.contains("='5.4'") {
                         self.lua_version = LuaVersion::Lua54;
-                        println!("Detected Lua 5.4 from config.lua");
+                        log_verbose!("Detected Lua 5.4 from config.lua");
                         return;
                     }
                 }
@@ -825,19 +1064,23 @@ impl ProjectContext {
                 if content.contains("std = ") {
                     if content.contains("\"lua51\"") || content.contains("'lua51'") {
                         self.lua_version = LuaVersion::Lua51;
-                        println!("Detected Lua 5.1 from .luacheckrc");
+                        log_verbose!("Detected Lua 5.1 from .luacheckrc");
                         return;
                     } else if content.contains("\"lua52\"") || content.contains("'lua52'") {
                         self.lua_version = LuaVersion::Lua52;
-                        println!("Detected Lua 5.2 from .luacheckrc");
+                        log_verbose!("Detected Lua 5.2 from .luacheckrc");
                         return;
                     } else if content.contains("\"lua53\"") || content.contains("'lua53'") {
                         self.lua_version = LuaVersion::Lua53;
-                        println!("Detected Lua 5.3 from .luacheckrc");
+                        log_verbose!("Detected Lua 5.3 from .luacheckrc");
                         return;
                     } else if content.contains("\"lua54\"") || content.contains("'lua54'") {
                         self.lua_version = LuaVersion::Lua54;
-                        println!("Detected Lua 5.4 from .luacheckrc");
+                        log_verbose!("Detected Lua 5.4 from .luacheckrc");
+                        return;
+                    } else if content.contains("\"luajit\"") || content.contains("'luajit'") {
+                        self.lua_version = LuaVersion::LuaJIT;
+                        log_verbose!("Detected LuaJIT from .luacheckrc");
                         return;
                     }
                 }
@@ -853,19 +1096,19 @@ impl ProjectContext {
                         // Look for lua version in dependencies section
                         if content.contains("lua ~> 5.1") || content.contains("\"lua >= 5.1, < 5.2\"") {
                             self.lua_version = LuaVersion::Lua51;
-                            println!("Detected Lua 5.1 from rockspec file");
+                            log_verbose!("Detected Lua 5.1 from rockspec file");
                             return;
                         } else if content.contains("lua ~> 5.2") || content.contains("\"lua >= 5.2, < 5.3\"") {
                             self.lua_version = LuaVersion::Lua52;
-                            println!("Detected Lua 5.2 from rockspec file");
+                            log_verbose!("Detected Lua 5.2 from rockspec file");
                             return;
                         } else if content.contains("lua ~> 5.3") || content.contains("\"lua >= 5.3, < 5.4\"") {
                             self.lua_version = LuaVersion::Lua53;
-                            println!("Detected Lua 5.3 from rockspec file");
+                            log_verbose!("Detected Lua 5.3 from rockspec file");
                             return;
                         } else if content.contains("lua ~> 5.4") || content.contains("\"lua >= 5.4\"") {
                             self.lua_version = LuaVersion::Lua54;
-                            println!("Detected Lua 5.4 from rockspec file");
+                            log_verbose!("Detected Lua 5.4 from rockspec file");
                             return;
                         }
                     }
@@ -883,7 +1126,7 @@ impl ProjectContext {
            dir.join("ftplugin").exists() ||
            dir.join("autoload").exists()) {
             self.lua_version = LuaVersion::Lua51;
-            println!("Detected Lua 5.1 from Neovim plugin structure");
+            log_verbose!("Detected Lua 5.1 from Neovim plugin structure");
             return;
         }
         
@@ -893,17 +1136,17 @@ impl ProjectContext {
             if let Ok(content) = std::fs::read_to_string(dir.join("conf.lua")) {
                 if content.contains("t.version = \"11.") {
                     self.lua_version = LuaVersion::Lua53;
-                    println!("Detected Lua 5.3 from LÖVE2D 11.x configuration");
+                    log_verbose!("Detected Lua 5.3 from LÖVE2D 11.x configuration");
                     return;
                 } else {
                     self.lua_version = LuaVersion::Lua51;
-                    println!("Detected Lua 5.1 from LÖVE2D configuration");
+                    log_verbose!("Detected Lua 5.1 from LÖVE2D configuration");
                     return;
                 }
             } else {
                 // Default to 5.1 for LÖVE if we can't determine version
                 self.lua_version = LuaVersion::Lua51;
-                println!("Detected Lua 5.1 from LÖVE2D project structure");
+                log_verbose!("Detected Lua 5.1 from LÖVE2D project structure");
                 return;
             }
         }
@@ -911,14 +1154,14 @@ impl ProjectContext {
         // WezTerm uses Lua 5.4
         if dir.join("wezterm.lua").exists() || dir.join(".wezterm.lua").exists() {
             self.lua_version = LuaVersion::Lua54;
-            println!("Detected Lua 5.4 from WezTerm configuration");
+            log_verbose!("Detected Lua 5.4 from WezTerm configuration");
             return;
         }
         
         // Luvit typically uses Lua 5.2
         if dir.join("package.lua").exists() && dir.join("deps").exists() {
             self.lua_version = LuaVersion::Lua52;
-            println!("Detected Lua 5.2 from Luvit project structure");
+            log_verbose!("Detected Lua 5.2 from Luvit project structure");
             return;
         }
         
@@ -928,19 +1171,19 @@ impl ProjectContext {
             if let Ok(content) = std::fs::read_to_string(&type_file) {
                 if content.contains("lua_version = \"5.1\"") || content.contains("-- Lua 5.1") {
                     self.lua_version = LuaVersion::Lua51;
-                    println!("Detected Lua 5.1 from type.lua");
+                    log_verbose!("Detected Lua 5.1 from type.lua");
                     return;
                 } else if content.contains("lua_version = \"5.2\"") || content.contains("-- Lua 5.2") {
                     self.lua_version = LuaVersion::Lua52;
-                    println!("Detected Lua 5.2 from type.lua");
+                    log_verbose!("Detected Lua 5.2 from type.lua");
                     return;
                 } else if content.contains("lua_version = \"5.3\"") || content.contains("-- Lua 5.3") {
                     self.lua_version = LuaVersion::Lua53;
-                    println!("Detected Lua 5.3 from type.lua");
+                    log_verbose!("Detected Lua 5.3 from type.lua");
                     return;
                 } else if content.contains("lua_version = \"5.4\"") || content.contains("-- Lua 5.4") {
                     self.lua_version = LuaVersion::Lua54;
-                    println!("Detected Lua 5.4 from type.lua");
+                    log_verbose!("Detected Lua 5.4 from type.lua");
                     return;
                 }
             }
@@ -999,24 +1242,24 @@ impl ProjectContext {
         // Determine version based on syntax features
         if has_to_close {
             self.lua_version = LuaVersion::Lua54;
-            println!("Detected Lua 5.4 from syntax features (to-be-closed variables)");
+            log_verbose!("Detected Lua 5.4 from syntax features (to-be-closed variables)");
         } else if has_integer_division {
             self.lua_version = LuaVersion::Lua53;
-            println!("Detected Lua 5.3 from syntax features (integer division)");
+            log_verbose!("Detected Lua 5.3 from syntax features (integer division)");
         } else if has_goto || has_bitwise {
             self.lua_version = LuaVersion::Lua52;
-            println!("Detected Lua 5.2 from syntax features (goto/bitwise)");
+            log_verbose!("Detected Lua 5.2 from syntax features (goto/bitwise)");
         } else {
             // Default to Lua 5.1 if no newer features are found
             self.lua_version = LuaVersion::Lua51;
-            println!("Using Lua 5.1 as default (no specific version detected)");
+            log_verbose!("Using Lua 5.1 as default (no specific version detected)");
         }
     }
     
     /// Scan the project for Lua files starting from the root
-    pub fn scan_lua_files(&mut self) -> Result<(), String> {
+    pub fn scan_lua_files(&mut self) -> Result<(), LuaToolsError> {
         let root = self.project_root.clone()
-            .ok_or_else(|| "Project root not detected".to_string())?;
+            .ok_or(LuaToolsError::NoProjectRoot)?;
         
         self.lua_files.clear();
         self._scan_directory_for_lua_files(&root)?;
@@ -1024,18 +1267,18 @@ impl ProjectContext {
         Ok(())
     }
     
-    fn _scan_directory_for_lua_files(&mut self, dir: &Path) -> Result<(), String> {
-        let entries = fs::read_dir(dir)
-            .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+    fn _scan_directory_for_lua_files(&mut self, dir: &Path) -> Result<(), LuaToolsError> {
+        let io_error = |source| LuaToolsError::Io { path: dir.to_path_buf(), source };
+        let entries = fs::read_dir(dir).map_err(io_error)?;
             
         for entry in entries {
-            let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+            let entry = entry.map_err(io_error)?;
             let path = entry.path();
             
             if path.is_dir() {
                 // Skip hidden directories and common exclude patterns
                 let dir_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-                if !dir_name.starts_with(".") && dir_name != "node_modules" && dir_name != "target" {
+                if !is_excluded_dir(dir_name) {
                     self._scan_directory_for_lua_files(&path)?;
                 }
             } else if path.is_file() {
@@ -1046,7 +1289,7 @@ impl ProjectContext {
                         
                         // Check for type.lua specifically
                         if path.file_name().and_then(|n| n.to_str()) == Some("type.lua") {
-                            println!("Found type definition file: {}", path.display());
+                            log_verbose!("Found type definition file: {}", path.display());
                         }
                     }
                 }
@@ -1057,13 +1300,13 @@ impl ProjectContext {
     }
     
     /// Process the type.lua file if it exists
-    pub fn process_type_file(&mut self) -> Result<bool, String> {
+    pub fn process_type_file(&mut self) -> Result<bool, LuaToolsError> {
         if self.type_file_processed {
             return Ok(true);
         }
         
         let root = self.project_root.clone()
-            .ok_or_else(|| "Project root not detected".to_string())?;
+            .ok_or(LuaToolsError::NoProjectRoot)?;
             
         // Check for primary type.lua in project root
         let type_file = root.join("type.lua");
@@ -1081,17 +1324,15 @@ impl ProjectContext {
             // Process all .lua files in the types directory
             let entries = match fs::read_dir(&type_dir) {
                 Ok(entries) => entries,
-                Err(e) => return Err(format!("Failed to read types directory: {}", e)),
+                Err(source) => return Err(LuaToolsError::Io { path: type_dir, source }),
             };
             
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let path = entry.path();
-                    if path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("lua") {
-                        println!("Processing additional type file: {}", path.display());
-                        self.process_single_type_file(&path)?;
-                        processed = true;
-                    }
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("lua") {
+                    log_verbose!("Processing additional type file: {}", path.display());
+                    self.process_single_type_file(&path)?;
+                    processed = true;
                 }
             }
         }
@@ -1100,56 +1341,71 @@ impl ProjectContext {
         Ok(processed)
     }
     
+    /// Get the framework registry, creating it and discovering definitions on first use
+    pub fn framework_registry(&mut self) -> &mut FrameworkRegistry {
+        self.framework_registry.get_or_insert_with(FrameworkRegistry::new)
+    }
+    
     /// Detect frameworks used in the project
     pub fn detect_frameworks(&mut self, dir: &Path) {
         // Use the framework registry to detect frameworks
-        if let Some(registry) = &self.framework_registry {
-            let detected = registry.detect_framework_usage(dir);
-            
-            // Store detected frameworks
-            self.detected_frameworks.clear();
-            for (name, version_opt) in detected {
-                if let Some(version) = version_opt {
-                    println!("Detected framework: {} {}", name, version);
-                    self.detected_frameworks.push((name, version));
-                } else if let Some(latest) = registry.get_latest_version(&name) {
-                    println!("Detected framework: {} (using latest version {})", name, latest);
-                    self.detected_frameworks.push((name, latest));
-                }
+        let registry = self.framework_registry.get_or_insert_with(FrameworkRegistry::new);
+        let detected = registry.detect_framework_usage(dir);
+        
+        // Store detected frameworks
+        self.detected_frameworks.clear();
+        for (name, version_opt) in detected {
+            if let Some(version) = version_opt {
+                log_verbose!("Detected framework: {} {}", name, version);
+                self.detected_frameworks.push((name, version));
+            } else if let Some(latest) = registry.get_latest_version(&name) {
+                log_verbose!("Detected framework: {} (using latest version {})", name, latest);
+                self.detected_frameworks.push((name, latest));
             }
-            
-            // Apply framework definitions to the project context
-            self.apply_framework_definitions();
         }
+        
+        // Apply framework definitions to the project context
+        self.apply_framework_definitions();
     }
     
     /// Apply detected framework definitions to the project context
     pub fn apply_framework_definitions(&mut self) {
-        if let Some(registry) = &self.framework_registry {
-            for (name, version) in &self.detected_frameworks {
-                println!("Applying framework definitions for {} {}", name, version);
+        // Take the registry out while applying so the context can be mutated
+        if let Some(registry) = self.framework_registry.take() {
+            for (name, version) in self.detected_frameworks.clone() {
+                log_verbose!("Applying framework definitions for {} {}", name, version);
                 
                 // Apply the framework definition to the project context
-                if registry.apply_framework_to_context(self, name, version) {
-                    println!("Successfully applied {} {} definitions", name, version);
+                if registry.apply_framework_to_context(self, &name, &version) {
+                    log_verbose!("Successfully applied {} {} definitions", name, version);
                 } else {
-                    println!("Failed to apply {} {} definitions", name, version);
+                    log_message!("Failed to apply {} {} definitions", name, version);
                 }
+                self.framework_globals.extend(registry.framework_globals(&name, &version));
             }
+            self.framework_registry = Some(registry);
         }
     }
     
     /// Process a single type definition file
-    fn process_single_type_file(&mut self, file_path: &Path) -> Result<(), String> {
+    fn process_single_type_file(&mut self, file_path: &Path) -> Result<(), LuaToolsError> {
         // Read the type file
-        println!("Processing type definition file: {}", file_path.display());
+        log_verbose!("Processing type definition file: {}", file_path.display());
         let content = match fs::read_to_string(file_path) {
             Ok(content) => content,
-            Err(e) => return Err(format!("Failed to read type file: {}", e)),
+            Err(source) => {
+                return Err(LuaToolsError::Io { path: file_path.to_path_buf(), source })
+            }
         };
         
-        // Parse the type file using our tokenizer and parser
-        let mut code_tokenizer = crate::tokenizer::CodeTokenizer::new_with_options(&content, true);
+        self.load_definitions(&content);
+        Ok(())
+    }
+    
+    /// Load type definitions and function signatures from Lua definition source
+    pub fn load_definitions(&mut self, content: &str) {
+        // Parse the definitions using our tokenizer and parser
+        let mut code_tokenizer = crate::tokenizer::CodeTokenizer::new(content);
         let tokens = code_tokenizer.tokenize();
         
         let mut code_parser = crate::parser::code_parser::CodeParser::new(tokens);
@@ -1157,21 +1413,195 @@ impl ProjectContext {
         
         // Extract type definitions from the AST
         self.extract_type_definitions_from_ast(&ast);
+    }
+    
+    /// Load the source of a project module as `module_name` (e.g. `app.util`). The functions
+    /// and fields of the table the module returns become its exports, which other modules
+    /// reach through `local alias = require("app.util")`
+    pub fn load_module(&mut self, module_name: &str, content: &str) {
+        let mut code_tokenizer = crate::tokenizer::CodeTokenizer::new(content);
+        let tokens = code_tokenizer.tokenize();
+        let ast = crate::parser::code_parser::CodeParser::new(tokens).parse();
+        
+        // Modules this one requires, with the local each is assigned to
+        let mut dependencies = Vec::new();
+        for node in &ast {
+            let (alias, call) = match node {
+                CodeASTNode::VariableDeclaration { name, value: Some(value), .. } => match &**value {
+                    CodeASTNode::ReturnStatement(values) => (Some(name.clone()), values.first()),
+                    _ => continue,
+                },
+                CodeASTNode::FunctionCallStmt { call, .. } => (None, Some(call)),
+                _ => continue,
+            };
+            if let Some(path) = call.and_then(parser_helpers::require_path) {
+                dependencies.push(DependencyInfo {
+                    required_path: path.to_string(),
+                    local_alias: alias,
+                    resolved_path: self.resolve_require_path(path),
+                });
+            }
+        }
+        let module = self.module_entry(module_name);
+        module.dependencies = dependencies;
+        module.processed = true;
+        
+        // The table named by the module's final `return` holds its exports
+        let returned = ast.iter().rev().find_map(|node| match node {
+            CodeASTNode::ReturnStatement(values) => match values.first() {
+                Some(Expression::Identifier(name, _)) => Some(name.clone()),
+                _ => None,
+            },
+            _ => None,
+        });
+        let Some(returned) = returned else {
+            return;
+        };
         
-        Ok(())
+        for node in &ast {
+            if let CodeASTNode::ModuleDeclaration { name, exports, .. } = node {
+                if *name == returned {
+                    for export in exports {
+                        self.add_export(module_name, export.clone());
+                    }
+                }
+            }
+        }
+        
+        // Signatures are extracted separately so they can be renamed after the module
+        let mut extracted = ProjectContext::new_with_version(self.lua_version);
+        extracted.extract_type_definitions_from_ast(&ast);
+        for (name, custom_type) in extracted.type_registry.custom_types {
+            self.type_registry.custom_types.entry(name).or_insert(custom_type);
+        }
+        let prefix = format!("{}.", returned);
+        for (name, mut signature) in extracted.type_registry.function_signatures {
+            let Some(member) = name.strip_prefix(&prefix) else {
+                continue;
+            };
+            if !member.contains('.') {
+                self.add_export(module_name, ExportItem {
+                    name: member.to_string(),
+                    type_info: TypeInfo::Function,
+                });
+            }
+            signature.name = format!("{}.{}", module_name, member);
+            self.type_registry.function_signatures.insert(signature.name.clone(), signature);
+        }
+    }
+
+    /// Load every `.lua` file under a LuaLS/EmmyLua meta definition directory, returning
+    /// how many files were read
+    pub fn load_definition_dir(&mut self, dir: &Path) -> Result<usize, LuaToolsError> {
+        let walker = walkdir::WalkDir::new(dir)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| {
+                entry.depth() == 0
+                    || !entry.file_type().is_dir()
+                    || !is_excluded_dir(&entry.file_name().to_string_lossy())
+            });
+        
+        let mut loaded = 0;
+        for entry in walker {
+            let entry = entry.map_err(|e| {
+                let path = e.path().unwrap_or(dir).to_path_buf();
+                // Only symlink loops have no underlying I/O error
+                let source = e.into_io_error()
+                    .unwrap_or_else(|| std::io::Error::other("filesystem loop"));
+                LuaToolsError::Io { path, source }
+            })?;
+            let path = entry.path();
+            if entry.file_type().is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("lua") {
+                self.process_single_type_file(path)?;
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+    
+    /// The signature of a function definition, taking parameter types and descriptions from
+    /// its `@param` annotations and return types from its `@return` annotations
+    pub fn function_signature(&self, name: &str, params: &[(String, TypeInfo)], doc: &Option<String>, annotations: &[AnnotationASTNode]) -> FunctionSignature {
+        let is_method = name.contains(':');
+        let mut parameters = Vec::new();
+        // A method's `self` is implied by the colon
+        let explicit_params = params.iter()
+            .filter(|(param_name, _)| !(is_method && param_name == "self"));
+        for (param_name, param_type) in explicit_params {
+            let param_doc = annotations.iter().find_map(|annotation| match annotation {
+                AnnotationASTNode::Param { name, type_field, lua_type, description }
+                    if name == param_name => Some((type_field, lua_type, description)),
+                _ => None,
+            });
+            let (type_info, description, optional) = match param_doc {
+                Some((type_field, lua_type, description)) => (
+                    lua_type.as_ref().map_or(param_type.clone(), |lua_type| self.lua_type_info(lua_type)),
+                    description.clone(),
+                    type_field.ends_with('?'),
+                ),
+                None => (param_type.clone(), None, false),
+            };
+            parameters.push(FunctionParameter {
+                name: param_name.clone(),
+                type_info,
+                description,
+                optional,
+            });
+        }
+        let return_types = annotations.iter()
+            .filter_map(|annotation| match annotation {
+                AnnotationASTNode::Return { type_field, lua_type, .. } => Some(
+                    lua_type.as_ref().map_or_else(|| self.type_name_to_info(type_field), |lua_type| self.lua_type_info(lua_type))
+                ),
+                _ => None,
+            })
+            .collect();
+        
+        FunctionSignature {
+            name: name.to_string(),
+            parameters,
+            return_types,
+            description: doc.as_ref().map(|doc| doc.lines()
+                .map(|line| line.trim_start_matches('-').trim())
+                .collect::<Vec<_>>()
+                .join(" ")),
+            is_method,
+        }
     }
     
     /// Extract type definitions from an AST (used for processing type.lua)
     fn extract_type_definitions_from_ast(&mut self, ast: &[crate::parser::ast::CodeASTNode]) {
-        use crate::parser::ast::{CodeASTNode, Expression, TypeInfo};
+        use crate::parser::ast::CodeASTNode;
         
+        // Fields belong to the most recent `---@class`
+        let mut current_class: Option<String> = None;
         for node in ast {
+            self.register_annotations(node.annotations(), &mut current_class);
             match node {
                 // Look for class annotations
-                CodeASTNode::Comment { text, .. } => {
+                CodeASTNode::Comment(text) => {
                     if text.starts_with("---@class ") {
                         // Parse class annotation
                         let class_line = text.trim_start_matches("---@class ").trim();
+                        // `Name : Parent, Other description` declares the parents after a colon
+                        let (class_line, parents) = match class_line.split_once(':') {
+                            Some((name, rest)) => {
+                                let mut words = rest.split_whitespace();
+                                let mut parents = Vec::new();
+                                for word in words.by_ref() {
+                                    parents.extend(word.split(',')
+                                        .filter(|parent| !parent.is_empty())
+                                        .map(str::to_string));
+                                    if !word.ends_with(',') {
+                                        break;
+                                    }
+                                }
+                                let description: Vec<&str> = words.collect();
+                                (format!("{} {}", name.trim(), description.join(" ")), parents)
+                            }
+                            None => (class_line.to_string(), Vec::new()),
+                        };
                         let parts: Vec<&str> = class_line.split_whitespace().collect();
                         if !parts.is_empty() {
                             let class_name = parts[0].to_string();
@@ -1189,6 +1619,7 @@ impl ProjectContext {
                                 description,
                                 is_alias: false,
                                 variants: Vec::new(),
+                                parents,
                             };
                             
                             self.type_registry.custom_types.insert(class_name, custom_type);
@@ -1216,10 +1647,10 @@ impl ProjectContext {
                             
                             // Find the custom type to add this field to
                             // This assumes fields come right after the class definition
-                            if let Some(last_type) = self.type_registry.custom_types.keys().last() {
-                                if let Some(custom_type) = self.type_registry.custom_types.get_mut(last_type) {
+                            let type_info = self.type_name_to_info(&type_name);
+                            if let Some(last_type) = self.type_registry.custom_types.keys().last().cloned() {
+                                if let Some(custom_type) = self.type_registry.custom_types.get_mut(&last_type) {
                                     // Add the field
-                                    let type_info = self.type_name_to_info(&type_name);
                                     let field = TypeField {
                                         name: field_name,
                                         type_info,
@@ -1251,6 +1682,7 @@ impl ProjectContext {
                                 description,
                                 is_alias: true,
                                 variants: Vec::new(),
+                                parents: Vec::new(),
                             };
                             
                             self.type_registry.custom_types.insert(alias_name, custom_type);
@@ -1261,8 +1693,8 @@ impl ProjectContext {
                         let variant = variant_line.trim_matches('\'').trim_matches('"').to_string();
                         
                         // Add to the last alias type
-                        if let Some(last_type) = self.type_registry.custom_types.keys().last() {
-                            if let Some(custom_type) = self.type_registry.custom_types.get_mut(last_type) {
+                        if let Some(last_type) = self.type_registry.custom_types.keys().last().cloned() {
+                            if let Some(custom_type) = self.type_registry.custom_types.get_mut(&last_type) {
                                 if custom_type.is_alias {
                                     custom_type.variants.push(variant);
                                 }
@@ -1271,26 +1703,9 @@ impl ProjectContext {
                     }
                 },
                 // Look for function definitions to extract signatures
-                CodeASTNode::FunctionDef { name, params, .. } => {
-                    // Extract function signature
-                    let mut parameters = Vec::new();
-                    for param in params {
-                        parameters.push(FunctionParameter {
-                            name: param.clone(),
-                            type_info: TypeInfo::Unknown,
-                            description: None,
-                            optional: false,
-                        });
-                    }
-                    
-                    let is_method = name.contains(':');
-                    let signature = FunctionSignature {
-                        name: name.clone(),
-                        parameters,
-                        return_types: Vec::new(),
-                        description: None,
-                        is_method,
-                    };
+                CodeASTNode::FunctionDef { name, params, doc, annotations, .. } => {
+                    let signature = self.function_signature(name, params, doc, annotations);
+                    let is_method = signature.is_method;
                     
                     // If it's a method, add it to the appropriate class
                     if is_method {
@@ -1299,6 +1714,12 @@ impl ProjectContext {
                             let class_name = parts[0].to_string();
                             let method_name = parts[1].to_string();
                             
+                            // `Types.Point:move` belongs to `Point` when no class is named `Types.Point`
+                            let class_name = if self.type_registry.custom_types.contains_key(&class_name) {
+                                class_name
+                            } else {
+                                class_name.rsplit('.').next().unwrap_or_default().to_string()
+                            };
                             if let Some(custom_type) = self.type_registry.custom_types.get_mut(&class_name) {
                                 custom_type.methods.insert(method_name, signature);
                             }
@@ -1313,6 +1734,82 @@ impl ProjectContext {
         }
     }
     
+    /// Register the classes, fields and aliases declared by the annotations of an AST's nodes
+    pub fn register_types(&mut self, ast: &[crate::parser::ast::CodeASTNode]) {
+        let mut current_class: Option<String> = None;
+        for node in ast {
+            self.register_annotations(node.annotations(), &mut current_class);
+        }
+    }
+    
+    /// Register the classes, fields and aliases declared by a node's annotations
+    fn register_annotations(&mut self, annotations: &[AnnotationASTNode], current_class: &mut Option<String>) {
+        for annotation in annotations {
+            match annotation {
+                AnnotationASTNode::Class { name, parents, .. } => {
+                    self.type_registry.custom_types.insert(name.clone(), CustomType {
+                        name: name.clone(),
+                        fields: Vec::new(),
+                        methods: HashMap::new(),
+                        description: None,
+                        is_alias: false,
+                        variants: Vec::new(),
+                        parents: parents.clone(),
+                    });
+                    *current_class = Some(name.clone());
+                }
+                AnnotationASTNode::Field { name, type_field, lua_type, description, .. } => {
+                    let type_info = lua_type.as_ref()
+                        .map_or_else(|| self.type_name_to_info(type_field), |lua_type| self.lua_type_info(lua_type));
+                    let custom_type = current_class.as_ref()
+                        .and_then(|class| self.type_registry.custom_types.get_mut(class));
+                    if let Some(custom_type) = custom_type {
+                        custom_type.fields.push(TypeField {
+                            name: name.clone(),
+                            type_info,
+                            description: description.clone(),
+                            optional: type_field.ends_with('?'),
+                        });
+                    }
+                }
+                AnnotationASTNode::Enum { name, key, members, values } => {
+                    // The values an enum-typed field can hold: the member names of a key
+                    // enum, else the table's values (or the listed members without a table)
+                    let variants = if values.is_empty() {
+                        members.iter().map(|(member, _)| member.clone()).collect()
+                    } else if *key {
+                        values.iter().map(|(member, _)| member.clone()).collect()
+                    } else {
+                        values.iter().map(|(_, value)| value.trim_matches('"').to_string()).collect()
+                    };
+                    self.type_registry.custom_types.insert(name.clone(), CustomType {
+                        name: name.clone(),
+                        fields: Vec::new(),
+                        methods: HashMap::new(),
+                        description: None,
+                        is_alias: true,
+                        variants,
+                        parents: Vec::new(),
+                    });
+                }
+                AnnotationASTNode::Alias { name, variants } => {
+                    self.type_registry.custom_types.insert(name.clone(), CustomType {
+                        name: name.clone(),
+                        fields: Vec::new(),
+                        methods: HashMap::new(),
+                        description: None,
+                        is_alias: true,
+                        variants: variants.iter()
+                            .map(|(value, _)| value.trim_matches('\'').trim_matches('"').to_string())
+                            .collect(),
+                        parents: Vec::new(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+    
     /// Convert a type name string to a TypeInfo
     fn type_name_to_info(&self, type_name: &str) -> TypeInfo {
         match type_name {
@@ -1324,12 +1821,144 @@ impl ProjectContext {
             _ => {
                 // Check if it's a custom type we know about
                 if self.type_registry.custom_types.contains_key(type_name) {
-                    TypeInfo::Table  // Treat custom types as tables for now
+                    TypeInfo::Named(type_name.to_string())
                 } else {
-                    TypeInfo::Unknown
+                    parser_helpers::type_info_from_name(type_name)
+                }
+            }
+        }
+    }
+    
+    /// Convert an annotation type to a TypeInfo, keeping the names of registered classes and aliases
+    pub fn lua_type_info(&self, lua_type: &LuaType) -> TypeInfo {
+        lua_type.to_type_info_with(&|name| self.type_name_to_info(name))
+    }
+    
+    /// Set the require search path from a package.path style string (e.g. `"?.lua;lua/?.lua"`)
+    pub fn set_package_path(&mut self, package_path: &str) {
+        self.package_path = package_path
+            .split(';')
+            .map(|template| template.trim())
+            .filter(|template| !template.is_empty())
+            .map(|template| template.to_string())
+            .collect();
+    }
+    
+    /// Resolve a dotted module path (e.g. `app.util`) to a file using the search templates.
+    /// Relative templates are resolved against the project root.
+    pub fn resolve_require_path(&self, required_path: &str) -> Option<PathBuf> {
+        let root = self.project_root.as_ref()?;
+        let module_path = required_path.replace('.', std::path::MAIN_SEPARATOR_STR);
+        
+        for template in &self.package_path {
+            let candidate = root.join(template.replace('?', &module_path));
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+        
+        None
+    }
+    
+    /// The name of the module loaded from `path`: the module whose source is that file, or
+    /// else the known module that `require` finds there through the search templates
+    /// (`lua/app/util.lua` is `app.util` under `lua/?.lua`, `app/init.lua` is `app` under
+    /// `?/init.lua`). Relative paths are taken from the project root.
+    pub fn module_for_path(&self, path: &Path) -> Option<&str> {
+        let path = match &self.project_root {
+            Some(root) if path.is_relative() => root.join(path),
+            _ => path.to_path_buf(),
+        };
+        let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        let is_path = |source: &Path| {
+            source == path || fs::canonicalize(source).is_ok_and(|source| source == canonical)
+        };
+        if let Some((name, _)) = self.modules.iter().find(|(_, module)| is_path(&module.source_path)) {
+            return Some(name);
+        }
+        
+        let root = self.project_root.as_ref()?;
+        let canonical_root = fs::canonicalize(root).unwrap_or_else(|_| root.clone());
+        let relative = path.strip_prefix(root)
+            .or_else(|_| canonical.strip_prefix(&canonical_root))
+            .ok()?
+            .to_string_lossy()
+            .replace(std::path::MAIN_SEPARATOR, "/");
+        self.package_path.iter()
+            .filter_map(|template| module_name_from_template(template, &relative))
+            .find_map(|name| self.modules.get_key_value(&name))
+            .map(|(name, _)| name.as_str())
+    }
+    
+    /// Load the new source of one module file in place, without rescanning the project: the
+    /// module's exports, signatures and requires are replaced and its edges in the dependency
+    /// graph updated. The modules that require it are marked unprocessed and returned, in
+    /// name order, for the caller to re-check. Returns None if no known module is loaded from
+    /// `path`
+    pub fn reanalyze_file(&mut self, path: &Path, new_source: &str) -> Option<Vec<String>> {
+        let module_name = self.module_for_path(path)?.to_string();
+        
+        // Forget what the old source provided. Signatures of nested modules (`app.util.*`
+        // when reloading `app`) belong to those modules and are kept
+        let module = self.modules.get_mut(&module_name)?;
+        module.exports.clear();
+        let old_dependencies = std::mem::take(&mut module.dependencies);
+        for dependency in &old_dependencies {
+            if let Some(dependents) = self.dependency_graph.get_mut(&dependency.required_path) {
+                dependents.remove(&module_name);
+                if dependents.is_empty() {
+                    self.dependency_graph.remove(&dependency.required_path);
+                }
+            }
+        }
+        let prefix = format!("{}.", module_name);
+        let nested_prefixes: Vec<String> = self.modules.keys()
+            .filter(|name| name.starts_with(&prefix))
+            .map(|name| format!("{}.", name))
+            .collect();
+        self.type_registry.function_signatures.retain(|name, _| {
+            !name.starts_with(&prefix) || nested_prefixes.iter().any(|nested| name.starts_with(nested))
+        });
+        
+        self.load_module(&module_name, new_source);
+        for dependency in &self.modules[&module_name].dependencies {
+            self.dependency_graph
+                .entry(dependency.required_path.clone())
+                .or_default()
+                .insert(module_name.clone());
+        }
+        
+        let mut dependents: Vec<String> = self.dependency_graph
+            .get(&module_name)
+            .map(|dependents| dependents.iter().cloned().collect())
+            .unwrap_or_default();
+        dependents.sort();
+        for dependent in &dependents {
+            if let Some(module) = self.modules.get_mut(dependent) {
+                module.processed = false;
+            }
+        }
+        Some(dependents)
+    }
+    
+    /// Populate `resolved_path` for every dependency that can be mapped to a file
+    pub fn resolve_dependencies(&mut self) {
+        let mut resolved = Vec::new();
+        for (module_name, module_info) in &self.modules {
+            for (index, dependency) in module_info.dependencies.iter().enumerate() {
+                if dependency.resolved_path.is_none() {
+                    if let Some(path) = self.resolve_require_path(&dependency.required_path) {
+                        resolved.push((module_name.clone(), index, path));
+                    }
                 }
             }
         }
+        
+        for (module_name, index, path) in resolved {
+            if let Some(module) = self.modules.get_mut(&module_name) {
+                module.dependencies[index].resolved_path = Some(path);
+            }
+        }
     }
     
     /// Build dependency graph between modules
@@ -1341,22 +1970,74 @@ impl ProjectContext {
                 // Get or create entry for this dependency
                 self.dependency_graph
                     .entry(dependency.required_path.clone())
-                    .or_insert_with(HashSet::new)
+                    .or_default()
                     .insert(module_name.clone());
             }
         }
     }
 
+    /// Find circular requires in the dependency graph.
+    /// Each cycle is returned in require order, e.g. `["a", "b"]` for `a -> b -> a`.
+    pub fn find_dependency_cycles(&self) -> Vec<Vec<String>> {
+        // The graph maps a dependency to its dependents, so invert it to follow requires
+        let mut requires: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (dependency, dependents) in &self.dependency_graph {
+            for dependent in dependents {
+                requires.entry(dependent.as_str()).or_default().push(dependency.as_str());
+            }
+        }
+        for targets in requires.values_mut() {
+            targets.sort();
+        }
+        
+        let mut nodes: Vec<&str> = requires.keys().copied().collect();
+        nodes.sort();
+        
+        let mut cycles = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack = Vec::new();
+        for node in nodes {
+            if !visited.contains(node) {
+                Self::visit_for_cycles(node, &requires, &mut visited, &mut stack, &mut cycles);
+            }
+        }
+        
+        cycles
+    }
+    
+    /// Depth-first visit used by `find_dependency_cycles`
+    fn visit_for_cycles<'a>(
+        node: &'a str,
+        requires: &HashMap<&'a str, Vec<&'a str>>,
+        visited: &mut HashSet<&'a str>,
+        stack: &mut Vec<&'a str>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        visited.insert(node);
+        stack.push(node);
+        
+        if let Some(targets) = requires.get(node) {
+            for &target in targets {
+                if let Some(start) = stack.iter().position(|&n| n == target) {
+                    // Back edge: everything from the target up to here forms a cycle
+                    cycles.push(stack[start..].iter().map(|n| n.to_string()).collect());
+                } else if !visited.contains(target) {
+                    Self::visit_for_cycles(target, requires, visited, stack, cycles);
+                }
+            }
+        }
+        
+        stack.pop();
+    }
+
     pub fn add_module(&mut self, name: String, info: ModuleInfo) {
         self.modules.insert(name, info);
     }
 
     pub fn resolve_type(&self, name: &str) -> Option<TypeInfo> {
-        // First check custom types
+        // First check custom types; an alias resolves to the union it stands for
         if let Some(custom_type) = self.type_registry.custom_types.get(name) {
-            // For simplicity, we just return a generic type for now
-            // In the future, we could create a more specific TypeInfo for custom types
-            return Some(TypeInfo::Table);
+            return Some(custom_type.alias_type().unwrap_or_else(|| TypeInfo::Named(name.to_string())));
         }
         
         // Then check standard types
@@ -1364,6 +2045,42 @@ impl ProjectContext {
     }
 
     pub fn add_export(&mut self, module_name: &str, export: ExportItem) {
+        self.module_entry(module_name)
+            .exports
+            .insert(export.name.clone(), export);
+    }
+
+    /// The signature of a function, by its full name (`utils.split`, `string.format`) or
+    /// else by its bare name within a module (`split`). A bare name defined in several
+    /// modules resolves to the first module in name order
+    pub fn find_symbol(&self, name: &str) -> Option<&FunctionSignature> {
+        let signatures = &self.type_registry.function_signatures;
+        if let Some(signature) = signatures.get(name) {
+            return Some(signature);
+        }
+        let suffix = format!(".{}", name);
+        signatures
+            .iter()
+            .filter(|(full_name, _)| full_name.ends_with(&suffix))
+            .min_by(|a, b| a.0.cmp(b.0))
+            .map(|(_, signature)| signature)
+    }
+
+    /// Every module's exports, paired with the module name, in module and export name order
+    pub fn all_exports(&self) -> impl Iterator<Item = (&str, &ExportItem)> {
+        let mut modules: Vec<_> = self.modules.iter().collect();
+        modules.sort_by(|a, b| a.0.cmp(b.0));
+        modules.into_iter().flat_map(|(module_name, module)| {
+            let mut exports: Vec<_> = module.exports.values().collect();
+            exports.sort_by(|a, b| a.name.cmp(&b.name));
+            exports
+                .into_iter()
+                .map(move |export| (module_name.as_str(), export))
+        })
+    }
+
+    /// The module named `module_name`, added with no exports if it is not known yet
+    fn module_entry(&mut self, module_name: &str) -> &mut ModuleInfo {
         self.modules
             .entry(module_name.to_string())
             .or_insert_with(|| ModuleInfo {
@@ -1373,14 +2090,12 @@ impl ProjectContext {
                 is_main: false,
                 processed: false,
             })
-            .exports
-            .insert(export.name.clone(), export);
     }
     
     /// Generate a type.lua file from observed types in the project
-    pub fn generate_type_file(&self) -> Result<String, String> {
+    pub fn generate_type_file(&self) -> Result<String, LuaToolsError> {
         if self.custom_types_count() == 0 {
-            return Err("No custom types to generate".to_string());
+            return Err(LuaToolsError::NoCustomTypes);
         }
         
         let mut output = String::new();
@@ -1405,12 +2120,16 @@ impl ProjectContext {
         
         for (name, custom_type) in &self.type_registry.custom_types {
             if !custom_type.is_alias {
-                output.push_str(&format!("---@class {}\n", name));
+                if custom_type.parents.is_empty() {
+                    output.push_str(&format!("---@class {}\n", name));
+                } else {
+                    output.push_str(&format!("---@class {} : {}\n", name, custom_type.parents.join(", ")));
+                }
                 
                 // Fields
                 for field in &custom_type.fields {
                     let optional_marker = if field.optional { "?" } else { "" };
-                    let type_name = self.type_name_for_info(&field.type_info);
+                    let type_name = field.type_info.to_string();
                     let description = field.description.as_deref().unwrap_or("");
                     
                     output.push_str(&format!("---@field {}{} {} {}\n", 
@@ -1445,7 +2164,11 @@ impl ProjectContext {
                 output.push_str(&format!("---@alias {}\n", name));
                 
                 for variant in &custom_type.variants {
-                    output.push_str(&format!("---| '\"{}\"'\n", variant));
+                    if variant.parse::<f64>().is_ok() {
+                        output.push_str(&format!("---| {}\n", variant));
+                    } else {
+                        output.push_str(&format!("---| '\"{}\"'\n", variant));
+                    }
                 }
                 
                 output.push_str(&format!("Types.{} = {{}}\n\n", name));
@@ -1457,7 +2180,7 @@ impl ProjectContext {
         output.push_str("-- Function Signatures\n");
         output.push_str("-- =====================\n\n");
         
-        for (_, function) in &self.type_registry.function_signatures {
+        for function in self.type_registry.function_signatures.values() {
             if !function.is_method {
                 output.push_str(&self.format_function_signature(function, None));
                 output.push_str(&format!("Types.{} = function(", function.name));
@@ -1477,13 +2200,189 @@ impl ProjectContext {
         Ok(output)
     }
     
+    /// Generate a type.lua file from an existing one with the observed types merged in.
+    /// Definitions found on only one side are kept and existing descriptions take priority
+    pub fn merge_type_file(&self, existing: &str) -> Result<TypeFileMerge, LuaToolsError> {
+        let mut merged = ProjectContext::new_with_version(self.lua_version);
+        merged.load_definitions(existing);
+        let mut conflicts = Vec::new();
+        
+        let mut names: Vec<&String> = self.type_registry.custom_types.keys().collect();
+        names.sort();
+        for name in names {
+            let observed = &self.type_registry.custom_types[name];
+            match merged.type_registry.custom_types.get_mut(name) {
+                Some(current) => merge_custom_type(current, observed, &mut conflicts),
+                None => {
+                    merged.type_registry.custom_types.insert(name.clone(), observed.clone());
+                }
+            }
+        }
+        
+        let mut names: Vec<&String> = self.type_registry.function_signatures.keys().collect();
+        names.sort();
+        for name in names {
+            let observed = &self.type_registry.function_signatures[name];
+            match merged.type_registry.function_signatures.get_mut(name) {
+                Some(current) => merge_signature(current, observed, name, &mut conflicts),
+                None => {
+                    merged.type_registry.function_signatures.insert(name.clone(), observed.clone());
+                }
+            }
+        }
+        
+        Ok(TypeFileMerge {
+            content: merged.generate_type_file()?,
+            conflicts,
+        })
+    }
+
+    /// Render the registered classes, aliases and functions as Markdown API documentation,
+    /// one section per class or alias followed by the standalone functions
+    pub fn generate_markdown(&self) -> String {
+        let mut output = String::new();
+        
+        let mut types: Vec<&CustomType> = self.type_registry.custom_types.values().collect();
+        types.sort_by(|a, b| a.name.cmp(&b.name));
+        for custom_type in types {
+            if custom_type.is_alias {
+                output.push_str(&format!("## Alias `{}`\n\n", custom_type.name));
+            } else {
+                output.push_str(&format!("## Class `{}`\n\n", custom_type.name));
+                if !custom_type.parents.is_empty() {
+                    let parents: Vec<String> = custom_type.parents.iter()
+                        .map(|parent| format!("`{}`", parent))
+                        .collect();
+                    output.push_str(&format!("Inherits from {}\n\n", parents.join(", ")));
+                }
+            }
+            if let Some(desc) = &custom_type.description {
+                output.push_str(&format!("{}\n\n", desc));
+            }
+            
+            if !custom_type.variants.is_empty() {
+                for variant in &custom_type.variants {
+                    output.push_str(&format!("- `{}`\n", variant));
+                }
+                output.push('\n');
+            }
+            
+            if !custom_type.fields.is_empty() {
+                output.push_str("### Fields\n\n");
+                for field in &custom_type.fields {
+                    output.push_str(&format!("- `{}`: `{}`", field.name, field.type_info));
+                    if let Some(desc) = &field.description {
+                        output.push_str(&format!(" — {}", desc));
+                    }
+                    output.push('\n');
+                }
+                output.push('\n');
+            }
+            
+            if !custom_type.methods.is_empty() {
+                output.push_str("### Methods\n\n");
+                let mut methods: Vec<(&String, &FunctionSignature)> = custom_type.methods.iter().collect();
+                methods.sort_by(|a, b| a.0.cmp(b.0));
+                for (method_name, method) in methods {
+                    let name = format!("{}:{}", custom_type.name, method_name);
+                    output.push_str(&self.format_markdown_function(&name, method));
+                }
+                output.push('\n');
+            }
+        }
+        
+        // Only document the project's own functions, not the standard library
+        let standard = Self::new_with_version(self.lua_version);
+        let mut functions: Vec<&FunctionSignature> = self.type_registry.function_signatures.values()
+            .filter(|function| !standard.type_registry.function_signatures.contains_key(&function.name))
+            .collect();
+        if !functions.is_empty() {
+            output.push_str("## Functions\n\n");
+            functions.sort_by(|a, b| a.name.cmp(&b.name));
+            for function in functions {
+                output.push_str(&self.format_markdown_function(&function.name, function));
+            }
+            output.push('\n');
+        }
+        
+        output
+    }
+    
+    /// Format a function as a Markdown bullet: its signature, description and parameters
+    fn format_markdown_function(&self, name: &str, function: &FunctionSignature) -> String {
+        let params = function.parameters.iter()
+            .map(|param| {
+                let optional_marker = if param.optional { "?" } else { "" };
+                format!("{}{}: {}", param.name, optional_marker, param.type_info)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut output = format!("- `{}({})", name, params);
+        if !function.return_types.is_empty() {
+            let return_types = function.return_types.iter()
+                .map(TypeInfo::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            output.push_str(&format!(": {}", return_types));
+        }
+        output.push('`');
+        if let Some(desc) = &function.description {
+            output.push_str(&format!(" — {}", desc));
+        }
+        output.push('\n');
+        
+        for param in &function.parameters {
+            if let Some(desc) = &param.description {
+                output.push_str(&format!("  - `{}`: {}\n", param.name, desc));
+            }
+        }
+        output
+    }
+    
+    /// Render a `.luarc.json` that points the Lua Language Server at the detected
+    /// frameworks: their runtime version, definition files and global names
+    pub fn generate_luarc(&self) -> String {
+        let frameworks: Vec<&FrameworkVersion> = self.detected_frameworks.iter()
+            .filter_map(|(name, version)| {
+                self.framework_registry.as_ref()?.get_framework(name, version)
+            })
+            .collect();
+        
+        // A framework's embedded runtime wins over the project's Lua version
+        let runtime_version = frameworks.first()
+            .map_or(self.lua_version, |framework| framework.lua_version);
+        
+        let library: Vec<String> = frameworks.iter()
+            .filter_map(|framework| framework.definition_path.as_ref())
+            .map(|path| json_string(&path.display().to_string()))
+            .collect();
+        
+        let mut globals: Vec<String> = Vec::new();
+        for (name, _) in &self.detected_frameworks {
+            for global in framework_global_names(name) {
+                let global = json_string(global);
+                if !globals.contains(&global) {
+                    globals.push(global);
+                }
+            }
+        }
+        
+        let mut output = String::from("{\n");
+        output.push_str("  \"$schema\": \"https://raw.githubusercontent.com/LuaLS/vscode-lua/master/setting/schema.json\",\n");
+        output.push_str(&format!("  \"runtime.version\": {},\n", json_string(runtime_version.as_str())));
+        output.push_str(&format!("  \"workspace.library\": {},\n", json_array(&library)));
+        output.push_str(&format!("  \"diagnostics.globals\": {}\n", json_array(&globals)));
+        output.push_str("}\n");
+        output
+    }
+    
     /// Count the number of custom types
     pub fn custom_types_count(&self) -> usize {
         self.type_registry.custom_types.len()
     }
     
     /// Format a function signature for the type file
-    fn format_function_signature(&self, function: &FunctionSignature, class_name: Option<&str>) -> String {
+    fn format_function_signature(&self, function: &FunctionSignature, _class_name: Option<&str>) -> String {
         let mut output = String::new();
         
         // Description
@@ -1494,7 +2393,7 @@ impl ProjectContext {
         // Parameters
         for param in &function.parameters {
             let optional_marker = if param.optional { "?" } else { "" };
-            let type_name = self.type_name_for_info(&param.type_info);
+            let type_name = param.type_info.to_string();
             let description = param.description.as_deref().unwrap_or("");
             
             output.push_str(&format!("---@param {}{} {} {}\n", 
@@ -1504,7 +2403,7 @@ impl ProjectContext {
         // Return types
         if !function.return_types.is_empty() {
             let return_types = function.return_types.iter()
-                .map(|rt| self.type_name_for_info(rt))
+                .map(TypeInfo::to_string)
                 .collect::<Vec<_>>()
                 .join(", ");
                 
@@ -1513,16 +2412,423 @@ impl ProjectContext {
         
         output
     }
-    
-    /// Get a string representation of a TypeInfo
-    fn type_name_for_info(&self, type_info: &TypeInfo) -> String {
-        match type_info {
-            TypeInfo::String => "string".to_string(),
-            TypeInfo::Number => "number".to_string(),
-            TypeInfo::Boolean => "boolean".to_string(),
-            TypeInfo::Table => "table".to_string(),
-            TypeInfo::Function => "function".to_string(),
-            TypeInfo::Unknown => "any".to_string(),
+}
+
+/// The module name a search template (`lua/?.lua`) gives a `/`-separated path relative to
+/// the project root, if the template matches it
+fn module_name_from_template(template: &str, path: &str) -> Option<String> {
+    let (prefix, suffix) = template.split_once('?')?;
+    let module_path = path.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    if module_path.is_empty() {
+        return None;
+    }
+    Some(module_path.replace('/', "."))
+}
+
+/// Whether a directory is skipped when scanning for Lua files: hidden directories
+/// (including `.git`), `node_modules` and `target`
+pub fn is_excluded_dir(name: &str) -> bool {
+    name.starts_with('.') || name == "node_modules" || name == "target"
+}
+
+/// Merge an observed class or alias into the existing definition of the same name
+fn merge_custom_type(current: &mut CustomType, observed: &CustomType, conflicts: &mut Vec<String>) {
+    if current.is_alias != observed.is_alias {
+        conflicts.push(format!("`{}` is both a class and an alias", current.name));
+        return;
+    }
+    if current.description.is_none() {
+        current.description = observed.description.clone();
+    }
+    if current.parents.is_empty() {
+        current.parents = observed.parents.clone();
+    } else if !observed.parents.is_empty() && current.parents != observed.parents {
+        conflicts.push(format!("`{}` inherits from different classes", current.name));
+    }
+    for variant in &observed.variants {
+        if !current.variants.contains(variant) {
+            current.variants.push(variant.clone());
         }
     }
+
+    for field in &observed.fields {
+        match current.fields.iter_mut().find(|f| f.name == field.name) {
+            None => current.fields.push(field.clone()),
+            Some(existing) if existing.type_info == TypeInfo::Unknown => {
+                existing.type_info = field.type_info.clone();
+            }
+            Some(existing) if field.type_info != TypeInfo::Unknown && existing.type_info != field.type_info => {
+                conflicts.push(format!(
+                    "field `{}.{}` is `{}` in the type file but `{}` in the project",
+                    current.name, field.name, existing.type_info, field.type_info
+                ));
+            }
+            Some(existing) => {
+                if existing.description.is_none() {
+                    existing.description = field.description.clone();
+                }
+            }
+        }
+    }
+
+    for (method_name, signature) in &observed.methods {
+        match current.methods.get_mut(method_name) {
+            Some(existing) => {
+                let label = format!("{}:{}", current.name, method_name);
+                merge_signature(existing, signature, &label, conflicts);
+            }
+            None => {
+                current.methods.insert(method_name.clone(), signature.clone());
+            }
+        }
+    }
+}
+
+/// Merge an observed function signature into the existing one; unknown types and missing
+/// descriptions are filled in, and differing parameters or returns are a conflict
+fn merge_signature(current: &mut FunctionSignature, observed: &FunctionSignature, label: &str, conflicts: &mut Vec<String>) {
+    let differs = |a: &TypeInfo, b: &TypeInfo| *a != TypeInfo::Unknown && *b != TypeInfo::Unknown && a != b;
+    let params_differ = current.parameters.len() != observed.parameters.len()
+        || current.parameters.iter().zip(&observed.parameters)
+            .any(|(a, b)| differs(&a.type_info, &b.type_info));
+    let returns_differ = !current.return_types.is_empty()
+        && !observed.return_types.is_empty()
+        && (current.return_types.len() != observed.return_types.len()
+            || current.return_types.iter().zip(&observed.return_types).any(|(a, b)| differs(a, b)));
+    if params_differ || returns_differ {
+        conflicts.push(format!("`{}` has a different signature in the type file and the project", label));
+        return;
+    }
+
+    if current.description.is_none() {
+        current.description = observed.description.clone();
+    }
+    for (existing, param) in current.parameters.iter_mut().zip(&observed.parameters) {
+        if existing.type_info == TypeInfo::Unknown {
+            existing.type_info = param.type_info.clone();
+        }
+        if existing.description.is_none() {
+            existing.description = param.description.clone();
+        }
+    }
+    if current.return_types.is_empty() {
+        current.return_types = observed.return_types.clone();
+    }
+}
+
+/// Global tables a framework provides to user code
+fn framework_global_names(framework: &str) -> &'static [&'static str] {
+    match framework {
+        "neovim" => &["vim"],
+        "wezterm" => &["wezterm"],
+        "love2d" => &["love"],
+        "hammerspoon" => &["hs", "spoon"],
+        "openresty" => &["ngx", "ndk"],
+        "roblox" => &["game", "workspace", "script", "Instance", "CFrame", "Enum"],
+        "yazi" => &["ya", "cx", "ui", "Url"],
+        _ => &[],
+    }
+}
+
+/// Quote a string as a JSON string literal
+fn json_string(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Format already-quoted JSON values as an array
+fn json_array(values: &[String]) -> String {
+    format!("[{}]", values.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn require_paths_resolve_through_package_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("app/widgets")).unwrap();
+        fs::create_dir_all(root.join("lua/plugin")).unwrap();
+        fs::write(root.join("app/util.lua"), "return {}").unwrap();
+        fs::write(root.join("app/widgets/init.lua"), "return {}").unwrap();
+        fs::write(root.join("lua/plugin/core.lua"), "return {}").unwrap();
+
+        let mut context = ProjectContext::new();
+        context.project_root = Some(root.to_path_buf());
+        assert_eq!(context.resolve_require_path("app.util"), Some(root.join("app/util.lua")));
+        assert_eq!(
+            context.resolve_require_path("app.widgets"),
+            Some(root.join("app/widgets/init.lua"))
+        );
+        assert_eq!(context.resolve_require_path("app.missing"), None);
+        assert_eq!(context.resolve_require_path("plugin.core"), None);
+
+        context.set_package_path("lua/?.lua;lua/?/init.lua");
+        assert_eq!(
+            context.resolve_require_path("plugin.core"),
+            Some(root.join("lua/plugin/core.lua"))
+        );
+    }
+
+    #[test]
+    fn dependency_cycles() {
+        let mut context = ProjectContext::new();
+        context.load_module("a", "local b = require(\"b\")\nreturn {}");
+        context.load_module("b", "local a = require(\"a\")\nreturn {}");
+        context.load_module("c", "local c = require(\"c\")\nreturn {}");
+        context.load_module("d", "local a = require(\"a\")\nreturn {}");
+        context.build_dependency_graph();
+        assert_eq!(
+            context.find_dependency_cycles(),
+            vec![vec!["a".to_string(), "b".to_string()], vec!["c".to_string()]]
+        );
+    }
+
+    #[test]
+    fn framework_registry_is_created_on_first_use() {
+        // Building the registry scans the framework directories, so contexts that never
+        // touch frameworks must not build it.
+        let mut context = ProjectContext::new_with_version(LuaVersion::Lua51);
+        context.load_module("m", "local M = {}\nreturn M");
+        context.build_dependency_graph();
+        assert!(context.framework_registry.is_none());
+
+        assert!(!context.framework_registry().get_framework_names().is_empty());
+        assert!(context.framework_registry.is_some());
+    }
+
+    #[test]
+    fn neovim_template_registers_vim_functions() {
+        let template =
+            crate::frameworks::create_framework_template("neovim", "0.10.0", LuaVersion::Lua51).unwrap();
+        let mut context = ProjectContext::new();
+        context.load_definitions(&template);
+        let signatures = &context.type_registry.function_signatures;
+        assert!(signatures.contains_key("vim.api.get_current_buf"));
+        assert!(signatures.contains_key("vim.api.command"));
+    }
+
+    #[test]
+    fn luajit_features() {
+        let jit = LuaVersion::LuaJIT;
+        for feature in ["bit", "ffi", "jit", "setfenv", "unpack", "goto"] {
+            assert!(jit.has_feature(feature), "{}", feature);
+        }
+        for feature in ["bit32", "utf8", "integer_division", "to_close"] {
+            assert!(!jit.has_feature(feature), "{}", feature);
+        }
+        assert!(!LuaVersion::Lua51.has_feature("ffi"));
+        assert_eq!(LuaVersion::parse("luajit"), Some(LuaVersion::LuaJIT));
+    }
+
+    #[test]
+    fn ffi_is_registered_for_luajit() {
+        let context = ProjectContext::new_with_version(LuaVersion::LuaJIT);
+        let signatures = &context.type_registry.function_signatures;
+        assert!(signatures.contains_key("ffi.new"));
+        assert!(signatures.contains_key("ffi.cdef"));
+        let context = ProjectContext::new_with_version(LuaVersion::Lua54);
+        assert!(!context.type_registry.function_signatures.contains_key("ffi.new"));
+    }
+
+    #[test]
+    fn scanning_without_a_project_root() {
+        let mut context = ProjectContext::new();
+        assert!(matches!(context.scan_lua_files(), Err(LuaToolsError::NoProjectRoot)));
+        assert!(matches!(context.process_type_file(), Err(LuaToolsError::NoProjectRoot)));
+    }
+
+    #[test]
+    fn functions_returning_a_class_keep_its_name() {
+        let mut context = ProjectContext::new();
+        context.load_definitions(
+            "---@class Account\n---@field balance number\n\n---@return Account\nfunction open() end\n",
+        );
+        let signature = &context.type_registry.function_signatures["open"];
+        assert_eq!(signature.return_types, vec![TypeInfo::Named("Account".to_string())]);
+        let type_file = context.generate_type_file().unwrap();
+        assert!(type_file.contains("---@return Account\nTypes.open = function() end"));
+    }
+
+    #[test]
+    fn string_alias_resolves_to_a_union() {
+        let mut context = ProjectContext::new();
+        context.load_definitions(
+            "---@alias Mode\n---| \"read\"\n---| \"write\"\n\n\
+             ---@param mode Mode\nfunction open(mode) end\n",
+        );
+        assert_eq!(
+            context.resolve_type("Mode"),
+            Some(TypeInfo::Union(vec![
+                TypeInfo::Literal("\"read\"".to_string()),
+                TypeInfo::Literal("\"write\"".to_string()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn fields_are_inherited_from_the_parent_class() {
+        let mut context = ProjectContext::new();
+        context.load_definitions(
+            "---@class Animal\n---@field name string\nlocal Animal = {}\n\n\
+             ---@class Dog : Animal\n---@field breed string\nlocal Dog = {}\n",
+        );
+        let registry = &context.type_registry;
+        let name = registry.find_field("Dog", "name").expect("inherited field not found");
+        assert_eq!(name.type_info, TypeInfo::String);
+        assert!(registry.find_field("Dog", "breed").is_some());
+        assert!(registry.find_field("Animal", "breed").is_none());
+        assert!(registry.is_subclass("Dog", "Animal"));
+    }
+
+    #[test]
+    fn merging_adds_a_new_field_to_an_existing_class() {
+        let existing = "---@class Account\n---@field balance number Current balance\n\
+                        Types.Account = {}\n";
+        let mut project = ProjectContext::new();
+        project.load_definitions(
+            "---@class Account\n---@field balance number\n---@field owner string\n\
+             local Account = {}\n",
+        );
+
+        let merge = project.merge_type_file(existing).unwrap();
+        assert!(merge.conflicts.is_empty(), "{:?}", merge.conflicts);
+        let content = &merge.content;
+        assert!(content.contains("---@field balance number Current balance\n"), "{}", content);
+        assert!(content.contains("---@field owner string"), "{}", content);
+    }
+
+    #[test]
+    fn find_symbol_by_full_and_bare_name() {
+        let mut context = ProjectContext::new();
+        context.load_definitions(
+            "---@param s string\n---@return string[]\nfunction strings.split(s) end\n",
+        );
+        let signature = context.find_symbol("strings.split").expect("signature not found");
+        assert_eq!(signature.return_types, vec![TypeInfo::Array(Box::new(TypeInfo::String))]);
+        let bare = context.find_symbol("split").map(|signature| signature.name.as_str());
+        assert_eq!(bare, Some("strings.split"));
+        assert!(context.find_symbol("join").is_none());
+    }
+
+    #[test]
+    fn module_names_from_file_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("lua/app")).unwrap();
+        fs::write(root.join("lua/app/util.lua"), "return {}").unwrap();
+        fs::write(root.join("lua/app/init.lua"), "return {}").unwrap();
+        fs::write(root.join("lua/app/unknown.lua"), "return {}").unwrap();
+
+        let mut context = ProjectContext::new();
+        context.project_root = Some(root.to_path_buf());
+        context.set_package_path("lua/?.lua;lua/?/init.lua");
+        context.load_module("app.util", "return {}");
+        context.load_module("app", "return {}");
+        assert_eq!(context.module_for_path(&root.join("lua/app/util.lua")), Some("app.util"));
+        assert_eq!(context.module_for_path(Path::new("lua/app/init.lua")), Some("app"));
+        assert_eq!(context.module_for_path(&root.join("lua/app/unknown.lua")), None);
+    }
+
+    #[test]
+    fn reanalyzing_a_module_updates_its_dependents() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("util.lua"), "").unwrap();
+        let util = |returned: &str| {
+            format!("local M = {{}}\n---@return {}\nfunction M.greet(name) end\nreturn M", returned)
+        };
+        let main = "local util = require(\"util\")\nreturn util.greet(\"x\")";
+
+        let mut context = ProjectContext::new();
+        context.project_root = Some(root.to_path_buf());
+        context.load_module("util", &util("string"));
+        context.load_module("main", main);
+        context.build_dependency_graph();
+        let returns = |context: &ProjectContext| {
+            context.find_symbol("util.greet").unwrap().return_types.clone()
+        };
+        assert_eq!(returns(&context), vec![TypeInfo::String]);
+
+        let dirty = context.reanalyze_file(&root.join("util.lua"), &util("number"));
+        assert_eq!(dirty, Some(vec!["main".to_string()]));
+        assert!(!context.modules["main"].processed);
+        assert_eq!(returns(&context), vec![TypeInfo::Number]);
+
+        // The dependent resolves the call to the new signature
+        let tokens = crate::tokenizer::CodeTokenizer::new(main).tokenize();
+        let ast = crate::parser::code_parser::CodeParser::new(tokens).parse();
+        let analyzer = crate::type_inference::TypeAnalyzer::new(context);
+        assert_eq!(analyzer.infer_return_types(&ast), vec![TypeInfo::Number]);
+    }
+
+    #[test]
+    fn markdown_for_a_class_with_two_fields() {
+        let mut context = ProjectContext::new();
+        context.load_definitions(
+            "---@class Point\n---@field x number Horizontal position\n---@field y number\n\
+             local Point = {}\n",
+        );
+        assert_eq!(
+            context.generate_markdown(),
+            "## Class `Point`\n\n\
+             ### Fields\n\n\
+             - `x`: `number` — Horizontal position\n\
+             - `y`: `number`\n\n"
+        );
+    }
+
+    #[test]
+    fn luarc_for_a_neovim_project() {
+        let mut context = ProjectContext::new();
+        let version = context.framework_registry().get_latest_version("neovim").unwrap();
+        let library = context
+            .framework_registry()
+            .get_framework("neovim", &version)
+            .and_then(|framework| framework.definition_path.clone())
+            .expect("neovim has no definition file");
+        context.detected_frameworks = vec![("neovim".to_string(), version)];
+
+        let luarc = context.generate_luarc();
+        assert!(luarc.contains("\"runtime.version\": \"5.1\""), "{}", luarc);
+        let entry = format!("\"workspace.library\": [\"{}\"]", library.display());
+        assert!(luarc.contains(&entry), "{}", luarc);
+        assert!(luarc.contains("\"diagnostics.globals\": [\"vim\"]"), "{}", luarc);
+    }
+
+    #[test]
+    fn meta_files_register_classes_and_functions() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("library")).unwrap();
+        fs::write(
+            dir.path().join("library/http.lua"),
+            "---@meta\n\n\
+             ---@class http.Response\n---@field status integer\n---@field body string\n\
+             local Response = {}\n\n\
+             ---@param url string\n---@return http.Response\nfunction http.get(url) end\n",
+        )
+        .unwrap();
+
+        let mut context = ProjectContext::new();
+        assert_eq!(context.load_definition_dir(dir.path()).unwrap(), 1);
+        let registry = &context.type_registry;
+        let status = registry.find_field("http.Response", "status").expect("class not registered");
+        assert_eq!(status.type_info, TypeInfo::Integer);
+        let get = &registry.function_signatures["http.get"];
+        assert_eq!(get.parameters[0].type_info, TypeInfo::String);
+        assert_eq!(get.return_types, vec![TypeInfo::Named("http.Response".to_string())]);
+    }
 }
@@ -5,7 +5,7 @@ use crate::tokenizer::token::AnnotationSubToken;
 
 /// Utility function to check if a character is considered punctuation in annotation tokenization.
 fn is_annotation_punctuation(ch: char) -> bool {
-    matches!(ch, ':' | ',' | '<' | '>' | '(' | ')' | '|' | '#')
+    matches!(ch, ':' | ',' | '<' | '>' | '(' | ')' | '{' | '}' | '|' | '#' | '?')
 }
 
 /// Reads an identifier from the lexer, supporting dotted names.
@@ -65,8 +65,11 @@ fn tokenize_annotation(text: &str) -> Vec<AnnotationSubToken> {
                 '>' => AnnotationSubToken::GreaterThan,
                 '(' => AnnotationSubToken::OpenParen,
                 ')' => AnnotationSubToken::CloseParen,
+                '{' => AnnotationSubToken::OpenBrace,
+                '}' => AnnotationSubToken::CloseBrace,
                 '|' => AnnotationSubToken::Operator("|".to_string()),
                 '#' => AnnotationSubToken::Operator("#".to_string()),
+                '?' => AnnotationSubToken::Operator("?".to_string()),
                 other => AnnotationSubToken::Operator(other.to_string()),
             };
             tokens.push(token);
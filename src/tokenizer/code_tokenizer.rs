@@ -1,8 +1,9 @@
 // src/tokenizer/code_tokenizer.rs
 
 use crate::tokenizer::annotation_tokenizer::parse_annotation_subtokens;
+use crate::tokenizer::interner::Name;
 use crate::tokenizer::lexer::Lexer;
-use crate::tokenizer::token::{AnnotationSubToken, Span, Token};
+use crate::tokenizer::token::{Span, Token};
 
 pub struct CodeTokenizer {
     pub lexer: Lexer,
@@ -24,7 +25,7 @@ impl CodeTokenizer {
                 continue;
             }
             // Comments and annotations.
-            else if ch == '-' && self.lexer.peek() == Some('-') {
+            else if ch == '-' && self.lexer.peek_n(1) == Some('-') {
                 let start_pos = self.lexer.pos;
                 let start_line = self.lexer.line;
                 let start_col = self.lexer.column;
@@ -33,18 +34,27 @@ impl CodeTokenizer {
                 self.lexer.advance();
                 // Check for block comment open marker: exactly "--[["
                 if self.lexer.current_char_opt() == Some('[') && self.lexer.peek_n(1) == Some('[') {
-                    let open_span = Span::new(start_pos, start_pos + 4, start_line, start_col);
                     self.lexer.advance(); // consume first '['
                     self.lexer.advance(); // consume second '['
-                    let content = self.lexer.collect_until_str("--]]");
+                    // The block ends at the first "]]"; a "--" just before it is conventional
+                    // ("--]]") and is not part of the comment text.
+                    let (content_start, content_line, content_col) =
+                        (self.lexer.pos, self.lexer.line, self.lexer.column);
+                    let content = self.lexer.collect_until_str("]]");
+                    if self.lexer.pos >= self.lexer.input.len() {
+                        let span = Span::new(start_pos, self.lexer.pos, start_line, start_col);
+                        tokens.push(Token::Error("unterminated block comment".to_string(), span));
+                        continue;
+                    }
+                    let content = content.strip_suffix("--").unwrap_or(&content).to_string();
                     let content_span = Span::new(
-                        self.lexer.pos,
-                        self.lexer.pos + content.len(),
-                        start_line,
-                        start_col,
+                        content_start,
+                        content_start + content.chars().count(),
+                        content_line,
+                        content_col,
                     );
                     tokens.push(Token::BlockComment(content, content_span));
-                    self.lexer.advance_by(4); // consume "--]]"
+                    self.lexer.advance_by(2); // consume "]]"
                     continue;
                 }
                 // Check for annotation (if a third dash is present).
@@ -68,10 +78,20 @@ impl CodeTokenizer {
                         }
                     }
                 }
+                // `---` starts a documentation line (but `----...` is a plain comment).
+                let is_doc = self.lexer.current_char_opt() == Some('-')
+                    && self.lexer.peek_n(1) != Some('-');
+                if is_doc {
+                    self.lexer.advance(); // consume third dash
+                }
                 // Otherwise, it's a normal comment.
                 let comment = self.lexer.collect_until('\n');
                 let span = Span::new(start_pos, self.lexer.pos, start_line, start_col);
-                tokens.push(Token::Comment(comment, span));
+                if is_doc {
+                    tokens.push(Token::DocComment(comment, span));
+                } else {
+                    tokens.push(Token::Comment(comment, span));
+                }
                 continue;
             }
             // ... (other tokenization logic for identifiers, numbers, strings, etc.) ...
@@ -89,15 +109,15 @@ impl CodeTokenizer {
                 if is_keyword(&ident) {
                     tokens.push(Token::Keyword(ident, span));
                 } else {
-                    tokens.push(Token::Identifier(vec![ident], span));
+                    tokens.push(Token::Identifier(Name::new(&ident), span));
                 }
             }
             // ... (handle numbers, strings, operators, punctuation, etc.) ...
-            else if ch.is_digit(10) {
+            else if ch.is_ascii_digit() {
                 let start_pos = self.lexer.pos;
                 let start_line = self.lexer.line;
                 let start_col = self.lexer.column;
-                let number = self.lexer.collect_while(|c| c.is_digit(10));
+                let number = self.lexer.collect_while(|c| c.is_ascii_digit());
                 let span = Span::new(start_pos, self.lexer.pos, start_line, start_col);
                 tokens.push(Token::NumberLiteral(number, span));
             } else if ch == '"' || ch == '\'' {
@@ -107,22 +127,91 @@ impl CodeTokenizer {
                 let quote = ch;
                 self.lexer.advance(); // consume opening quote
                 let string_val = self.lexer.collect_until(quote);
-                self.lexer.advance(); // consume closing quote
+                if self.lexer.advance().is_none() {
+                    // The input ended before the closing quote.
+                    let span = Span::new(start_pos, self.lexer.pos, start_line, start_col);
+                    tokens.push(Token::Error("unterminated string".to_string(), span));
+                    continue;
+                }
                 let span = Span::new(start_pos, self.lexer.pos, start_line, start_col);
                 tokens.push(Token::StringLiteral(string_val, span));
             }
-            // Operators and punctuation are handled similarly...
+            // Long strings: `[[...]]`, `[==[...]==]`.
+            else if let Some(level) = self.long_bracket_level() {
+                let start_pos = self.lexer.pos;
+                let start_line = self.lexer.line;
+                let start_col = self.lexer.column;
+                self.lexer.advance_by(level + 2); // consume the opening bracket
+                // A newline right after the opening bracket is not part of the string.
+                if self.lexer.current_char_opt() == Some('\n') {
+                    self.lexer.advance();
+                }
+                let close = format!("]{}]", "=".repeat(level));
+                let string_val = self.lexer.collect_until_str(&close);
+                if self.lexer.pos >= self.lexer.input.len() {
+                    let span = Span::new(start_pos, self.lexer.pos, start_line, start_col);
+                    tokens.push(Token::Error("unterminated long string".to_string(), span));
+                    continue;
+                }
+                self.lexer.advance_by(level + 2); // consume the closing bracket
+                let span = Span::new(start_pos, self.lexer.pos, start_line, start_col);
+                tokens.push(Token::StringLiteral(string_val, span));
+            }
+            // Operators and punctuation.
             else {
                 let start_pos = self.lexer.pos;
                 let start_line = self.lexer.line;
                 let start_col = self.lexer.column;
-                let span = Span::new(start_pos, start_pos + 1, start_line, start_col);
-                tokens.push(Token::Operator(ch.to_string(), span));
-                self.lexer.advance();
+                let op = self.collect_operator();
+                let span = Span::new(start_pos, self.lexer.pos, start_line, start_col);
+                let token = match op.as_str() {
+                    "(" => Token::ParenOpen(span),
+                    ")" => Token::ParenClose(span),
+                    "{" => Token::BraceOpen(span),
+                    "}" => Token::BraceClose(span),
+                    "[" => Token::BracketOpen(span),
+                    "]" => Token::BracketClose(span),
+                    "=" => Token::Assignment(span),
+                    "..." => Token::VarArg(span),
+                    _ => Token::Operator(op, span),
+                };
+                tokens.push(token);
             }
         }
         tokens
     }
+
+    /// The number of `=` in the opening long bracket (`[[`, `[=[`, ...) at the current
+    /// position, or None if there is none.
+    fn long_bracket_level(&self) -> Option<usize> {
+        if self.lexer.current_char_opt() != Some('[') {
+            return None;
+        }
+        let mut level = 0;
+        while self.lexer.peek_n(level + 1) == Some('=') {
+            level += 1;
+        }
+        (self.lexer.peek_n(level + 1) == Some('[')).then_some(level)
+    }
+
+    /// Consume an operator or punctuation character, preferring the longest multi-character operator.
+    fn collect_operator(&mut self) -> String {
+        const MULTI_CHAR_OPERATORS: [&str; 10] =
+            ["...", "==", "~=", "<=", ">=", "..", "//", "::", "<<", ">>"];
+        for op in MULTI_CHAR_OPERATORS {
+            let matches = op
+                .chars()
+                .enumerate()
+                .all(|(i, c)| self.lexer.peek_n(i) == Some(c));
+            if matches {
+                self.lexer.advance_by(op.len());
+                return op.to_string();
+            }
+        }
+        let ch = self.lexer.current_char();
+        self.lexer.advance();
+        ch.to_string()
+    }
 }
 
 fn is_keyword(ident: &str) -> bool {
@@ -137,6 +226,7 @@ fn is_keyword(ident: &str) -> bool {
             | "false"
             | "for"
             | "function"
+            | "goto"
             | "if"
             | "in"
             | "local"
@@ -152,3 +242,58 @@ fn is_keyword(ident: &str) -> bool {
             | "require"
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The messages of the error tokens produced for `source`
+    fn errors(source: &str) -> Vec<String> {
+        CodeTokenizer::new(source)
+            .tokenize()
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Error(message, _) => Some(message),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unterminated_input_ends_with_an_error_token() {
+        assert_eq!(errors("local s = \"unterminated"), vec!["unterminated string"]);
+        assert_eq!(errors("local s = [[unterminated"), vec!["unterminated long string"]);
+        assert_eq!(errors("local x = 1\n--[[ unterminated"), vec!["unterminated block comment"]);
+        assert!(errors("local s = \"done\"").is_empty());
+    }
+
+    #[test]
+    fn block_comment_span_brackets_the_body() {
+        let source = "local x = 1\n--[[ first\nsecond ]]\nreturn x";
+        let tokens = CodeTokenizer::new(source).tokenize();
+        let (text, span) = tokens
+            .iter()
+            .find_map(|token| match token {
+                Token::BlockComment(text, span) => Some((text, span)),
+                _ => None,
+            })
+            .expect("no block comment token");
+        assert_eq!(text, " first\nsecond ");
+        assert_eq!(&source[span.start..span.end], " first\nsecond ");
+        assert_eq!(&source[span.start - 4..span.start], "--[[");
+        assert_eq!(&source[span.end..span.end + 2], "]]");
+    }
+
+    #[test]
+    fn leading_byte_order_mark_is_skipped() {
+        let tokens = CodeTokenizer::new("\u{FEFF}local x = 1").tokenize();
+        match &tokens[0] {
+            Token::Keyword(keyword, span) => {
+                assert_eq!(keyword, "local");
+                assert_eq!(span.column, 1);
+            }
+            other => panic!("expected the local keyword, got {:?}", other),
+        }
+        assert_eq!(tokens.len(), 4);
+    }
+}
@@ -0,0 +1,139 @@
+// src/tokenizer/interner.rs
+//
+// Process-wide string interner for identifier names.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{Mutex, OnceLock};
+
+/// An interned identifier. Equal names share one id and one allocation, so comparing and
+/// hashing them is as cheap as comparing and hashing an integer. The name carries its text,
+/// so reading it never touches the interner.
+#[derive(Clone, Copy)]
+pub struct Name {
+    id: u32,
+    text: &'static str,
+}
+
+#[derive(Default)]
+struct Interner {
+    ids: HashMap<&'static str, Name>,
+}
+
+/// Interned strings live for the rest of the process; a project only has so many distinct
+/// identifiers, and handing out `&'static str` means callers never borrow the interner.
+fn interner() -> &'static Mutex<Interner> {
+    static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(Mutex::default)
+}
+
+impl Name {
+    /// Intern `text`, returning the existing id if it was seen before.
+    pub fn new(text: &str) -> Self {
+        let mut interner = interner().lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(&name) = interner.ids.get(text) {
+            return name;
+        }
+        let text: &'static str = Box::leak(text.to_string().into_boxed_str());
+        let name = Name {
+            id: interner.ids.len() as u32,
+            text,
+        };
+        interner.ids.insert(text, name);
+        name
+    }
+
+    /// The interned text.
+    pub fn as_str(self) -> &'static str {
+        self.text
+    }
+
+    /// The numeric id; equal names have equal ids.
+    pub fn id(self) -> u32 {
+        self.id
+    }
+}
+
+impl PartialEq for Name {
+    fn eq(&self, other: &Name) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Name {}
+
+impl Hash for Name {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl Deref for Name {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq<str> for Name {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Name {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl From<&str> for Name {
+    fn from(text: &str) -> Self {
+        Name::new(text)
+    }
+}
+
+impl fmt::Debug for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Name {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Name {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Ok(Name::new(&text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_names_share_an_id() {
+        let first = Name::new("interner_test_name");
+        let second = Name::from("interner_test_name");
+        assert_eq!(first.id(), second.id());
+        assert!(std::ptr::eq(first.as_str(), second.as_str()));
+        assert_ne!(Name::new("interner_test_other").id(), first.id());
+        assert_eq!(first, "interner_test_name");
+    }
+}
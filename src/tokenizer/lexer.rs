@@ -1,6 +1,5 @@
 // src/lexer.rs
 
-use crate::tokenizer::token::Span;
 
 pub struct Lexer {
     pub input: Vec<char>,
@@ -13,7 +12,8 @@ impl Lexer {
     pub fn new(input: &str) -> Self {
         Self {
             input: input.chars().collect(),
-            pos: 0,
+            // Skip a UTF-8 byte order mark, keeping positions relative to the full input.
+            pos: usize::from(input.starts_with('\u{FEFF}')),
             line: 1,
             column: 1,
         }
@@ -27,8 +27,9 @@ impl Lexer {
         self.input.get(self.pos + n).cloned()
     }
 
+    /// The character at the current position, or `'\0'` at the end of input.
     pub fn current_char(&self) -> char {
-        self.input[self.pos]
+        self.current_char_opt().unwrap_or('\0')
     }
 
     pub fn current_char_opt(&self) -> Option<char> {
@@ -82,10 +83,10 @@ impl Lexer {
     }
 
     pub fn collect_until_str(&mut self, delimiter: &str) -> String {
+        let delimiter: Vec<char> = delimiter.chars().collect();
         let mut result = String::new();
         while self.pos < self.input.len() {
-            let remaining: String = self.input[self.pos..].iter().collect();
-            if remaining.starts_with(delimiter) {
+            if self.input[self.pos..].starts_with(&delimiter) {
                 break;
             }
             result.push(self.current_char());
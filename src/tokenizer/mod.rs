@@ -1,8 +1,10 @@
 pub mod annotation_tokenizer;
 pub mod code_tokenizer;
+pub mod interner;
 pub mod lexer;
 pub mod token;
 
 // Optionally, provide a unified interface here.
 pub use annotation_tokenizer::parse_annotation_subtokens;
 pub use code_tokenizer::CodeTokenizer;
+pub use interner::Name;
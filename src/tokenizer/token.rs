@@ -1,6 +1,9 @@
 // src/token.rs
 
+use crate::tokenizer::interner::Name;
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Span {
     pub start: usize,
     pub end: usize, // exclusive end offset
@@ -17,10 +20,16 @@ impl Span {
             column,
         }
     }
+
+    /// Span from the start of this span to the end of `end`.
+    pub fn through(&self, end: &Span) -> Span {
+        Span::new(self.start, end.end, self.line, self.column)
+    }
 }
 
 /// Structured subtokens for annotation content.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AnnotationSubToken {
     Prefix(String),
     Identifier(Vec<String>),
@@ -31,6 +40,8 @@ pub enum AnnotationSubToken {
     GreaterThan,
     OpenParen,
     CloseParen,
+    OpenBrace,
+    CloseBrace,
     StringLiteral(String),
     NumberLiteral(String),
     Text(String),
@@ -51,6 +62,8 @@ impl AnnotationSubToken {
             AnnotationSubToken::GreaterThan => format!("{}GreaterThan(>)", indent_str),
             AnnotationSubToken::OpenParen => format!("{}OpenParen(()", indent_str),
             AnnotationSubToken::CloseParen => format!("{}CloseParen())", indent_str),
+            AnnotationSubToken::OpenBrace => format!("{}OpenBrace({{)", indent_str),
+            AnnotationSubToken::CloseBrace => format!("{}CloseBrace(}})", indent_str),
             AnnotationSubToken::StringLiteral(s) => {
                 format!("{}StringLiteral({})", indent_str, s)
             }
@@ -64,8 +77,9 @@ impl AnnotationSubToken {
 
 /// Unified token types.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Token {
-    Identifier(Vec<String>, Span),
+    Identifier(Name, Span),
     DroppedIdentifier(Span),
     Keyword(String, Span),
     Operator(String, Span),
@@ -76,6 +90,8 @@ pub enum Token {
     BlockComment(String, Span),
     BlockCommentClose(Span),
     Comment(String, Span),
+    /// A `---` documentation line that is not an annotation, with the text after the dashes.
+    DocComment(String, Span),
     StringLiteral(String, Span),
     NumberLiteral(String, Span),
     VarArg(Span),
@@ -85,15 +101,42 @@ pub enum Token {
     BraceClose(Span),
     BracketOpen(Span),
     BracketClose(Span),
+    /// Input that could not be tokenized (e.g. an unterminated string), with a description
+    /// of the problem.
+    Error(String, Span),
 }
 
 impl Token {
+    pub fn span(&self) -> &Span {
+        match self {
+            Token::Identifier(_, span)
+            | Token::Keyword(_, span)
+            | Token::Operator(_, span)
+            | Token::Annotation(_, span)
+            | Token::BlockComment(_, span)
+            | Token::Comment(_, span)
+            | Token::DocComment(_, span)
+            | Token::StringLiteral(_, span)
+            | Token::NumberLiteral(_, span)
+            | Token::Error(_, span) => span,
+            Token::DroppedIdentifier(span)
+            | Token::Assignment(span)
+            | Token::BlockCommentOpen(span)
+            | Token::BlockCommentClose(span)
+            | Token::VarArg(span)
+            | Token::ParenOpen(span)
+            | Token::ParenClose(span)
+            | Token::BraceOpen(span)
+            | Token::BraceClose(span)
+            | Token::BracketOpen(span)
+            | Token::BracketClose(span) => span,
+        }
+    }
+
     pub fn pretty_print(&self, indent: usize) -> String {
         let indent_str = "  ".repeat(indent);
         match self {
-            Token::Identifier(parts, _) => {
-                format!("{}Identifier({})", indent_str, parts.join("."))
-            }
+            Token::Identifier(name, _) => format!("{}Identifier({})", indent_str, name),
             Token::DroppedIdentifier(_) => format!("{}DroppedIdentifier", indent_str),
             Token::Keyword(s, _) => format!("{}Keyword({})", indent_str, s),
             Token::Operator(s, _) => format!("{}Operator({})", indent_str, s),
@@ -112,6 +155,7 @@ impl Token {
             }
             Token::BlockCommentClose(_) => format!("{}BlockCommentClose", indent_str),
             Token::Comment(text, _) => format!("{}Comment({})", indent_str, text),
+            Token::DocComment(text, _) => format!("{}DocComment({})", indent_str, text),
             Token::StringLiteral(text, _) => format!("{}StringLiteral({})", indent_str, text),
             Token::NumberLiteral(text, _) => format!("{}NumberLiteral({})", indent_str, text),
             Token::VarArg(_) => format!("{}VarArg(...)", indent_str),
@@ -121,6 +165,7 @@ impl Token {
             Token::BraceClose(_) => format!("{}BraceClose", indent_str),
             Token::BracketOpen(_) => format!("{}BracketOpen", indent_str),
             Token::BracketClose(_) => format!("{}BracketClose", indent_str),
+            Token::Error(message, _) => format!("{}Error({})", indent_str, message),
         }
     }
 }
@@ -1,8 +1,15 @@
 // src/type_inference.rs
 
-use crate::parser::ast::{CodeASTNode, ExportItem, Expression, TypeInfo};
-use crate::project_context::ProjectContext;
-use std::collections::HashMap;
+use crate::diagnostics::Diagnostic;
+use crate::parser::ast::{
+    AnnotationASTNode, CodeASTNode, ExportItem, Expression, FieldKey, TypeInfo,
+};
+use crate::parser::lua_type::LuaType;
+use crate::parser::parser_helpers;
+use crate::parser::visitor::{self, Visitor};
+use crate::project_context::{self, DependencyInfo, LuaVersion, ProjectContext};
+use crate::tokenizer::token::Span;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FieldInfo {
@@ -16,11 +23,43 @@ pub struct FunctionSignature {
     pub returns: Vec<TypeInfo>,
 }
 
+/// A `local` declared in a scope, with the number of times it has been read.
+#[derive(Debug, Clone)]
+pub struct LocalBinding {
+    pub name: String,
+    pub span: Span,
+    pub reads: usize,
+}
+
+/// A name occurrence (declaration or reference) with the type it resolved to.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypedName {
+    pub name: String,
+    pub type_info: TypeInfo,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone)]
 pub struct ScopeContext {
     pub variables: HashMap<String, TypeInfo>,
     pub parent: Option<Box<ScopeContext>>,
     pub function_returns: Vec<TypeInfo>,
+    /// Locals declared in this scope, in declaration order.
+    pub locals: Vec<LocalBinding>,
+    /// Field types of the tables bound to names in this scope, from their constructors.
+    pub table_fields: HashMap<String, Vec<FieldInfo>>,
+    /// Signatures of the functions stored in this scope's locals (`f`) or their fields
+    /// (`M.f`), from their definitions.
+    pub functions: HashMap<String, project_context::FunctionSignature>,
+    /// Types of this scope's locals that were declared with `---@type` or `---@class`.
+    pub declared_types: HashMap<String, TypeInfo>,
+}
+
+impl Default for ScopeContext {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ScopeContext {
@@ -29,7 +68,44 @@ impl ScopeContext {
             variables: HashMap::new(),
             parent: None,
             function_returns: Vec::new(),
+            locals: Vec::new(),
+            table_fields: HashMap::new(),
+            functions: HashMap::new(),
+            declared_types: HashMap::new(),
+        }
+    }
+
+    /// Declare a local in this scope.
+    pub fn declare_local(&mut self, name: &str, type_info: TypeInfo, span: Span) {
+        self.variables.insert(name.to_string(), type_info);
+        self.table_fields.remove(name);
+        self.functions
+            .retain(|function, _| root_name(function) != name);
+        self.declared_types.remove(name);
+        self.locals.push(LocalBinding {
+            name: name.to_string(),
+            span,
+            reads: 0,
+        });
+    }
+
+    /// Record a read of `name` against the innermost binding, returning false if it is unbound.
+    pub fn mark_read(&mut self, name: &str) -> bool {
+        if let Some(local) = self.locals.iter_mut().rev().find(|l| l.name == name) {
+            local.reads += 1;
+            return true;
         }
+        if self.variables.contains_key(name) {
+            return true;
+        }
+        self.parent.as_mut().is_some_and(|p| p.mark_read(name))
+    }
+
+    /// Locals that were never read, excluding names starting with `_`.
+    pub fn unused_locals(&self) -> impl Iterator<Item = &LocalBinding> {
+        self.locals
+            .iter()
+            .filter(|l| l.reads == 0 && !l.name.starts_with('_'))
     }
 
     pub fn lookup(&self, name: &str) -> Option<TypeInfo> {
@@ -38,11 +114,78 @@ impl ScopeContext {
             .cloned()
             .or_else(|| self.parent.as_ref().and_then(|p| p.lookup(name)))
     }
+
+    /// The fields of the table bound to `name`, if its innermost binding was built from a
+    /// table constructor.
+    pub fn lookup_fields(&self, name: &str) -> Option<&[FieldInfo]> {
+        if self.variables.contains_key(name) {
+            return self.table_fields.get(name).map(Vec::as_slice);
+        }
+        self.parent.as_ref().and_then(|p| p.lookup_fields(name))
+    }
+
+    /// Like `lookup_fields`, for updating the fields.
+    pub fn lookup_fields_mut(&mut self, name: &str) -> Option<&mut Vec<FieldInfo>> {
+        if self.variables.contains_key(name) {
+            return self.table_fields.get_mut(name);
+        }
+        self.parent.as_mut().and_then(|p| p.lookup_fields_mut(name))
+    }
+
+    /// The annotated type of the innermost local named `name`, if it has one.
+    pub fn lookup_declared(&self, name: &str) -> Option<&TypeInfo> {
+        if self.variables.contains_key(name) {
+            return self.declared_types.get(name);
+        }
+        self.parent.as_ref().and_then(|p| p.lookup_declared(name))
+    }
+
+    /// The signature of the function `name` (e.g. `f` or `M.f`), if the innermost local its
+    /// root is bound to holds a function defined in the source.
+    pub fn lookup_function(&self, name: &str) -> Option<&project_context::FunctionSignature> {
+        if self.variables.contains_key(root_name(name)) {
+            return self.functions.get(name);
+        }
+        self.parent.as_ref().and_then(|p| p.lookup_function(name))
+    }
+
+    /// Record the signature of a function stored in a local or a local's field, in the
+    /// scope of that local. Returns false if the root of the name is not a local.
+    pub fn define_function(&mut self, signature: project_context::FunctionSignature) -> bool {
+        if self.variables.contains_key(root_name(&signature.name)) {
+            self.functions.insert(signature.name.clone(), signature);
+            return true;
+        }
+        self.parent
+            .as_mut()
+            .is_some_and(|p| p.define_function(signature))
+    }
 }
 
 pub struct TypeAnalyzer {
     pub current_scope: ScopeContext,
     pub project_context: ProjectContext,
+    /// Diagnostics accumulated by `analyze`.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Every name occurrence seen by `analyze`, with its resolved type.
+    pub typed_names: Vec<TypedName>,
+    /// Modules loaded with `require`, in source order, with the local each was assigned to.
+    pub dependencies: Vec<DependencyInfo>,
+    /// Warn when a local shadows a binding from an enclosing scope (off by default).
+    pub report_shadowing: bool,
+    /// Globals assigned anywhere in the analyzed source.
+    globals: HashSet<String>,
+    /// Globals assigned so far, in source order, by the statements analyzed.
+    defined_globals: HashSet<String>,
+    /// How many function bodies enclose the node being analyzed.
+    function_depth: usize,
+}
+
+/// An analyzer starting from the standard library of the latest Lua version.
+impl Default for TypeAnalyzer {
+    fn default() -> Self {
+        Self::new(ProjectContext::new())
+    }
 }
 
 impl TypeAnalyzer {
@@ -50,39 +193,619 @@ impl TypeAnalyzer {
         Self {
             current_scope: ScopeContext::new(),
             project_context: project,
+            diagnostics: Vec::new(),
+            typed_names: Vec::new(),
+            dependencies: Vec::new(),
+            report_shadowing: false,
+            globals: HashSet::new(),
+            defined_globals: HashSet::new(),
+            function_depth: 0,
         }
     }
 
     pub fn analyze(&mut self, ast: &[CodeASTNode]) {
+        // Functions may run after globals defined later in the file, so collect them up front.
+        collect_globals(ast, &mut self.globals);
+        self.project_context.register_types(ast);
+        self.analyze_nodes(ast);
+
+        // Top-level locals stay visible for lookups but are only reported once.
+        let root = ScopeContext {
+            locals: std::mem::take(&mut self.current_scope.locals),
+            ..ScopeContext::new()
+        };
+        self.report_unused_locals(&root);
+    }
+
+    fn analyze_nodes(&mut self, ast: &[CodeASTNode]) {
         for node in ast {
             match node {
                 CodeASTNode::FunctionDef {
+                    name,
+                    is_local,
                     params,
+                    param_spans,
                     body,
                     return_types: _,
+                    doc,
+                    annotations,
+                    annotation_spans,
+                    span,
                     ..
                 } => {
-                    let inferred_returns = self.infer_return_types(body);
+                    self.check_param_annotations(
+                        params,
+                        param_spans,
+                        annotations,
+                        annotation_spans,
+                    );
+                    if *is_local {
+                        self.check_shadowing(name, span, false);
+                        // Declared before the body so that it can call itself.
+                        self.current_scope
+                            .declare_local(name, TypeInfo::Function, span.clone());
+                    } else if name.contains(['.', ':']) {
+                        self.current_scope.mark_read(root_name(name));
+                    } else {
+                        self.define_global(name);
+                    }
+                    self.define_function(name, params, doc, annotations);
+                    let _inferred_returns = self.infer_return_types(body);
                     // In a full integration, we might update the function node's return_types here.
-                    self.analyze_function(params, body);
+                    let mut params = annotated_params(params, annotations, &self.project_context);
+                    if let Some((owner, _)) = name.split_once(':') {
+                        let self_type = self.method_self_type(owner);
+                        if let Some(param) = params.iter_mut().find(|(param, _)| param == "self") {
+                            param.1 = self_type;
+                        }
+                    }
+                    self.analyze_function(&params, param_spans, body);
                 }
-                CodeASTNode::ModuleDeclaration { name, exports, .. } => {
+                CodeASTNode::ModuleDeclaration {
+                    name,
+                    exports,
+                    items,
+                    annotations,
+                    ..
+                } => {
+                    let type_info = declared_type(annotations, &self.project_context)
+                        .unwrap_or_else(|| TypeInfo::table_of(exports, items));
+                    self.current_scope.variables.insert(name.clone(), type_info);
+                    let named = exports
+                        .iter()
+                        .map(|export| (export.name.clone(), export.type_info.clone()));
+                    self.current_scope
+                        .table_fields
+                        .insert(name.clone(), table_fields(named, items.iter().cloned()));
                     self.analyze_module(name, exports);
                 }
-                _ => {}
+                CodeASTNode::VariableDeclaration {
+                    name,
+                    name_span,
+                    value,
+                    annotations,
+                    span,
+                    ..
+                } => {
+                    // The initializer is evaluated before the local comes into scope.
+                    let inferred = match value.as_deref() {
+                        Some(value) => {
+                            self.analyze_nodes(std::slice::from_ref(value));
+                            if let CodeASTNode::ReturnStatement(values) = value {
+                                if values
+                                    .first()
+                                    .and_then(parser_helpers::require_path)
+                                    .is_some()
+                                {
+                                    if let Some(dependency) = self.dependencies.last_mut() {
+                                        dependency.local_alias = Some(name.clone());
+                                    }
+                                }
+                            }
+                            self.infer_initializer_type(value)
+                        }
+                        None => TypeInfo::Unknown,
+                    };
+                    // A `---@type` or `---@class` annotation is more reliable than the initializer.
+                    let declared = declared_type(annotations, &self.project_context);
+                    if let Some(declared) = &declared {
+                        let value_span = match value.as_deref() {
+                            Some(CodeASTNode::ReturnStatement(values)) if !values.is_empty() => {
+                                values[0].span()
+                            }
+                            _ => name_span.clone(),
+                        };
+                        self.check_assignment(&inferred, declared, &value_span);
+                    }
+                    let type_info = declared.clone().unwrap_or(inferred);
+                    self.record_name(name, type_info.clone(), name_span);
+                    self.check_shadowing(name, name_span, false);
+                    self.current_scope
+                        .declare_local(name, type_info, span.clone());
+                    if let Some(declared) = declared {
+                        self.current_scope
+                            .declared_types
+                            .insert(name.clone(), declared);
+                    }
+                    if let Some(CodeASTNode::ReturnStatement(values)) = value.as_deref() {
+                        if let Some(Expression::TableConstructor { fields, items, .. }) =
+                            values.first()
+                        {
+                            let fields = self.infer_table_fields(fields, items);
+                            self.current_scope.table_fields.insert(name.clone(), fields);
+                        }
+                    }
+                }
+                CodeASTNode::ReturnStatement(exprs) => {
+                    for expr in exprs {
+                        self.check_expression(expr);
+                    }
+                }
+                CodeASTNode::Assignment { lhs, rhs, .. } => {
+                    for expr in rhs {
+                        self.check_expression(expr);
+                    }
+                    for (index, target) in lhs.iter().enumerate() {
+                        let value_type = rhs
+                            .get(index)
+                            .map_or(TypeInfo::Unknown, |value| self.infer_expression_type(value));
+                        if let Some(value) = rhs.get(index) {
+                            if let Some(declared) = self.declared_target_type(target) {
+                                self.check_assignment(&value_type, &declared, &value.span());
+                            }
+                        }
+                        match target {
+                            Expression::Identifier(name, _) => match name.rsplit_once('.') {
+                                Some((base, field)) => {
+                                    // Assigning to a field reads the table it belongs to.
+                                    self.current_scope.mark_read(root_name(name));
+                                    self.record_field_write(base, field, value_type);
+                                }
+                                None => self.define_global(name),
+                            },
+                            Expression::TableFieldAccess { table, key, .. } => {
+                                self.check_expression(target);
+                                if let (Expression::Identifier(base, _), FieldKey::Index(index)) =
+                                    (&**table, key)
+                                {
+                                    if let Expression::Literal(text, _) = &**index {
+                                        if text.parse::<usize>().is_ok() {
+                                            let field = format!("[{}]", text);
+                                            self.record_field_write(base, &field, value_type);
+                                        }
+                                    }
+                                }
+                            }
+                            _ => self.check_expression(target),
+                        }
+                    }
+                }
+                CodeASTNode::TableConstructor(fields) => {
+                    for (_, expr) in fields {
+                        self.check_expression(expr);
+                    }
+                }
+                CodeASTNode::IfStatement {
+                    condition,
+                    then_block,
+                    else_block,
+                    ..
+                } => {
+                    self.check_expression(condition);
+                    self.analyze_block(&[], then_block);
+                    if let Some(else_block) = else_block {
+                        self.analyze_block(&[], else_block);
+                    }
+                }
+                CodeASTNode::WhileLoop {
+                    condition, body, ..
+                } => {
+                    self.check_expression(condition);
+                    self.analyze_block(&[], body);
+                }
+                CodeASTNode::ForNumeric {
+                    var,
+                    start,
+                    end,
+                    step,
+                    body,
+                    ..
+                } => {
+                    self.check_expression(start);
+                    self.check_expression(end);
+                    if let Some(step) = step {
+                        self.check_expression(step);
+                    }
+                    self.analyze_block(&[(var.clone(), TypeInfo::Number)], body);
+                }
+                CodeASTNode::ForGeneric { vars, exprs, body, .. } => {
+                    for expr in exprs {
+                        self.check_expression(expr);
+                    }
+                    let bindings: Vec<(String, TypeInfo)> = vars
+                        .iter()
+                        .map(|var| (var.clone(), TypeInfo::Unknown))
+                        .collect();
+                    self.analyze_block(&bindings, body);
+                }
+                CodeASTNode::DoBlock { body, .. } => {
+                    self.analyze_block(&[], body);
+                }
+                CodeASTNode::RepeatUntil {
+                    body, condition, ..
+                } => {
+                    // The condition can see locals declared in the loop body.
+                    self.enter_scope(&[]);
+                    self.analyze_nodes(body);
+                    self.check_expression(condition);
+                    self.exit_scope();
+                }
+                CodeASTNode::FunctionCallStmt { call, .. } => {
+                    self.check_expression(call);
+                }
+                CodeASTNode::Comment(_)
+                | CodeASTNode::Break
+                | CodeASTNode::Goto(_)
+                | CodeASTNode::Label(_) => {}
+            }
+        }
+    }
+
+    fn analyze_function(
+        &mut self,
+        params: &[(String, TypeInfo)],
+        param_spans: &[Span],
+        body: &[CodeASTNode],
+    ) {
+        for ((name, type_info), span) in params.iter().zip(param_spans) {
+            self.record_name(name, type_info.clone(), span);
+            // The parameters' scope is not entered yet, so the current one encloses them.
+            self.check_shadowing(name, span, true);
+        }
+        self.function_depth += 1;
+        self.analyze_block(params, body);
+        self.function_depth -= 1;
+    }
+
+    /// The annotated type of an assignment target: a local declared with `---@type`, or a
+    /// field declared on the class of the table it belongs to.
+    fn declared_target_type(&self, target: &Expression) -> Option<TypeInfo> {
+        let (base, field) = match target {
+            Expression::Identifier(name, _) => match name.rsplit_once('.') {
+                Some((base, field)) => (self.name_type(base), field),
+                None => return self.current_scope.lookup_declared(name).cloned(),
+            },
+            Expression::TableFieldAccess {
+                table,
+                key: FieldKey::Name(field),
+                ..
+            } => (self.infer_expression_type(table), field.as_str()),
+            _ => return None,
+        };
+        match self.field_type(&base, field) {
+            TypeInfo::Unknown => None,
+            declared => Some(declared),
+        }
+    }
+
+    /// Warn when a value of type `value` is assigned where `declared` is expected and the
+    /// types are known to be incompatible.
+    fn check_assignment(&mut self, value: &TypeInfo, declared: &TypeInfo, span: &Span) {
+        if value.is_assignable_to(declared, &self.project_context) {
+            return;
+        }
+        self.diagnostics.push(Diagnostic::warning(
+            "assign-type-mismatch",
+            format!("cannot assign `{}` to `{}`", value, declared),
+            span.clone(),
+        ));
+    }
+
+    /// With `report_shadowing`, warn if `name` already resolves in a scope enclosing the one
+    /// it is being declared in; `from_current` includes the current scope in the search.
+    fn check_shadowing(&mut self, name: &str, span: &Span, from_current: bool) {
+        if !self.report_shadowing || name == "self" || name.starts_with('_') {
+            return;
+        }
+        let enclosing = if from_current {
+            Some(&self.current_scope)
+        } else {
+            self.current_scope.parent.as_deref()
+        };
+        if enclosing.is_some_and(|scope| scope.lookup(name).is_some()) {
+            self.diagnostics.push(Diagnostic::warning(
+                "shadowed-local",
+                format!("local `{}` shadows a binding from an enclosing scope", name),
+                span.clone(),
+            ));
+        }
+    }
+
+    /// Record an assignment to a bare name that is not a visible local, which creates a global.
+    fn define_global(&mut self, name: &str) {
+        if self.current_scope.lookup(name).is_none() {
+            self.defined_globals.insert(name.to_string());
+        }
+    }
+
+    /// Analyze a block in a new scope holding the given bindings.
+    fn analyze_block(&mut self, bindings: &[(String, TypeInfo)], body: &[CodeASTNode]) {
+        self.enter_scope(bindings);
+        self.analyze_nodes(body);
+        self.exit_scope();
+    }
+
+    /// Push a child scope holding the given bindings.
+    fn enter_scope(&mut self, bindings: &[(String, TypeInfo)]) {
+        let mut scope = ScopeContext::new();
+        for (name, type_info) in bindings {
+            scope.variables.insert(name.clone(), type_info.clone());
+        }
+        let parent = std::mem::replace(&mut self.current_scope, scope);
+        self.current_scope.parent = Some(Box::new(parent));
+    }
+
+    /// Pop the current scope, reporting any locals it declared but never read.
+    fn exit_scope(&mut self) {
+        let parent = self
+            .current_scope
+            .parent
+            .take()
+            .map(|parent| *parent)
+            .unwrap_or_default();
+        let scope = std::mem::replace(&mut self.current_scope, parent);
+        self.report_unused_locals(&scope);
+    }
+
+    fn report_unused_locals(&mut self, scope: &ScopeContext) {
+        for local in scope.unused_locals() {
+            self.diagnostics.push(Diagnostic::warning(
+                "unused-local",
+                format!("unused local `{}`", local.name),
+                local.span.clone(),
+            ));
+        }
+    }
+
+    /// Report references to names that are not defined anywhere.
+    fn check_expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Identifier(name, span) => self.check_identifier(name, span),
+            Expression::Literal(..) => {}
+            Expression::FunctionCall {
+                callee,
+                callee_span,
+                args,
+                skipped_args,
+                span,
+            } => {
+                if !self.current_scope.mark_read(root_name(callee)) {
+                    self.check_removed_function(callee, callee_span);
+                }
+                if !skipped_args {
+                    self.check_argument_count(callee, args, span);
+                }
+                if let Some(path) = parser_helpers::require_path(expr) {
+                    self.dependencies.push(DependencyInfo {
+                        required_path: path.to_string(),
+                        local_alias: None,
+                        resolved_path: self.project_context.resolve_require_path(path),
+                    });
+                }
+                for arg in args {
+                    self.check_expression(arg);
+                }
+            }
+            Expression::MethodCall { receiver, args, .. } => {
+                self.check_expression(receiver);
+                for arg in args {
+                    self.check_expression(arg);
+                }
+            }
+            Expression::BinaryOp { lhs, rhs, .. } => {
+                self.check_expression(lhs);
+                self.check_expression(rhs);
+            }
+            Expression::UnaryOp { operand, .. } => self.check_expression(operand),
+            Expression::TableConstructor { fields, items, .. } => {
+                for expr in fields.iter().map(|(_, value)| value).chain(items) {
+                    self.check_expression(expr);
+                }
+            }
+            Expression::TableFieldAccess { table, key, .. } => {
+                if let (Expression::Identifier(name, span), FieldKey::Name(field)) = (&**table, key)
+                {
+                    if self.current_scope.lookup(name).is_none() {
+                        self.check_removed_function(&format!("{}.{}", name, field), span);
+                    }
+                }
+                self.check_expression(table);
+                if let FieldKey::Index(index) = key {
+                    self.check_expression(index);
+                }
+            }
+            Expression::Function {
+                params,
+                param_spans,
+                body,
+                ..
+            } => self.analyze_function(params, param_spans, body),
+        }
+    }
+
+    /// Report a call that passes fewer arguments than the callee's required parameters, or
+    /// more than its parameters when it takes no varargs. Library signatures don't list
+    /// their parameters, so functions without any are not checked.
+    fn check_argument_count(&mut self, callee: &str, args: &[Expression], span: &Span) {
+        let Some(signature) = self.call_signature(callee) else {
+            return;
+        };
+        let parameters = &signature.parameters;
+        if parameters.is_empty() {
+            return;
+        }
+        let vararg = parameters.iter().any(|param| param.name == "...");
+        let fixed = parameters
+            .iter()
+            .filter(|param| param.name != "...")
+            .count();
+        let required = parameters
+            .iter()
+            .rposition(|param| !param.optional && param.name != "...")
+            .map_or(0, |index| index + 1);
+        // A call or method call in last place passes all of its results, if any.
+        let open_ended = matches!(
+            args.last(),
+            Some(Expression::FunctionCall { .. } | Expression::MethodCall { .. })
+        );
+        let given = args.len() - usize::from(open_ended);
+        let expected = |bound: &str, count: usize| {
+            let bound = if required == fixed && !vararg {
+                ""
+            } else {
+                bound
+            };
+            let plural = if count == 1 { "" } else { "s" };
+            format!(
+                "`{}` expects {}{} argument{} but is given {}",
+                callee, bound, count, plural, given
+            )
+        };
+        let diagnostic = if given < required && !open_ended {
+            Diagnostic::warning(
+                "missing-parameter",
+                expected("at least ", required),
+                span.clone(),
+            )
+        } else if given > fixed && !vararg {
+            Diagnostic::warning(
+                "redundant-parameter",
+                expected("at most ", fixed),
+                args[fixed].span(),
+            )
+        } else {
+            return;
+        };
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn check_identifier(&mut self, name: &str, span: &Span) {
+        let root = root_name(name);
+        let type_info = self.name_type(name);
+        self.record_name(name, type_info, span);
+        if self.current_scope.mark_read(root) || self.is_known_global(root) {
+            return;
+        }
+        if self.check_removed_function(name, span) {
+            return;
+        }
+        self.diagnostics.push(Diagnostic::warning(
+            "undefined-global",
+            format!("undefined global `{}`", root),
+            span.clone(),
+        ));
+    }
+
+    /// Report a use of a standard library function the target Lua version no longer
+    /// provides, returning whether one was reported.
+    fn check_removed_function(&mut self, name: &str, span: &Span) -> bool {
+        let version = self.project_context.lua_version;
+        let Some(replacement) = version.removed_function_replacement(name) else {
+            return false;
+        };
+        self.diagnostics.push(Diagnostic::warning(
+            "deprecated",
+            format!(
+                "`{}` is not available in Lua {}; use {} instead",
+                name,
+                version.as_str(),
+                replacement
+            ),
+            span.clone(),
+        ));
+        true
+    }
+
+    /// Report `---@param` annotations naming no parameter, and, on functions that
+    /// document their parameters, parameters left without one.
+    fn check_param_annotations(
+        &mut self,
+        params: &[(String, TypeInfo)],
+        param_spans: &[Span],
+        annotations: &[AnnotationASTNode],
+        annotation_spans: &[Span],
+    ) {
+        let mut documented = HashSet::new();
+        for (annotation, span) in annotations.iter().zip(annotation_spans) {
+            if let AnnotationASTNode::Param { name, .. } = annotation {
+                documented.insert(name.as_str());
+                if !params.iter().any(|(param, _)| param == name) {
+                    self.diagnostics.push(Diagnostic::warning(
+                        "undefined-doc-param",
+                        format!("`---@param {}` does not match any parameter", name),
+                        span.clone(),
+                    ));
+                }
+            }
+        }
+        if documented.is_empty() {
+            return;
+        }
+        for ((param, _), span) in params.iter().zip(param_spans) {
+            if param != "self" && !documented.contains(param.as_str()) {
+                self.diagnostics.push(Diagnostic::warning(
+                    "missing-param-doc",
+                    format!("parameter `{}` has no `---@param` annotation", param),
+                    span.clone(),
+                ));
             }
         }
     }
 
-    fn analyze_function(&mut self, params: &[(String, TypeInfo)], body: &[CodeASTNode]) {
-        let mut fn_scope = ScopeContext::new();
-        fn_scope.parent = Some(Box::new(self.current_scope.clone()));
-        for (name, type_info) in params {
-            fn_scope.variables.insert(name.clone(), type_info.clone());
+    fn record_name(&mut self, name: &str, type_info: TypeInfo, span: &Span) {
+        self.typed_names.push(TypedName {
+            name: name.to_string(),
+            type_info,
+            span: span.clone(),
+        });
+    }
+
+    /// Type of a local's initializer, which the parser wraps in a single-expression return.
+    fn infer_initializer_type(&self, value: &CodeASTNode) -> TypeInfo {
+        match value {
+            CodeASTNode::ReturnStatement(exprs) => exprs
+                .first()
+                .map(|expr| self.infer_expression_type(expr))
+                .unwrap_or(TypeInfo::Unknown),
+            CodeASTNode::TableConstructor(_) => TypeInfo::Table,
+            CodeASTNode::FunctionDef { .. } => TypeInfo::Function,
+            _ => TypeInfo::Unknown,
         }
-        let previous_scope = std::mem::replace(&mut self.current_scope, fn_scope);
-        self.analyze(body);
-        self.current_scope = previous_scope;
+    }
+
+    /// Whether a name is a global from the source, the standard library or a loaded framework.
+    ///
+    /// Top-level code runs in order, so there a source global only counts once it has been
+    /// assigned; function bodies may run later and see every global in the file.
+    fn is_known_global(&self, name: &str) -> bool {
+        let context = &self.project_context;
+        let source_globals = if self.function_depth == 0 {
+            &self.defined_globals
+        } else {
+            &self.globals
+        };
+        source_globals.contains(name)
+            || context.framework_globals.contains(name)
+            || context.modules.contains_key(name)
+            || context
+                .modules
+                .get("_G")
+                .is_some_and(|globals| globals.exports.contains_key(name))
+            || context
+                .type_registry
+                .function_signatures
+                .keys()
+                .any(|signature| root_name(signature) == name)
     }
 
     fn analyze_module(&mut self, module_name: &str, exports: &[ExportItem]) {
@@ -125,23 +848,480 @@ impl TypeAnalyzer {
         collected_types
     }
 
+    /// The type of a plain or dotted name, following class fields for each component.
+    fn name_type(&self, name: &str) -> TypeInfo {
+        if let Some(type_info) = self.current_scope.lookup(name) {
+            return type_info;
+        }
+        if let Some(type_info) = name
+            .split_once('.')
+            .and_then(|(alias, member)| self.module_member_type(alias, member))
+        {
+            return type_info;
+        }
+        match name.rsplit_once('.') {
+            Some((base, field)) => self
+                .table_field_type(base, field)
+                .unwrap_or_else(|| self.field_type(&self.name_type(base), field)),
+            None => TypeInfo::Unknown,
+        }
+    }
+
+    /// Update the shape of the table bound to `base` for an assignment to one of its
+    /// fields. A field assigned values of different types becomes `Unknown`.
+    fn record_field_write(&mut self, base: &str, field: &str, type_info: TypeInfo) {
+        let Some(fields) = self.current_scope.lookup_fields_mut(base) else {
+            return;
+        };
+        match fields.iter_mut().find(|info| info.name == field) {
+            Some(info) if info.type_info == TypeInfo::Unknown => info.type_info = type_info,
+            Some(info) if type_info != TypeInfo::Unknown && info.type_info != type_info => {
+                info.type_info = TypeInfo::Unknown
+            }
+            Some(_) => {}
+            None => fields.push(FieldInfo {
+                name: field.to_string(),
+                type_info,
+            }),
+        }
+    }
+
+    /// The type of a field of the table bound to `name`, as inferred from its constructor.
+    fn table_field_type(&self, name: &str, field: &str) -> Option<TypeInfo> {
+        self.current_scope
+            .lookup_fields(name)?
+            .iter()
+            .find(|info| info.name == field && info.type_info != TypeInfo::Unknown)
+            .map(|info| info.type_info.clone())
+    }
+
+    /// The shape of a table constructor: each named entry with the inferred type of its
+    /// value, followed by the positional entries as `[1]`, `[2]`, ...
+    pub fn infer_table_fields(
+        &self,
+        fields: &[(String, Expression)],
+        items: &[Expression],
+    ) -> Vec<FieldInfo> {
+        let named = fields
+            .iter()
+            .map(|(name, value)| (name.clone(), self.infer_expression_type(value)));
+        table_fields(
+            named,
+            items.iter().map(|item| self.infer_expression_type(item)),
+        )
+    }
+
+    /// The module a local was assigned from with `local alias = require("module")`.
+    fn required_module(&self, alias: &str) -> Option<&str> {
+        self.dependencies
+            .iter()
+            .rev()
+            .find(|dependency| dependency.local_alias.as_deref() == Some(alias))
+            .map(|dependency| dependency.required_path.as_str())
+    }
+
+    /// The type of an export of a required module, reached through its local alias.
+    fn module_member_type(&self, alias: &str, member: &str) -> Option<TypeInfo> {
+        let module = self
+            .project_context
+            .modules
+            .get(self.required_module(alias)?)?;
+        module
+            .exports
+            .get(member)
+            .map(|export| export.type_info.clone())
+    }
+
+    /// Record the signature of a function defined in the source, so that calls to it can be
+    /// checked: in the scope of the local it is stored in, or among the registered
+    /// signatures for a global. Methods are registered with their class already.
+    fn define_function(
+        &mut self,
+        name: &str,
+        params: &[(String, TypeInfo)],
+        doc: &Option<String>,
+        annotations: &[AnnotationASTNode],
+    ) {
+        let signature = self
+            .project_context
+            .function_signature(name, params, doc, annotations);
+        if signature.is_method {
+            return;
+        }
+        if self.current_scope.lookup(root_name(name)).is_some() {
+            self.current_scope.define_function(signature);
+        } else {
+            self.project_context
+                .type_registry
+                .function_signatures
+                .insert(name.to_string(), signature);
+        }
+    }
+
+    /// The signature of a called function; `alias.f` finds `f` of the module that
+    /// `alias` was required from.
+    fn call_signature(&self, callee: &str) -> Option<&project_context::FunctionSignature> {
+        if let Some(signature) = self.current_scope.lookup_function(callee) {
+            return Some(signature);
+        }
+        let signatures = &self.project_context.type_registry.function_signatures;
+        signatures.get(callee).or_else(|| {
+            let (alias, member) = callee.split_once('.')?;
+            signatures.get(&format!("{}.{}", self.required_module(alias)?, member))
+        })
+    }
+
+    /// The declared type of a field (or method) of a class or one of its parents, or the
+    /// value type of a table with string keys.
+    fn field_type(&self, base: &TypeInfo, field: &str) -> TypeInfo {
+        let class = match base {
+            TypeInfo::Named(class) => class,
+            TypeInfo::Dict(key, value) if **key == TypeInfo::String => return *value.clone(),
+            _ => return TypeInfo::Unknown,
+        };
+        let registry = &self.project_context.type_registry;
+        registry
+            .find_field(class, field)
+            .map(|f| f.type_info.clone())
+            .or_else(|| {
+                registry
+                    .find_method(class, field)
+                    .map(|_| TypeInfo::Function)
+            })
+            .unwrap_or(TypeInfo::Unknown)
+    }
+
+    /// The first declared return type of a class's method, inherited methods included.
+    fn method_return_type(&self, receiver: &TypeInfo, method: &str) -> TypeInfo {
+        let TypeInfo::Named(class) = receiver else {
+            return TypeInfo::Unknown;
+        };
+        self.project_context
+            .type_registry
+            .find_method(class, method)
+            .and_then(|signature| signature.return_types.first().cloned())
+            .unwrap_or(TypeInfo::Unknown)
+    }
+
+    /// The type of `self` in a method of `owner` (`A` in `function A:b()`): the class the
+    /// owner was declared with, else a class of the same name.
+    fn method_self_type(&self, owner: &str) -> TypeInfo {
+        match self.name_type(owner) {
+            TypeInfo::Named(class) => TypeInfo::Named(class),
+            _ => self
+                .project_context
+                .resolve_type(owner)
+                .unwrap_or(TypeInfo::Unknown),
+        }
+    }
+
     pub fn infer_expression_type(&self, expr: &Expression) -> TypeInfo {
         match expr {
-            Expression::Identifier(id) => {
-                self.current_scope.lookup(id).unwrap_or(TypeInfo::Unknown)
+            Expression::Identifier(id, _) => self.name_type(id),
+            Expression::Literal(text, _) => parser_helpers::literal_type(text),
+            // A call has the first return type of the function's signature, when known.
+            Expression::FunctionCall { callee, .. } => self
+                .call_signature(callee)
+                .and_then(|signature| signature.return_types.first().cloned())
+                .unwrap_or(TypeInfo::Unknown),
+            Expression::BinaryOp { op, lhs, rhs } => match op.as_str() {
+                // `a and b` and `a or b` evaluate to one of their operands.
+                "and" | "or" => {
+                    let lhs = self.infer_expression_type(lhs);
+                    if lhs == self.infer_expression_type(rhs) {
+                        lhs
+                    } else {
+                        TypeInfo::Unknown
+                    }
+                }
+                ".." => TypeInfo::String,
+                "<" | ">" | "<=" | ">=" | "~=" | "==" => TypeInfo::Boolean,
+                _ => TypeInfo::Number,
+            },
+            Expression::UnaryOp { op, .. } => match op.as_str() {
+                "not" => TypeInfo::Boolean,
+                // Lengths are integers from 5.3, which tells them apart from floats
+                "#" if matches!(
+                    self.project_context.lua_version,
+                    LuaVersion::Lua53 | LuaVersion::Lua54
+                ) => TypeInfo::Integer,
+                _ => TypeInfo::Number,
+            },
+            Expression::TableConstructor { fields, items, .. } => {
+                let exports: Vec<ExportItem> = fields
+                    .iter()
+                    .map(|(name, value)| ExportItem {
+                        name: name.clone(),
+                        type_info: self.infer_expression_type(value),
+                    })
+                    .collect();
+                let items: Vec<TypeInfo> = items
+                    .iter()
+                    .map(|item| self.infer_expression_type(item))
+                    .collect();
+                TypeInfo::table_of(&exports, &items)
             }
-            Expression::Literal(lit) => {
-                // For simplicity, treat all literals as strings.
-                TypeInfo::String
+            Expression::MethodCall {
+                receiver, method, ..
+            } => self.method_return_type(&self.infer_expression_type(receiver), method),
+            Expression::TableFieldAccess { table, key, .. } => {
+                let table_type = self.infer_expression_type(table);
+                match (key, table_type) {
+                    (FieldKey::Name(name), table_type) => match &**table {
+                        Expression::Identifier(base, _) => self
+                            .module_member_type(base, name)
+                            .or_else(|| self.table_field_type(base, name))
+                            .unwrap_or_else(|| self.field_type(&table_type, name)),
+                        _ => self.field_type(&table_type, name),
+                    },
+                    (FieldKey::Index(_), TypeInfo::Array(element)) => *element,
+                    (FieldKey::Index(_), TypeInfo::Dict(_, value)) => *value,
+                    (FieldKey::Index(_), _) => TypeInfo::Unknown,
+                }
             }
-            Expression::FunctionCall { callee: _, args } => {
-                // Infer function call type based on its arguments.
-                let _arg_types = args
-                    .iter()
-                    .map(|a| self.infer_expression_type(a))
-                    .collect::<Vec<_>>();
-                TypeInfo::Function
+            Expression::Function { .. } => TypeInfo::Function,
+        }
+    }
+}
+
+/// Field infos for the named entries and then the positional ones (`[1]`, `[2]`, ...).
+fn table_fields(
+    named: impl Iterator<Item = (String, TypeInfo)>,
+    items: impl Iterator<Item = TypeInfo>,
+) -> Vec<FieldInfo> {
+    let positional = items
+        .enumerate()
+        .map(|(index, type_info)| (format!("[{}]", index + 1), type_info));
+    named
+        .chain(positional)
+        .map(|(name, type_info)| FieldInfo { name, type_info })
+        .collect()
+}
+
+/// The first segment of a dotted or method name (`a` for `a.b:c`).
+fn root_name(name: &str) -> &str {
+    name.split(['.', ':']).next().unwrap_or(name)
+}
+
+/// Parameters with their types taken from matching `---@param` annotations.
+fn annotated_params(
+    params: &[(String, TypeInfo)],
+    annotations: &[AnnotationASTNode],
+    context: &ProjectContext,
+) -> Vec<(String, TypeInfo)> {
+    params
+        .iter()
+        .map(|(name, type_info)| {
+            let annotated = annotations.iter().find_map(|annotation| match annotation {
+                AnnotationASTNode::Param {
+                    name: param_name,
+                    lua_type: Some(lua_type),
+                    ..
+                } if param_name == name => Some(context.lua_type_info(lua_type)),
+                _ => None,
+            });
+            (name.clone(), annotated.unwrap_or_else(|| type_info.clone()))
+        })
+        .collect()
+}
+
+/// The type given by a `---@type` annotation, or the class a `---@class` annotation declares.
+fn declared_type(annotations: &[AnnotationASTNode], context: &ProjectContext) -> Option<TypeInfo> {
+    annotations.iter().find_map(|annotation| match annotation {
+        AnnotationASTNode::Type { type_field } => {
+            LuaType::parse(type_field).map(|lua_type| context.lua_type_info(&lua_type))
+        }
+        AnnotationASTNode::Class { name, .. } => Some(TypeInfo::Named(name.clone())),
+        _ => None,
+    })
+}
+
+/// Collect names assigned as globals (assignments and non-local function definitions).
+fn collect_globals(ast: &[CodeASTNode], globals: &mut HashSet<String>) {
+    struct GlobalCollector<'a>(&'a mut HashSet<String>);
+
+    impl Visitor for GlobalCollector<'_> {
+        fn visit_function_def(&mut self, node: &CodeASTNode) {
+            if let CodeASTNode::FunctionDef {
+                name,
+                is_local: false,
+                ..
+            } = node
+            {
+                self.0.insert(root_name(name).to_string());
             }
         }
+
+        fn visit_assignment(&mut self, node: &CodeASTNode) {
+            if let CodeASTNode::Assignment { lhs, .. } = node {
+                for target in lhs {
+                    if let Expression::Identifier(name, _) = target {
+                        self.0.insert(root_name(name).to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    visitor::walk(ast, &mut GlobalCollector(globals));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::code_parser::CodeParser;
+    use crate::tokenizer::CodeTokenizer;
+
+    /// The types `source` returns, analyzed for the given Lua version
+    fn return_types(source: &str, version: LuaVersion) -> Vec<TypeInfo> {
+        let ast = CodeParser::new(CodeTokenizer::new(source).tokenize()).parse();
+        let mut analyzer = TypeAnalyzer::new(ProjectContext::new_with_version(version));
+        analyzer.analyze(&ast);
+        analyzer.infer_return_types(&ast)
+    }
+
+    /// Codes of the diagnostics reported for `source`
+    fn diagnostic_codes(source: &str) -> Vec<String> {
+        versioned_diagnostic_codes(source, LuaVersion::Lua54)
+    }
+
+    /// Codes of the diagnostics reported for `source`, analyzed for the given Lua version
+    fn versioned_diagnostic_codes(source: &str, version: LuaVersion) -> Vec<String> {
+        let ast = CodeParser::new(CodeTokenizer::new(source).tokenize()).parse();
+        let mut analyzer = TypeAnalyzer::new(ProjectContext::new_with_version(version));
+        analyzer.analyze(&ast);
+        analyzer.diagnostics.into_iter().map(|diagnostic| diagnostic.code).collect()
+    }
+
+    #[test]
+    fn assignments_are_checked_against_declared_types() {
+        let mismatch = "assign-type-mismatch".to_string();
+        assert!(diagnostic_codes("---@type number\nlocal x = \"a\"\nprint(x)").contains(&mismatch));
+        assert!(!diagnostic_codes("---@type number\nlocal x = 1\nprint(x)").contains(&mismatch));
+        assert!(!diagnostic_codes("---@type number?\nlocal x = 1\nprint(x)").contains(&mismatch));
+    }
+
+    #[test]
+    fn undefined_names_are_reported_at_their_token() {
+        let source = "return undefined_thing";
+        let analysis = crate::analysis::analyze_str(source);
+        assert_eq!(analysis.diagnostics.len(), 1);
+        let diagnostic = &analysis.diagnostics[0];
+        assert!(diagnostic.message.contains("undefined"));
+        assert_eq!((diagnostic.span.line, diagnostic.span.column), (1, 8));
+        assert_eq!(&source[diagnostic.span.start..diagnostic.span.end], "undefined_thing");
+    }
+
+    #[test]
+    fn unused_locals() {
+        let unused = "unused-local".to_string();
+        assert_eq!(diagnostic_codes("local x = 1"), vec![unused.clone()]);
+        assert!(!diagnostic_codes("local x = 1\nprint(x)").contains(&unused));
+        assert!(!diagnostic_codes("local _x = 1").contains(&unused));
+        assert_eq!(diagnostic_codes("local function f()\n    local y = 2\nend\nf()"), vec![unused]);
+    }
+
+    #[test]
+    fn undefined_globals() {
+        let undefined = "undefined-global".to_string();
+        assert!(!diagnostic_codes("local y = 1; print(y)").contains(&undefined));
+        assert_eq!(diagnostic_codes("print(z)"), vec![undefined.clone()]);
+        assert!(!diagnostic_codes("z = 1; print(z)").contains(&undefined));
+        let loop_names = "local t = {}\nfor k, v in pairs(t) do print(k, v) end";
+        assert!(!diagnostic_codes(loop_names).contains(&undefined));
+    }
+
+    #[test]
+    fn methods_bind_self_to_their_class() {
+        let source = "---@class Counter\n---@field count integer\nlocal Counter = {}\n\n\
+                      function Counter:get()\n    return self.count\nend\n";
+        let analysis = crate::analysis::analyze_str(source);
+        let self_type = analysis.type_at(6, 12).map(|(_, type_info)| type_info);
+        assert_eq!(self_type, Some(TypeInfo::Named("Counter".to_string())));
+    }
+
+    #[test]
+    fn unary_operators() {
+        let length = "local list = {}\nreturn #list";
+        assert_eq!(return_types(length, LuaVersion::Lua51), vec![TypeInfo::Number]);
+        assert_eq!(return_types(length, LuaVersion::Lua54), vec![TypeInfo::Integer]);
+        let negation = "local flag = true\nreturn not flag";
+        assert_eq!(return_types(negation, LuaVersion::Lua54), vec![TypeInfo::Boolean]);
+        let minus = "local n = 1\nreturn -n";
+        assert_eq!(return_types(minus, LuaVersion::Lua54), vec![TypeInfo::Number]);
+    }
+
+    #[test]
+    fn removed_functions_depend_on_the_version() {
+        let source = "local t = { 1, 2 }\nreturn unpack(t)";
+        let deprecated = "deprecated".to_string();
+        assert!(versioned_diagnostic_codes(source, LuaVersion::Lua53).contains(&deprecated));
+        assert!(!versioned_diagnostic_codes(source, LuaVersion::Lua51).contains(&deprecated));
+    }
+
+    #[test]
+    fn annotated_parameters_keep_their_type_in_the_body() {
+        let source = "---@param n number\nlocal function double(n)\n    return n * 2\nend\n\
+                      return double";
+        let analysis = crate::analysis::analyze_str(source);
+        let param_type = analysis.type_at(3, 12).map(|(_, type_info)| type_info);
+        assert_eq!(param_type, Some(TypeInfo::Number));
+    }
+
+    #[test]
+    fn param_annotations_match_the_signature() {
+        let misspelled = "---@param nmae string\n---@param age number\n\
+                          local function greet(name, age)\n    return name, age\nend\nreturn greet";
+        let analysis = crate::analysis::analyze_str(misspelled);
+        let stale: Vec<_> =
+            analysis.diagnostics.iter().filter(|d| d.code == "undefined-doc-param").collect();
+        assert_eq!(stale.len(), 1, "{:?}", analysis.diagnostics);
+        assert_eq!(stale[0].span.line, 1);
+
+        let missing = "---@param a number\nlocal function add(a, b)\n    return a + b\nend\n\
+                       return add";
+        assert_eq!(diagnostic_codes(missing), vec!["missing-param-doc".to_string()]);
+    }
+
+    #[test]
+    fn shadowing_is_reported_once_when_enabled() {
+        let source = "local x = 1\nlocal function f()\n    local x = 2\n    return x\nend\n\
+                      return f() + x";
+        let ast = CodeParser::new(CodeTokenizer::new(source).tokenize()).parse();
+        let mut analyzer = TypeAnalyzer::new(ProjectContext::new());
+        analyzer.report_shadowing = true;
+        analyzer.analyze(&ast);
+        let shadowed: Vec<_> =
+            analyzer.diagnostics.iter().filter(|d| d.code == "shadowed-local").collect();
+        assert_eq!(shadowed.len(), 1, "{:?}", analyzer.diagnostics);
+        assert_eq!(shadowed[0].span.line, 3);
+        assert!(!diagnostic_codes(source).contains(&"shadowed-local".to_string()));
+    }
+
+    #[test]
+    fn argument_counts() {
+        let define = "local function add(a, b)\n    return a + b\nend\n";
+        let codes = |call: &str| diagnostic_codes(&format!("{}return {}", define, call));
+        assert_eq!(codes("add(1)"), vec!["missing-parameter".to_string()]);
+        assert_eq!(codes("add(1, 2, 3)"), vec!["redundant-parameter".to_string()]);
+        assert!(codes("add(1, 2)").is_empty());
+    }
+
+    #[test]
+    fn required_module_functions_resolve_through_the_alias() {
+        let mut context = ProjectContext::new();
+        context.load_module(
+            "app.util",
+            "local M = {}\n\n---@return string\nfunction M.greet(name)\n    return name\nend\n\n\
+             return M\n",
+        );
+        let source = "local util = require(\"app.util\")\nreturn util.greet(\"x\")";
+        context.load_module("app.main", source);
+        context.build_dependency_graph();
+
+        let ast = CodeParser::new(CodeTokenizer::new(source).tokenize()).parse();
+        let mut analyzer = TypeAnalyzer::new(context);
+        analyzer.analyze(&ast);
+        assert_eq!(analyzer.infer_return_types(&ast), vec![TypeInfo::String]);
     }
 }
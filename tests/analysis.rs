@@ -0,0 +1,74 @@
+//! The library's analysis entry points, used the way an editor integration would.
+
+use std::env;
+use std::fs;
+use std::process::Command;
+
+use lua_tools::log::{self, Verbosity};
+use lua_tools::parser::ast::TypeInfo;
+use lua_tools::{analyze_file, analyze_str};
+
+#[test]
+fn analyzing_an_inline_string() {
+    let source = "local M = {}\n\nfunction M.greet(name)\n    return \"hi \" .. name\nend\n\n\
+                  return M\n";
+    let analysis = analyze_str(source);
+    assert!(analysis.diagnostics.is_empty(), "{:?}", analysis.diagnostics);
+    assert!(!analysis.tokens.is_empty());
+    assert_eq!(analysis.ast.len(), 3);
+    assert!(analysis.frameworks.is_empty());
+}
+
+#[test]
+fn disable_next_line_suppresses_exactly_one_warning() {
+    let source = "---@diagnostic disable-next-line: undefined-global\n\
+                  print(first.value)\n\
+                  print(second.value)\n";
+    let analysis = analyze_str(source);
+    let undefined: Vec<_> = analysis
+        .diagnostics
+        .iter()
+        .filter(|d| d.code == "undefined-global")
+        .collect();
+    assert_eq!(undefined.len(), 1, "{:?}", analysis.diagnostics);
+    assert_eq!(undefined[0].span.line, 3);
+}
+
+#[test]
+fn hovering_a_local_reports_its_type() {
+    let analysis = analyze_str("local n = 5\nreturn n");
+    let (name, type_info) = analysis.type_at(2, 8).expect("no name at the cursor");
+    assert_eq!(name, "n");
+    assert_eq!(type_info, TypeInfo::Number);
+}
+
+#[test]
+fn analysis_writes_nothing_to_stdout() {
+    // Stdout can't be captured in-process, so the test runs itself again in a child that
+    // analyzes a Neovim plugin between two markers; nothing may appear between them.
+    if env::var_os("LUA_TOOLS_STDOUT_CHILD").is_some() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("lua/plugin")).unwrap();
+        fs::write(dir.path().join("init.lua"), "vim.api.nvim_command(\"set nu\")\n").unwrap();
+        let file = dir.path().join("lua/plugin/core.lua");
+        fs::write(&file, "local M = {}\nfunction M.setup() vim.notify(\"hi\") end\nreturn M\n")
+            .unwrap();
+
+        log::set_verbosity(Verbosity::Verbose);
+        println!("<<begin>>");
+        analyze_file(&file).unwrap();
+        println!("<<end>>");
+        return;
+    }
+
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["analysis_writes_nothing_to_stdout", "--exact", "--nocapture"])
+        .env("LUA_TOOLS_STDOUT_CHILD", "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("<<begin>>\n<<end>>\n"), "{}", stdout);
+    // The messages still go somewhere, just not to stdout
+    assert!(!output.stderr.is_empty());
+}
@@ -0,0 +1,279 @@
+//! End-to-end tests for the `lua_commenter` binary.
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SOURCE: &str = "local M = {}\n\nfunction M.add(a, b)\n    return a + b\nend\n\nreturn M\n";
+
+/// Run `lua_commenter` in `dir` with the given arguments.
+fn run(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_lua_commenter"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .expect("Failed to run lua_commenter")
+}
+
+/// Run `lua_commenter` in `dir`, feeding `input` to its stdin.
+fn run_with_stdin(dir: &Path, args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lua_commenter"))
+        .current_dir(dir)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run lua_commenter");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// The names of the entries of `dir`, sorted.
+fn entries(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn stdin_is_annotated_to_stdout() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_with_stdin(dir.path(), &["-"], SOURCE);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("---@function M.add"), "{}", stdout);
+    assert!(!stdout.starts_with("-- "), "no path header without --stdin-path");
+    assert!(entries(dir.path()).is_empty());
+
+    let output = run_with_stdin(dir.path(), &["--stdin-path", "lib/m.lua", "-"], SOURCE);
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("-- lib/m.lua\n"));
+}
+
+#[test]
+fn check_passes_on_annotated_file() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("m.lua"), SOURCE).unwrap();
+    assert!(run(dir.path(), &["-o", "annotated_{}", "m.lua"]).status.success());
+    let annotated = fs::read_to_string(dir.path().join("annotated_m.lua")).unwrap();
+
+    let output = run(dir.path(), &["--check", "m.lua"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(fs::read_to_string(dir.path().join("annotated_m.lua")).unwrap(), annotated);
+}
+
+#[test]
+fn check_fails_on_unannotated_file() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("m.lua"), SOURCE).unwrap();
+
+    let output = run(dir.path(), &["--check", "m.lua"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Would annotate"));
+    assert!(!dir.path().join("annotated_m.lua").exists());
+    assert!(!dir.path().join(".lua_tools").exists());
+}
+
+#[test]
+fn second_run_hits_cache() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("m.lua"), SOURCE).unwrap();
+
+    let first = run(dir.path(), &["-v", "-o", "annotated_{}", "m.lua"]);
+    assert!(first.status.success());
+    assert!(!String::from_utf8_lossy(&first.stderr).contains("Cache hit"));
+
+    let second = run(dir.path(), &["-v", "-o", "annotated_{}", "m.lua"]);
+    assert!(second.status.success());
+    assert!(String::from_utf8_lossy(&second.stderr).contains("Cache hit"));
+}
+
+#[test]
+fn watch_reannotates_modified_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("m.lua");
+    let output = dir.path().join("annotated_m.lua");
+    fs::write(&source, SOURCE).unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lua_commenter"))
+        .current_dir(dir.path())
+        .args(["--watch", "m.lua"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to run lua_commenter");
+
+    // Poll for up to ten seconds rather than sleeping a fixed time. `touch` runs on every
+    // poll, as the watcher may not have taken its first look at the file yet.
+    let wait_for = |done: &dyn Fn(&str) -> bool, touch: &dyn Fn()| {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(10) {
+            touch();
+            if fs::read_to_string(&output).is_ok_and(|text| done(&text)) {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+        false
+    };
+    let first = wait_for(&|text| text.contains("M.add"), &|| {});
+    let modified = SOURCE.replace("return M", "function M.sub(a, b)\nend\n\nreturn M");
+    let second = wait_for(&|text| text.contains("M.sub"), &|| fs::write(&source, &modified).unwrap());
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert!(first, "the initial pass annotates the file");
+    assert!(second, "modifying the file annotates it again");
+}
+
+#[test]
+fn summary_reports_counts_on_stderr() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("a.lua"), SOURCE).unwrap();
+    fs::write(src.join("b.lua"), SOURCE).unwrap();
+
+    let output = run(dir.path(), &["-o", "annotated_{}", "src"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("2 files scanned, 2 modified, 10 annotations added, 0 diagnostics in"),
+        "{}",
+        stderr
+    );
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("files scanned"));
+}
+
+#[test]
+fn emit_tokens_prints_tokens_and_writes_nothing() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("m.lua"), SOURCE).unwrap();
+
+    let output = run(dir.path(), &["--emit-tokens", "-o", "annotated_{}", "m.lua"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.lines().any(|line| line.trim() == "Keyword(local)"), "{}", stdout);
+    assert!(stdout.lines().any(|line| line.trim() == "Identifier(M)"), "{}", stdout);
+    assert_eq!(entries(dir.path()), vec!["m.lua"]);
+}
+
+#[test]
+fn emit_ast_prints_the_ast_only_when_asked() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("m.lua"), SOURCE).unwrap();
+
+    let output = run(dir.path(), &["--emit-ast", "m.lua"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("FunctionDef: M.add"));
+
+    let output = run(dir.path(), &["-v", "m.lua"]);
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("FunctionDef"));
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("FunctionDef"));
+}
+
+#[test]
+fn ignore_patterns_are_relative_to_the_walked_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    fs::create_dir_all(src.join("gen")).unwrap();
+    fs::write(src.join("main.lua"), SOURCE).unwrap();
+    fs::write(src.join("gen/parser.lua"), SOURCE).unwrap();
+
+    let output = run(dir.path(), &["-r", "--ignore", "gen/*.lua", "src"]);
+    assert!(output.status.success());
+    assert_eq!(entries(&src), ["annotated_main.lua", "gen", "main.lua"]);
+    assert_eq!(entries(&src.join("gen")), ["parser.lua"]);
+}
+
+#[test]
+fn parallel_run_processes_every_file_and_reports_failures() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    let names = ["a", "b", "c", "d", "e", "f"];
+    for name in names {
+        fs::write(src.join(format!("{}.lua", name)), SOURCE).unwrap();
+    }
+    // Not UTF-8, so it cannot be read as source.
+    fs::write(src.join("broken.lua"), [0xff, 0xfe]).unwrap();
+
+    let output = run(dir.path(), &["-j", "4", "-o", "annotated_{}", "src"]);
+    assert_eq!(output.status.code(), Some(1));
+    for name in names {
+        assert!(src.join(format!("annotated_{}.lua", name)).exists(), "{}", name);
+    }
+    assert!(!src.join("annotated_broken.lua").exists());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to read"), "{}", stderr);
+    assert!(stderr.contains("7 files scanned, 6 modified"), "{}", stderr);
+    assert!(stderr.contains("1 failed"), "{}", stderr);
+}
+
+#[test]
+fn forced_framework_is_applied_without_detection() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("config.lua"), SOURCE).unwrap();
+
+    let output = run(dir.path(), &["-v", "--no-cache", "-o", "annotated_{}", "config.lua"]);
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("wezterm"));
+
+    let output = run(
+        dir.path(),
+        &["-v", "--no-cache", "--framework", "wezterm:20240222", "-o", "annotated_{}", "config.lua"],
+    );
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Using framework wezterm 20240222"), "{}", stderr);
+}
+
+#[test]
+fn lua_version_flag_selects_the_standard_library() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("m.lua"), "local band = bit32.band\nreturn band\n").unwrap();
+
+    let diagnostics = |version: &str| {
+        let args = ["--no-cache", "--lua-version", version, "-o", "annotated_{}", "m.lua"];
+        let output = run(dir.path(), &args);
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    };
+    // bit32 arrived in 5.2, so under 5.1 it is an undefined global
+    assert!(diagnostics("5.1").contains(" 1 diagnostics in"));
+    assert!(diagnostics("5.2").contains(" 0 diagnostics in"));
+}
+
+#[test]
+fn settings_file_pins_the_lua_version() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("m.lua"), "local band = bit32.band\nreturn band\n").unwrap();
+    let args = ["--no-cache", "-o", "annotated_{}", "m.lua"];
+
+    let output = run(dir.path(), &args);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(" 0 diagnostics in"));
+
+    fs::write(dir.path().join(".lua_tools.toml"), "lua_version = \"5.1\"\n").unwrap();
+    let output = run(dir.path(), &args);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(" 1 diagnostics in"));
+}
+
+#[test]
+fn diff_shows_added_params_without_writing() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("m.lua"), SOURCE).unwrap();
+
+    let output = run(dir.path(), &["--diff", "m.lua"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\n+---@param a any"), "{}", stdout);
+    assert!(stdout.contains("\n+---@param b any"), "{}", stdout);
+    assert_eq!(entries(dir.path()), ["m.lua"]);
+}
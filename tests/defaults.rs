@@ -0,0 +1,19 @@
+//! The public types with a no-argument `new()` also implement `Default`.
+
+use lua_tools::annotator::Annotator;
+use lua_tools::frameworks::FrameworkRegistry;
+use lua_tools::project_context::ProjectContext;
+use lua_tools::type_inference::{ScopeContext, TypeAnalyzer};
+
+#[test]
+fn defaults_match_new() {
+    let context = ProjectContext::default();
+    assert_eq!(context.lua_version, ProjectContext::new().lua_version);
+    let annotator = Annotator::default();
+    assert!(annotator.preserve_existing);
+    let _registry = FrameworkRegistry::default();
+    let scope = ScopeContext::default();
+    assert!(scope.lookup("anything").is_none());
+    let analyzer = TypeAnalyzer::default();
+    assert!(analyzer.diagnostics.is_empty());
+}